@@ -0,0 +1,22 @@
+// Copyright (c) 2025 mysql_async contributors
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+/// Client-side information about a connection, e.g. for diagnostics.
+///
+/// Returned by [`crate::Conn::conn_info`].
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ConnInfo {
+    /// Connection identifier (see [`crate::Conn::id`]).
+    pub id: u32,
+    /// Label of the connection, if any (see [`crate::Conn::set_label`]).
+    pub label: Option<String>,
+    /// Server version (see [`crate::Conn::server_version`]).
+    pub server_version: (u16, u16, u16),
+    /// `true` if the connection belongs to a [`crate::Pool`].
+    pub pooled: bool,
+}
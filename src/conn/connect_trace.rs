@@ -0,0 +1,134 @@
+// Copyright (c) 2025 mysql_async contributors
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+use std::{
+    fmt,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+/// Outcome of a connection attempt reported via [`ConnectTrace`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConnectOutcome {
+    /// Connection was successfully established.
+    Connected,
+    /// Connection attempt failed. Contains the textual representation of the error
+    /// (the error itself is returned to the caller).
+    Failed(String),
+}
+
+impl ConnectOutcome {
+    /// Returns `true` if the connection was successfully established.
+    pub fn is_connected(&self) -> bool {
+        matches!(self, ConnectOutcome::Connected)
+    }
+}
+
+/// Timings of a single connection attempt.
+///
+/// Reported to the hook installed via [`crate::OptsBuilder::connect_trace_hook`]
+/// after each connection attempt, whether it succeeded or not. Phases that weren't
+/// reached (or aren't applicable, e.g. DNS for a socket connection, or TLS for
+/// a plain connection) are reported as `None`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConnectTrace {
+    /// Time spent resolving the host name.
+    pub dns: Option<Duration>,
+    /// Time spent establishing the transport (TCP or unix socket) connection.
+    pub tcp: Option<Duration>,
+    /// Time spent on the TLS upgrade.
+    pub tls: Option<Duration>,
+    /// Time spent on the protocol handshake and authentication.
+    pub handshake: Option<Duration>,
+    /// Total time of this connection attempt, including init and setup commands.
+    pub total: Duration,
+    /// Outcome of this connection attempt.
+    pub outcome: ConnectOutcome,
+}
+
+/// Accumulates phase timings while a connection is being established.
+#[derive(Debug)]
+pub(crate) struct ConnectTracer {
+    started: Instant,
+    phase_started: Instant,
+    pub(crate) dns: Option<Duration>,
+    pub(crate) tcp: Option<Duration>,
+    pub(crate) tls: Option<Duration>,
+    pub(crate) handshake: Option<Duration>,
+}
+
+impl ConnectTracer {
+    pub(crate) fn new() -> Self {
+        let now = Instant::now();
+        Self {
+            started: now,
+            phase_started: now,
+            dns: None,
+            tcp: None,
+            tls: None,
+            handshake: None,
+        }
+    }
+
+    /// Marks the beginning of a phase.
+    pub(crate) fn start_phase(&mut self) {
+        self.phase_started = Instant::now();
+    }
+
+    /// Returns the time elapsed since the beginning of the current phase.
+    pub(crate) fn phase_elapsed(&self) -> Duration {
+        self.phase_started.elapsed()
+    }
+
+    pub(crate) fn finish(self, outcome: ConnectOutcome) -> ConnectTrace {
+        ConnectTrace {
+            dns: self.dns,
+            tcp: self.tcp,
+            tls: self.tls,
+            handshake: self.handshake,
+            total: self.started.elapsed(),
+            outcome,
+        }
+    }
+}
+
+static HOOK_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// Wraps a connect trace hook, so that it could be stored within [`crate::Opts`].
+#[derive(Clone)]
+pub(crate) struct ConnectTraceHook(usize, Arc<dyn Fn(&ConnectTrace) + Send + Sync + 'static>);
+
+impl ConnectTraceHook {
+    pub(crate) fn new<T>(hook: T) -> Self
+    where
+        T: Fn(&ConnectTrace) + Send + Sync + 'static,
+    {
+        Self(HOOK_ID.fetch_add(1, Ordering::SeqCst), Arc::new(hook))
+    }
+
+    pub(crate) fn call(&self, trace: &ConnectTrace) {
+        (self.1)(trace)
+    }
+}
+
+impl PartialEq for ConnectTraceHook {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl Eq for ConnectTraceHook {}
+
+impl fmt::Debug for ConnectTraceHook {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("ConnectTraceHook").field(&"..").finish()
+    }
+}
@@ -0,0 +1,179 @@
+// Copyright (c) 2025 mysql_async contributors
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! String escaping that follows `mysql_real_escape_string` semantics.
+
+/// Multibyte structure of a character set (as far as escaping is concerned).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Multibyte {
+    /// Single-byte character set.
+    None,
+    /// `utf8mb3`/`utf8mb4`.
+    Utf8,
+    /// `big5`
+    Big5,
+    /// `gbk`
+    Gbk,
+    /// `sjis`, `cp932`
+    Sjis,
+    /// `gb18030`
+    Gb18030,
+}
+
+impl Multibyte {
+    fn from_charset(charset: &str) -> Self {
+        match charset {
+            "utf8" | "utf8mb3" | "utf8mb4" => Multibyte::Utf8,
+            "big5" => Multibyte::Big5,
+            "gbk" => Multibyte::Gbk,
+            "sjis" | "cp932" => Multibyte::Sjis,
+            "gb18030" => Multibyte::Gb18030,
+            _ => Multibyte::None,
+        }
+    }
+
+    /// Returns the length of a valid multibyte character at the beginning of `bytes`
+    /// or `0` if there is no such character.
+    fn ismbchar(self, bytes: &[u8]) -> usize {
+        let lead = bytes[0];
+        let trail = bytes.get(1).copied();
+        match self {
+            Multibyte::None => 0,
+            Multibyte::Utf8 => {
+                let len = self.mbcharlen(lead);
+                match bytes.get(..len) {
+                    Some(ch) if len > 1 && std::str::from_utf8(ch).is_ok() => len,
+                    _ => 0,
+                }
+            }
+            Multibyte::Big5 => match trail {
+                Some(0x40..=0x7E | 0xA1..=0xFE) if self.mbcharlen(lead) == 2 => 2,
+                _ => 0,
+            },
+            Multibyte::Gbk => match trail {
+                Some(0x40..=0x7E | 0x80..=0xFE) if self.mbcharlen(lead) == 2 => 2,
+                _ => 0,
+            },
+            Multibyte::Sjis => match trail {
+                Some(0x40..=0x7E | 0x80..=0xFC) if self.mbcharlen(lead) == 2 => 2,
+                _ => 0,
+            },
+            Multibyte::Gb18030 => match bytes {
+                [0x81..=0xFE, 0x40..=0x7E | 0x80..=0xFE, ..] => 2,
+                [0x81..=0xFE, 0x30..=0x39, 0x81..=0xFE, 0x30..=0x39, ..] => 4,
+                _ => 0,
+            },
+        }
+    }
+
+    /// Returns the apparent length of a character starting with the given byte.
+    fn mbcharlen(self, lead: u8) -> usize {
+        match (self, lead) {
+            (Multibyte::Utf8, 0xC2..=0xDF) => 2,
+            (Multibyte::Utf8, 0xE0..=0xEF) => 3,
+            (Multibyte::Utf8, 0xF0..=0xF4) => 4,
+            (Multibyte::Big5, 0xA1..=0xF9) => 2,
+            (Multibyte::Gbk | Multibyte::Gb18030, 0x81..=0xFE) => 2,
+            (Multibyte::Sjis, 0x81..=0x9F | 0xE0..=0xFC) => 2,
+            _ => 1,
+        }
+    }
+}
+
+/// Escapes `bytes` the way `mysql_real_escape_string` does for the given character set.
+///
+/// If `no_backslash_escapes` is `true` (`NO_BACKSLASH_ESCAPES` SQL mode), then only `'`
+/// is escaped (by doubling it). Otherwise special characters are escaped with a backslash.
+/// Multibyte characters are copied as is, and a lead byte of an invalid multibyte character
+/// is escaped, so that the server won't treat it as a part of a multibyte character.
+pub(crate) fn escape_bytes(bytes: &[u8], charset: &str, no_backslash_escapes: bool) -> Vec<u8> {
+    let multibyte = Multibyte::from_charset(charset);
+    let mut output = Vec::with_capacity(bytes.len() + bytes.len() / 8);
+
+    let mut i = 0;
+    while i < bytes.len() {
+        let len = multibyte.ismbchar(&bytes[i..]);
+        if len > 0 {
+            output.extend_from_slice(&bytes[i..i + len]);
+            i += len;
+            continue;
+        }
+
+        let byte = bytes[i];
+        i += 1;
+
+        if no_backslash_escapes {
+            if byte == b'\'' {
+                output.push(b'\'');
+            }
+            output.push(byte);
+            continue;
+        }
+
+        let escaped = if multibyte.mbcharlen(byte) > 1 {
+            Some(byte)
+        } else {
+            match byte {
+                b'\0' => Some(b'0'),
+                b'\n' => Some(b'n'),
+                b'\r' => Some(b'r'),
+                b'\\' => Some(b'\\'),
+                b'\'' => Some(b'\''),
+                b'"' => Some(b'"'),
+                0x1A => Some(b'Z'),
+                _ => None,
+            }
+        };
+
+        match escaped {
+            Some(escaped) => output.extend_from_slice(&[b'\\', escaped]),
+            None => output.push(byte),
+        }
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod test {
+    use super::escape_bytes;
+
+    #[test]
+    fn should_escape_bytes() {
+        let input = "a'b\"c\\d\0e\nf\rg\x1Ah — ключ 🦀".as_bytes();
+        let expected = "a\\'b\\\"c\\\\d\\0e\\nf\\rg\\Zh — ключ 🦀".as_bytes();
+        assert_eq!(escape_bytes(input, "utf8mb4", false), expected);
+        assert_eq!(escape_bytes(input, "latin1", false), expected);
+
+        let expected = "a''b\"c\\d\0e\nf\rg\x1Ah — ключ 🦀".as_bytes();
+        assert_eq!(escape_bytes(input, "utf8mb4", true), expected);
+
+        // Invalid UTF-8 lead byte followed by a quote.
+        assert_eq!(escape_bytes(b"\xC3'", "utf8mb4", false), b"\\\xC3\\'");
+    }
+
+    #[test]
+    fn should_escape_multibyte_charsets() {
+        // 0xBF5C is a valid GBK character, so a backslash must not be inserted after 0xBF.
+        assert_eq!(escape_bytes(b"\xBF\x5C'", "gbk", false), b"\xBF\x5C\\'");
+        // 0xBF27 is not a valid GBK character, so the lead byte is escaped.
+        assert_eq!(
+            escape_bytes(b"\xBF' OR 1=1", "gbk", false),
+            b"\\\xBF\\' OR 1=1"
+        );
+        assert_eq!(escape_bytes(b"\xBF' OR 1=1", "gbk", true), b"\xBF'' OR 1=1");
+
+        // 0x955C is a valid SJIS character.
+        assert_eq!(escape_bytes(b"\x95\x5C\\", "sjis", false), b"\x95\x5C\\\\");
+        assert_eq!(escape_bytes(b"\xA4\x5C'", "big5", false), b"\xA4\x5C\\'");
+        assert_eq!(
+            escape_bytes(b"\x81\x30\x81\x30'", "gb18030", false),
+            b"\x81\x30\x81\x30\\'"
+        );
+    }
+}
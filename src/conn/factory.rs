@@ -0,0 +1,194 @@
+// Copyright (c) 2025 mysql_async contributors
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+use futures_util::FutureExt;
+
+use crate::{BoxFuture, Conn, Opts, Pool};
+
+/// Something that is able to open connections.
+///
+/// Code that takes a `ConnectionFactory` instead of a concrete [`Opts`] or [`Pool`]
+/// could be given a fake factory in tests.
+///
+/// Implemented for [`Opts`] (opens a new connection every time) and for [`Pool`]
+/// (takes a connection from the pool).
+///
+/// # Testing pattern
+///
+/// [`Conn`] talks to a real server, so it can't be faked. The factory yields
+/// [`ConnectionFactory::Connection`] instead, so that data-access code generic over
+/// the factory could be tested against a fake connection type. Usually this means
+/// that the application defines its own data-access trait, implements it for [`Conn`]
+/// (using [`Queryable`](crate::prelude::Queryable)) and for a fake:
+///
+/// ```
+/// # use mysql_async::{prelude::*, test_misc::get_opts, Conn, Opts, Result};
+/// use futures_util::future::{BoxFuture, FutureExt};
+///
+/// trait UserStore: Send {
+///     fn user_name(&mut self, id: u64) -> BoxFuture<'_, Result<Option<String>>>;
+/// }
+///
+/// impl UserStore for Conn {
+///     fn user_name(&mut self, id: u64) -> BoxFuture<'_, Result<Option<String>>> {
+///         async move { self.exec_first("SELECT CONCAT('user', ?)", (id,)).await }.boxed()
+///     }
+/// }
+///
+/// async fn greet<F>(factory: &F, id: u64) -> Result<String>
+/// where
+///     F: ConnectionFactory,
+///     F::Connection: UserStore,
+/// {
+///     let mut conn = factory.connect().await?;
+///     let name = conn.user_name(id).await?;
+///     Ok(format!("Hello, {}!", name.as_deref().unwrap_or("stranger")))
+/// }
+///
+/// struct FakeStore;
+///
+/// impl UserStore for FakeStore {
+///     fn user_name(&mut self, _id: u64) -> BoxFuture<'_, Result<Option<String>>> {
+///         async move { Ok(None) }.boxed()
+///     }
+/// }
+///
+/// struct FakeFactory;
+///
+/// impl ConnectionFactory for FakeFactory {
+///     type Connection = FakeStore;
+///
+///     fn connect(&self) -> BoxFuture<'_, Result<FakeStore>> {
+///         async move { Ok(FakeStore) }.boxed()
+///     }
+/// }
+///
+/// # #[tokio::main]
+/// # async fn main() -> Result<()> {
+/// let opts = Opts::from(get_opts());
+/// assert_eq!(greet(&opts, 42).await?, "Hello, user42!");
+/// assert_eq!(greet(&FakeFactory, 42).await?, "Hello, stranger!");
+/// # Ok(()) }
+/// ```
+pub trait ConnectionFactory: Send + Sync {
+    /// Connection type produced by this factory.
+    type Connection: Send;
+
+    /// Opens a new connection.
+    fn connect(&self) -> BoxFuture<'_, Self::Connection>;
+}
+
+impl ConnectionFactory for Opts {
+    type Connection = Conn;
+
+    fn connect(&self) -> BoxFuture<'_, Conn> {
+        Conn::new(self.clone()).boxed()
+    }
+}
+
+impl ConnectionFactory for Pool {
+    type Connection = Conn;
+
+    fn connect(&self) -> BoxFuture<'_, Conn> {
+        self.get_conn().boxed()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use futures_util::FutureExt;
+
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    use super::ConnectionFactory;
+    use crate::{prelude::*, test_misc::get_opts, BoxFuture, Conn, DriverError, Error, Opts, Pool};
+
+    /// Data-access trait of an imaginary application.
+    trait Greeter: Send {
+        fn greeting(&mut self) -> BoxFuture<'_, String>;
+    }
+
+    impl Greeter for Conn {
+        fn greeting(&mut self) -> BoxFuture<'_, String> {
+            async move { Ok(self.query_first("SELECT 'hello'").await?.unwrap()) }.boxed()
+        }
+    }
+
+    async fn greet_twice<F>(factory: &F) -> crate::Result<Vec<String>>
+    where
+        F: ConnectionFactory,
+        F::Connection: Greeter,
+    {
+        let mut output = Vec::new();
+        for _ in 0..2 {
+            let mut conn = factory.connect().await?;
+            output.push(conn.greeting().await?);
+        }
+        Ok(output)
+    }
+
+    struct FakeConnection(&'static str);
+
+    impl Greeter for FakeConnection {
+        fn greeting(&mut self) -> BoxFuture<'_, String> {
+            let greeting = self.0.to_owned();
+            async move { Ok(greeting) }.boxed()
+        }
+    }
+
+    #[derive(Default)]
+    struct FakeFactory {
+        connects: AtomicUsize,
+    }
+
+    impl ConnectionFactory for FakeFactory {
+        type Connection = FakeConnection;
+
+        fn connect(&self) -> BoxFuture<'_, FakeConnection> {
+            let n = self.connects.fetch_add(1, Ordering::SeqCst);
+            async move {
+                match n {
+                    0 => Ok(FakeConnection("canned")),
+                    _ => Err(Error::Driver(DriverError::PoolDisconnected)),
+                }
+            }
+            .boxed()
+        }
+    }
+
+    #[tokio::test]
+    async fn should_use_fake_connection_factory() {
+        let factory = FakeFactory::default();
+        let err = greet_twice(&factory).await.unwrap_err();
+        assert!(matches!(err, Error::Driver(DriverError::PoolDisconnected)));
+        assert_eq!(factory.connects.load(Ordering::SeqCst), 2);
+
+        let factory = FakeFactory::default();
+        let mut conn = factory.connect().await.unwrap();
+        assert_eq!(conn.greeting().await.unwrap(), "canned");
+    }
+
+    #[tokio::test]
+    async fn should_connect_using_opts_and_pool() -> crate::Result<()> {
+        let opts = Opts::from(get_opts());
+        assert_eq!(greet_twice(&opts).await?, vec!["hello", "hello"]);
+
+        let pool = Pool::new(get_opts());
+        assert_eq!(greet_twice(&pool).await?, vec!["hello", "hello"]);
+
+        let factory: Arc<dyn ConnectionFactory<Connection = Conn>> = Arc::new(pool.clone());
+        factory.connect().await?.ping().await?;
+        drop(factory);
+
+        pool.disconnect().await?;
+        Ok(())
+    }
+}
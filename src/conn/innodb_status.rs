@@ -0,0 +1,276 @@
+// Copyright (c) 2025 mysql_async contributors
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+/// Header of the deadlock section of `SHOW ENGINE INNODB STATUS`.
+const DEADLOCK_SECTION: &str = "LATEST DETECTED DEADLOCK";
+
+/// The latest deadlock detected by InnoDB.
+///
+/// Parsed from the `LATEST DETECTED DEADLOCK` section of `SHOW ENGINE INNODB STATUS`
+/// (see [`crate::Conn::innodb_status`]). The format of this section isn't documented and varies
+/// between server versions, so parsing is best-effort: fields that couldn't be found
+/// are left empty.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct InnodbDeadlock {
+    /// The time the deadlock was detected, as printed by the server
+    /// (e.g. `2025-01-15 10:23:45`).
+    pub detected_at: Option<String>,
+    /// Transactions involved in the deadlock.
+    pub transactions: Vec<DeadlockTransaction>,
+    /// Number of the transaction that was rolled back (see [`DeadlockTransaction::number`]).
+    pub rolled_back: Option<u32>,
+}
+
+/// A transaction involved in an [`InnodbDeadlock`].
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct DeadlockTransaction {
+    /// Number of the transaction within the deadlock report (e.g. `1` for `*** (1) TRANSACTION`).
+    pub number: u32,
+    /// InnoDB transaction identifier.
+    pub trx_id: Option<u64>,
+    /// Connection identifier (`MySQL thread id`, see [`crate::Conn::id`]).
+    pub thread_id: Option<u64>,
+    /// The statement the transaction was executing, if reported.
+    pub query: Option<String>,
+    /// Locks held by the transaction (e.g. `RECORD LOCKS space id 2 page no 4 ...`).
+    pub holds_locks: Vec<String>,
+    /// Locks the transaction was waiting for.
+    pub waits_for: Vec<String>,
+}
+
+/// Part of a transaction report that is being parsed.
+enum TrxPart {
+    Header,
+    Query,
+    HoldsLocks,
+    WaitsFor,
+}
+
+impl InnodbDeadlock {
+    /// Parses the `LATEST DETECTED DEADLOCK` section of the given `SHOW ENGINE INNODB STATUS`
+    /// output.
+    ///
+    /// Returns `None` if there is no such section, i.e. if no deadlock was detected since
+    /// the server start.
+    ///
+    /// ```
+    /// # use mysql_async::{test_misc::get_opts, Conn, InnodbDeadlock};
+    /// # #[tokio::main]
+    /// # async fn main() -> mysql_async::Result<()> {
+    /// # let mut conn = Conn::new(get_opts()).await?;
+    /// let status = conn.innodb_status().await?;
+    /// if let Some(deadlock) = InnodbDeadlock::parse(&status) {
+    ///     for trx in &deadlock.transactions {
+    ///         println!("thread {:?}: {:?}", trx.thread_id, trx.query);
+    ///     }
+    /// }
+    /// # conn.disconnect().await }
+    /// ```
+    pub fn parse(status: &str) -> Option<Self> {
+        let mut lines = status
+            .lines()
+            .skip_while(|line| line.trim() != DEADLOCK_SECTION);
+        lines.next()?;
+
+        let mut deadlock = InnodbDeadlock::default();
+        let mut part = TrxPart::Header;
+        let mut query = Vec::new();
+
+        // the section is underlined and ends where the next (overlined) section begins
+        for line in lines.skip_while(|line| is_separator(line)) {
+            if is_separator(line) {
+                break;
+            }
+
+            if let Some(marker) = line.strip_prefix("*** ") {
+                if let Some(trx) = deadlock.transactions.last_mut() {
+                    finish_query(trx, &mut query);
+                }
+                if let Some(number) = marker.strip_prefix("WE ROLL BACK TRANSACTION ") {
+                    deadlock.rolled_back = parse_number(number);
+                    continue;
+                }
+                let (number, rest) = match parse_marker(marker) {
+                    Some(marker) => marker,
+                    None => continue,
+                };
+                if rest.starts_with("TRANSACTION") {
+                    deadlock.transactions.push(DeadlockTransaction {
+                        number,
+                        ..DeadlockTransaction::default()
+                    });
+                    part = TrxPart::Header;
+                } else if rest.starts_with("HOLDS THE LOCK") {
+                    part = TrxPart::HoldsLocks;
+                } else if rest.starts_with("WAITING FOR THIS LOCK") {
+                    part = TrxPart::WaitsFor;
+                }
+                continue;
+            }
+
+            let trx = match deadlock.transactions.last_mut() {
+                Some(trx) => trx,
+                None => {
+                    if deadlock.detected_at.is_none() && !line.trim().is_empty() {
+                        let mut words = line.split_whitespace();
+                        deadlock.detected_at = match (words.next(), words.next()) {
+                            (Some(date), Some(time)) => Some(format!("{} {}", date, time)),
+                            _ => None,
+                        };
+                    }
+                    continue;
+                }
+            };
+
+            match part {
+                TrxPart::Header => {
+                    if let Some(rest) = line.strip_prefix("TRANSACTION ") {
+                        trx.trx_id = parse_number(rest);
+                    } else if let Some(rest) = line.strip_prefix("MySQL thread id ") {
+                        trx.thread_id = parse_number(rest);
+                        // the statement follows the thread description
+                        part = TrxPart::Query;
+                    }
+                }
+                TrxPart::Query => query.push(line),
+                TrxPart::HoldsLocks | TrxPart::WaitsFor => {
+                    if line.starts_with("RECORD LOCKS ") || line.starts_with("TABLE LOCK ") {
+                        let locks = match part {
+                            TrxPart::HoldsLocks => &mut trx.holds_locks,
+                            _ => &mut trx.waits_for,
+                        };
+                        locks.push(line.trim().to_owned());
+                    }
+                }
+            }
+        }
+
+        if let Some(trx) = deadlock.transactions.last_mut() {
+            finish_query(trx, &mut query);
+        }
+
+        Some(deadlock)
+    }
+}
+
+/// Returns `true` if the given line underlines (or overlines) a section header.
+fn is_separator(line: &str) -> bool {
+    let line = line.trim();
+    !line.is_empty() && line.bytes().all(|x| x == b'-')
+}
+
+/// Parses `(N) rest` of a `*** (N) ...` line.
+fn parse_marker(marker: &str) -> Option<(u32, &str)> {
+    let (number, rest) = marker.strip_prefix('(')?.split_once(')')?;
+    Some((number.parse().ok()?, rest.trim_start()))
+}
+
+/// Parses the leading number of the given string, ignoring an opening parenthesis.
+fn parse_number<T: std::str::FromStr>(s: &str) -> Option<T> {
+    let s = s.trim_start_matches('(');
+    let end = s.find(|x: char| !x.is_ascii_digit()).unwrap_or(s.len());
+    s[..end].parse().ok()
+}
+
+/// Stores the collected statement lines (if any) into the given transaction.
+fn finish_query(trx: &mut DeadlockTransaction, query: &mut Vec<&str>) {
+    if !query.is_empty() {
+        let text = query.join("\n").trim().to_owned();
+        if !text.is_empty() {
+            trx.query = Some(text);
+        }
+        query.clear();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{DeadlockTransaction, InnodbDeadlock};
+
+    const STATUS: &str = "
+=====================================
+2025-01-15 10:24:00 0x7f8b1c0e6700 INNODB MONITOR OUTPUT
+=====================================
+Per second averages calculated from the last 5 seconds
+------------------------
+LATEST DETECTED DEADLOCK
+------------------------
+2025-01-15 10:23:45 0x7f8b1c0e6700
+*** (1) TRANSACTION:
+TRANSACTION 12345, ACTIVE 5 sec starting index read
+mysql tables in use 1, locked 1
+LOCK WAIT 3 lock struct(s), heap size 1136, 2 row lock(s)
+MySQL thread id 10, OS thread handle 140236, query id 100 localhost root updating
+UPDATE t SET x = 1
+WHERE id = 2
+*** (1) HOLDS THE LOCK(S):
+RECORD LOCKS space id 2 page no 4 n bits 72 index PRIMARY of table `test`.`t` trx id 12345 lock_mode X locks rec but not gap
+Record lock, heap no 2 PHYSICAL RECORD: n_fields 3; compact format; info bits 0
+ 0: len 4; hex 80000001; asc     ;;
+
+*** (1) WAITING FOR THIS LOCK TO BE GRANTED:
+RECORD LOCKS space id 2 page no 4 n bits 72 index PRIMARY of table `test`.`t` trx id 12345 lock_mode X locks rec but not gap waiting
+Record lock, heap no 3 PHYSICAL RECORD: n_fields 3; compact format; info bits 0
+
+*** (2) TRANSACTION:
+TRANSACTION 12346, ACTIVE 3 sec starting index read
+mysql tables in use 1, locked 1
+MySQL thread id 11, OS thread handle 140237, query id 101 localhost root updating
+UPDATE t SET x = 2 WHERE id = 1
+*** (2) HOLDS THE LOCK(S):
+RECORD LOCKS space id 2 page no 4 n bits 72 index PRIMARY of table `test`.`t` trx id 12346 lock_mode X locks rec but not gap
+*** (2) WAITING FOR THIS LOCK TO BE GRANTED:
+RECORD LOCKS space id 2 page no 4 n bits 72 index PRIMARY of table `test`.`t` trx id 12346 lock_mode X locks rec but not gap waiting
+*** WE ROLL BACK TRANSACTION (2)
+------------
+TRANSACTIONS
+------------
+Trx id counter 12350
+";
+
+    #[test]
+    fn should_parse_latest_deadlock() {
+        let deadlock = InnodbDeadlock::parse(STATUS).unwrap();
+        assert_eq!(deadlock.detected_at.as_deref(), Some("2025-01-15 10:23:45"));
+        assert_eq!(deadlock.rolled_back, Some(2));
+        assert_eq!(
+            deadlock.transactions,
+            vec![
+                DeadlockTransaction {
+                    number: 1,
+                    trx_id: Some(12345),
+                    thread_id: Some(10),
+                    query: Some("UPDATE t SET x = 1\nWHERE id = 2".into()),
+                    holds_locks: vec!["RECORD LOCKS space id 2 page no 4 n bits 72 index PRIMARY \
+                        of table `test`.`t` trx id 12345 lock_mode X locks rec but not gap"
+                        .into()],
+                    waits_for: vec!["RECORD LOCKS space id 2 page no 4 n bits 72 index PRIMARY \
+                        of table `test`.`t` trx id 12345 lock_mode X locks rec but not gap \
+                        waiting"
+                        .into()],
+                },
+                DeadlockTransaction {
+                    number: 2,
+                    trx_id: Some(12346),
+                    thread_id: Some(11),
+                    query: Some("UPDATE t SET x = 2 WHERE id = 1".into()),
+                    holds_locks: vec!["RECORD LOCKS space id 2 page no 4 n bits 72 index PRIMARY \
+                        of table `test`.`t` trx id 12346 lock_mode X locks rec but not gap"
+                        .into()],
+                    waits_for: vec!["RECORD LOCKS space id 2 page no 4 n bits 72 index PRIMARY \
+                        of table `test`.`t` trx id 12346 lock_mode X locks rec but not gap \
+                        waiting"
+                        .into()],
+                },
+            ]
+        );
+
+        let no_deadlock = "------------\nTRANSACTIONS\n------------\nTrx id counter 12350\n";
+        assert_eq!(InnodbDeadlock::parse(no_deadlock), None);
+    }
+}
@@ -0,0 +1,224 @@
+// Copyright (c) 2025 mysql_async contributors
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+use bytes::{BufMut, Bytes, BytesMut};
+use mysql_common::{params::Params, value::Value};
+
+use std::io;
+
+/// Size of a chunk of `LOAD DATA` payload sent to the server.
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// Name of a file requested by a `LOAD DATA` query issued by [`crate::Conn::load_data`].
+pub(crate) const LOAD_DATA_FILE_NAME: &str = "mysql_async_load_data";
+
+/// Result of a [`crate::Conn::load_data`] call.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct LoadDataResult {
+    /// Number of inserted rows.
+    pub affected_rows: u64,
+    /// Number of warnings produced by the `LOAD DATA` statement.
+    pub warnings: u16,
+}
+
+/// Builds the `LOAD DATA` statement for [`crate::Conn::load_data`].
+///
+/// The statement uses the default field (`\t`) and line (`\n`) terminators
+/// and the default escape character (`\`), so that `write_row` is able to escape any value.
+///
+/// `table` is split into the database and the table name at the first `.`.
+pub(crate) fn load_data_query(table: &str, columns: &[&str]) -> String {
+    let table = table
+        .splitn(2, '.')
+        .map(quote_identifier)
+        .collect::<Vec<_>>()
+        .join(".");
+    let mut query = format!(
+        "LOAD DATA LOCAL INFILE '{}' INTO TABLE {} CHARACTER SET binary",
+        LOAD_DATA_FILE_NAME, table
+    );
+
+    if !columns.is_empty() {
+        let columns = columns
+            .iter()
+            .map(|column| quote_identifier(column))
+            .collect::<Vec<_>>()
+            .join(", ");
+        query.push_str(" (");
+        query.push_str(&columns);
+        query.push(')');
+    }
+
+    query
+}
+
+/// Quotes an identifier with backticks.
+fn quote_identifier(identifier: &str) -> String {
+    format!("`{}`", identifier.replace('`', "``"))
+}
+
+/// Turns an iterator of rows into a stream of `LOAD DATA` payload chunks.
+pub(crate) fn load_data_chunks<I>(rows: I) -> impl Iterator<Item = io::Result<Bytes>>
+where
+    I: Iterator,
+    I::Item: Into<Params>,
+{
+    let mut rows = rows.peekable();
+    std::iter::from_fn(move || {
+        rows.peek()?;
+
+        let mut chunk = BytesMut::with_capacity(CHUNK_SIZE);
+        while chunk.len() < CHUNK_SIZE {
+            let row = match rows.next() {
+                Some(row) => row,
+                None => break,
+            };
+
+            let result = match row.into() {
+                Params::Positional(values) => write_row(&mut chunk, values),
+                Params::Empty => write_row(&mut chunk, Vec::new()),
+                Params::Named(_) => Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "named parameters are not supported for LOAD DATA rows",
+                )),
+            };
+            if let Err(err) = result {
+                return Some(Err(err));
+            }
+        }
+
+        Some(Ok(chunk.freeze()))
+    })
+}
+
+/// Writes a row in the default `LOAD DATA` format.
+fn write_row(buf: &mut BytesMut, values: Vec<Value>) -> io::Result<()> {
+    for (i, value) in values.into_iter().enumerate() {
+        if i > 0 {
+            buf.put_u8(b'\t');
+        }
+        write_value(buf, value)?;
+    }
+    buf.put_u8(b'\n');
+    Ok(())
+}
+
+/// Writes a value escaping special characters.
+///
+/// Fails on NaN and infinite floats, that have no representation in SQL.
+fn write_value(buf: &mut BytesMut, value: Value) -> io::Result<()> {
+    let finite = match value {
+        Value::Float(x) => x.is_finite(),
+        Value::Double(x) => x.is_finite(),
+        _ => true,
+    };
+    if !finite {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("can't load a non-finite float value {:?}", value),
+        ));
+    }
+
+    match value {
+        Value::NULL => buf.put_slice(b"\\N"),
+        Value::Bytes(bytes) => {
+            for byte in bytes {
+                match byte {
+                    b'\\' => buf.put_slice(b"\\\\"),
+                    b'\t' => buf.put_slice(b"\\t"),
+                    b'\n' => buf.put_slice(b"\\n"),
+                    b'\r' => buf.put_slice(b"\\r"),
+                    b'\0' => buf.put_slice(b"\\0"),
+                    byte => buf.put_u8(byte),
+                }
+            }
+        }
+        Value::Int(x) => buf.put_slice(x.to_string().as_bytes()),
+        Value::UInt(x) => buf.put_slice(x.to_string().as_bytes()),
+        Value::Float(x) => buf.put_slice(x.to_string().as_bytes()),
+        Value::Double(x) => buf.put_slice(x.to_string().as_bytes()),
+        Value::Date(y, m, d, h, i, s, us) => buf.put_slice(
+            format!(
+                "{:04}-{:02}-{:02} {:02}:{:02}:{:02}.{:06}",
+                y, m, d, h, i, s, us
+            )
+            .as_bytes(),
+        ),
+        Value::Time(neg, d, h, i, s, us) => buf.put_slice(
+            format!(
+                "{}{:02}:{:02}:{:02}.{:06}",
+                if neg { "-" } else { "" },
+                u64::from(d) * 24 + u64::from(h),
+                i,
+                s,
+                us
+            )
+            .as_bytes(),
+        ),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::{load_data_chunks, load_data_query};
+    use crate::Value;
+
+    #[test]
+    fn should_escape_load_data_values() {
+        let rows = vec![
+            (1, Some("plain")),
+            (2, None),
+            (3, Some("tab\there")),
+            (4, Some("line\nbreak\r\n")),
+            (5, Some("back\\slash \\N")),
+        ];
+
+        let chunks = load_data_chunks(rows.into_iter())
+            .collect::<std::io::Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(chunks.len(), 1);
+        assert_eq!(
+            &chunks[0][..],
+            &b"1\tplain\n2\t\\N\n3\ttab\\there\n4\tline\\nbreak\\r\\n\n5\tback\\\\slash \\\\N\n"[..]
+        );
+
+        let chunks = load_data_chunks(
+            vec![(
+                Value::Date(2020, 1, 2, 3, 4, 5, 6),
+                Value::Time(true, 1, 2, 3, 4, 5),
+            )]
+            .into_iter(),
+        )
+        .collect::<std::io::Result<Vec<_>>>()
+        .unwrap();
+        assert_eq!(
+            &chunks[0][..],
+            &b"2020-01-02 03:04:05.000006\t-26:03:04.000005\n"[..]
+        );
+
+        assert_eq!(
+            load_data_query("db.tbl", &["a", "b`c"]),
+            "LOAD DATA LOCAL INFILE 'mysql_async_load_data' INTO TABLE `db`.`tbl` \
+             CHARACTER SET binary (`a`, `b``c`)"
+        );
+        assert_eq!(
+            load_data_query("t`bl; DROP TABLE x", &[]),
+            "LOAD DATA LOCAL INFILE 'mysql_async_load_data' INTO TABLE `t``bl; DROP TABLE x` \
+             CHARACTER SET binary"
+        );
+
+        for value in [f64::NAN, f64::INFINITY, f64::NEG_INFINITY] {
+            let mut chunks = load_data_chunks(vec![(1, value)].into_iter());
+            let err = chunks.next().unwrap().unwrap_err();
+            assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+        }
+        let mut chunks = load_data_chunks(vec![(Value::Float(f32::NAN),)].into_iter());
+        assert!(chunks.next().unwrap().is_err());
+    }
+}
@@ -0,0 +1,275 @@
+// Copyright (c) 2025 mysql_async contributors
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Detection of `LOAD DATA LOCAL INFILE` statements (see [`crate::Opts::strict_local_infile`]).
+//!
+//! The same tokenizer is used to count `?` placeholders of SQL-level prepared statements
+//! (see [`crate::Opts::sql_prepared_statements`]).
+
+/// A token of an SQL query (as far as `LOAD DATA` detection is concerned).
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token<'a> {
+    /// A keyword or an unquoted identifier.
+    Word(&'a [u8]),
+    /// Unescaped value of a string literal.
+    Str(Vec<u8>),
+    /// A `?` placeholder.
+    Placeholder,
+    /// Anything else.
+    Other,
+}
+
+/// Returns names of files requested by `LOAD DATA LOCAL INFILE` (or `LOAD XML LOCAL INFILE`)
+/// statements of the given query, in order.
+///
+/// Names are unescaped the way the server does it, so they are expected to match the names
+/// of LOCAL INFILE requests.
+pub(crate) fn local_infile_names(query: &[u8], no_backslash_escapes: bool) -> Vec<Vec<u8>> {
+    let tokens = tokenize(query, no_backslash_escapes);
+    let mut names = Vec::new();
+
+    for (i, token) in tokens.iter().enumerate() {
+        if !is_word(token, &["LOAD"]) {
+            continue;
+        }
+
+        let mut rest = tokens[i + 1..].iter().peekable();
+        if !rest.next().is_some_and(|x| is_word(x, &["DATA", "XML"])) {
+            continue;
+        }
+        if rest
+            .peek()
+            .is_some_and(|x| is_word(x, &["LOW_PRIORITY", "CONCURRENT"]))
+        {
+            rest.next();
+        }
+        if !rest.next().is_some_and(|x| is_word(x, &["LOCAL"]))
+            || !rest.next().is_some_and(|x| is_word(x, &["INFILE"]))
+        {
+            continue;
+        }
+        if let Some(Token::Str(name)) = rest.next() {
+            names.push(name.clone());
+        }
+    }
+
+    names
+}
+
+/// Returns the number of `?` placeholders of the given query.
+///
+/// Question marks within string literals, quoted identifiers and comments are ignored.
+pub(crate) fn placeholder_count(query: &[u8], no_backslash_escapes: bool) -> usize {
+    tokenize(query, no_backslash_escapes)
+        .into_iter()
+        .filter(|x| *x == Token::Placeholder)
+        .count()
+}
+
+fn is_word(token: &Token<'_>, words: &[&str]) -> bool {
+    match token {
+        Token::Word(word) => words
+            .iter()
+            .any(|x| x.as_bytes().eq_ignore_ascii_case(word)),
+        _ => false,
+    }
+}
+
+fn tokenize(query: &[u8], no_backslash_escapes: bool) -> Vec<Token<'_>> {
+    let mut tokens = Vec::new();
+
+    let mut i = 0;
+    while i < query.len() {
+        match query[i] {
+            x if x.is_ascii_whitespace() => i += 1,
+            b'#' => i = skip_line(query, i),
+            b'-' if is_dash_comment(&query[i..]) => i = skip_line(query, i),
+            b'/' if query.get(i + 1) == Some(&b'*') => {
+                if query.get(i + 2) == Some(&b'!') {
+                    // Executable comment. Its content is a part of the query.
+                    i += 3;
+                    while query.get(i).is_some_and(u8::is_ascii_digit) {
+                        i += 1;
+                    }
+                } else {
+                    i = find(query, i + 2, b"*/").map_or(query.len(), |end| end + 2);
+                }
+            }
+            b'*' if query.get(i + 1) == Some(&b'/') => i += 2,
+            quote @ (b'\'' | b'"') => {
+                let (value, end) = unquote(query, i, quote, no_backslash_escapes);
+                tokens.push(Token::Str(value));
+                i = end;
+            }
+            b'`' => {
+                let (_, end) = unquote(query, i, b'`', true);
+                tokens.push(Token::Other);
+                i = end;
+            }
+            b'?' => {
+                tokens.push(Token::Placeholder);
+                i += 1;
+            }
+            x if is_word_byte(x) => {
+                let start = i;
+                while query.get(i).copied().is_some_and(is_word_byte) {
+                    i += 1;
+                }
+                tokens.push(Token::Word(&query[start..i]));
+            }
+            _ => {
+                tokens.push(Token::Other);
+                i += 1;
+            }
+        }
+    }
+
+    tokens
+}
+
+fn is_word_byte(x: u8) -> bool {
+    x.is_ascii_alphanumeric() || x == b'_' || x == b'$' || x >= 0x80
+}
+
+/// `--` must be followed by a whitespace or a control character to start a comment.
+fn is_dash_comment(query: &[u8]) -> bool {
+    match query {
+        [b'-', b'-'] => true,
+        [b'-', b'-', x, ..] => *x <= b' ',
+        _ => false,
+    }
+}
+
+/// Returns the position right after the end of the line that contains `i`.
+fn skip_line(query: &[u8], i: usize) -> usize {
+    find(query, i, b"\n").map_or(query.len(), |end| end + 1)
+}
+
+fn find(query: &[u8], from: usize, needle: &[u8]) -> Option<usize> {
+    query[from..]
+        .windows(needle.len())
+        .position(|x| x == needle)
+        .map(|x| x + from)
+}
+
+/// Unescapes a quoted literal that starts at `start`.
+///
+/// Returns the value and the position right after the closing quote.
+fn unquote(query: &[u8], start: usize, quote: u8, no_backslash_escapes: bool) -> (Vec<u8>, usize) {
+    let mut value = Vec::new();
+
+    let mut i = start + 1;
+    while i < query.len() {
+        match query[i] {
+            x if x == quote => {
+                if query.get(i + 1) == Some(&quote) {
+                    value.push(quote);
+                    i += 2;
+                } else {
+                    return (value, i + 1);
+                }
+            }
+            b'\\' if !no_backslash_escapes && i + 1 < query.len() => {
+                match query[i + 1] {
+                    b'0' => value.push(b'\0'),
+                    b'b' => value.push(0x08),
+                    b'n' => value.push(b'\n'),
+                    b'r' => value.push(b'\r'),
+                    b't' => value.push(b'\t'),
+                    b'Z' => value.push(0x1A),
+                    // `\%` and `\_` are kept as is (see "String Literals" in the MySQL manual)
+                    x @ (b'%' | b'_') => value.extend_from_slice(&[b'\\', x]),
+                    x => value.push(x),
+                }
+                i += 2;
+            }
+            x => {
+                value.push(x);
+                i += 1;
+            }
+        }
+    }
+
+    (value, query.len())
+}
+
+#[cfg(test)]
+mod test {
+    use super::{local_infile_names, placeholder_count};
+
+    fn names(query: &str) -> Vec<String> {
+        local_infile_names(query.as_bytes(), false)
+            .into_iter()
+            .map(|x| String::from_utf8(x).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn should_detect_local_infile_statements() {
+        assert_eq!(
+            names("LOAD DATA LOCAL INFILE '/tmp/foo.csv' INTO TABLE foo"),
+            ["/tmp/foo.csv"]
+        );
+        assert_eq!(
+            names(r#"load data low_priority local infile "bar" into table t"#),
+            ["bar"]
+        );
+        assert_eq!(
+            names("LOAD XML CONCURRENT LOCAL INFILE 'a.xml' INTO TABLE t ROWS IDENTIFIED BY '<r>'"),
+            ["a.xml"]
+        );
+        assert_eq!(
+            names("SELECT 1; LOAD DATA LOCAL INFILE 'a' INTO TABLE t; LOAD DATA LOCAL INFILE 'b' INTO TABLE t"),
+            ["a", "b"]
+        );
+        assert_eq!(
+            names("LOAD /* comment */ DATA\n-- comment\nLOCAL # comment\n INFILE 'a' INTO TABLE t"),
+            ["a"]
+        );
+        assert_eq!(
+            names("/*!50000 LOAD DATA LOCAL INFILE 'a' */ INTO TABLE t"),
+            ["a"]
+        );
+    }
+
+    #[test]
+    fn should_unescape_file_names() {
+        assert_eq!(
+            names(r#"LOAD DATA LOCAL INFILE 'it''s \'a\' \"b\" c:\\d\%' INTO TABLE t"#),
+            [r#"it's 'a' "b" c:\d\%"#]
+        );
+        assert_eq!(
+            local_infile_names(br"LOAD DATA LOCAL INFILE 'c:\d''e' INTO TABLE t", true),
+            [br"c:\d'e".to_vec()]
+        );
+    }
+
+    #[test]
+    fn should_ignore_other_statements() {
+        assert!(names("SELECT 'LOAD DATA LOCAL INFILE ''a'' INTO TABLE t'").is_empty());
+        assert!(names("SELECT `LOAD DATA LOCAL INFILE 'a' INTO TABLE t`").is_empty());
+        assert!(names("/* LOAD DATA LOCAL INFILE 'a' */ SELECT 1").is_empty());
+        assert!(names("-- LOAD DATA LOCAL INFILE 'a'\nSELECT 1").is_empty());
+        assert!(names("LOAD DATA INFILE '/var/lib/mysql-files/a' INTO TABLE t").is_empty());
+        assert!(names("SELECT * FROM t INTO OUTFILE 'a'").is_empty());
+        assert!(names("SELECT load, data, local, infile FROM t").is_empty());
+    }
+
+    #[test]
+    fn should_count_placeholders() {
+        assert_eq!(placeholder_count(b"SELECT 1", false), 0);
+        assert_eq!(placeholder_count(b"SELECT ?, ?+?", false), 3);
+        assert_eq!(
+            placeholder_count(b"SELECT '?', \"?\", `?`, ? /* ? */ # ?\n-- ?\n, ?", false),
+            2
+        );
+        assert_eq!(placeholder_count(b"SELECT /*!50000 ? */", false), 1);
+        assert_eq!(placeholder_count(br"SELECT 'a\' ?', ?", false), 1);
+        assert_eq!(placeholder_count(br"SELECT 'a\'', ?", true), 0);
+    }
+}
@@ -7,6 +7,7 @@
 // modified, or distributed except according to those terms.
 
 use futures_util::FutureExt;
+use tokio::io::{AsyncRead, AsyncWrite};
 
 use mysql_common::{
     constants::{SessionStateType, DEFAULT_MAX_ALLOWED_PACKET, UTF8MB4_GENERAL_CI, UTF8_GENERAL_CI},
@@ -23,6 +24,7 @@ use mysql_common::{
 
 use std::{
     borrow::Cow,
+    collections::HashMap,
     fmt,
     future::Future,
     mem::{self, replace},
@@ -34,11 +36,26 @@ use std::{
 
 use crate::{
     buffer_pool::PooledBuf,
-    conn::{pool::Pool, stmt_cache::StmtCache},
+    conn::{
+        conn_info::ConnInfo,
+        connect_trace::{ConnectOutcome, ConnectTracer},
+        load_data::{load_data_chunks, load_data_query, LoadDataResult},
+        pool::Pool,
+        process_list::ProcessInfo,
+        query_cache::{QueryCache, QueryCacheKey},
+        replica_status::ReplicaStatus,
+        server_edition::ServerEdition,
+        stmt_cache::StmtCache,
+        table_columns::{ColumnDef, TableColumnsRow, TABLE_COLUMNS_QUERY},
+        table_maintenance::{
+            table_maintenance_query, TableMaintenanceMessage, TableMaintenanceRow,
+        },
+    },
     consts::{CapabilityFlags, Command, StatusFlags},
     error::*,
-    io::Stream,
-    opts::Opts,
+    io::{order_addrs, Stream},
+    opts::{Opts, ResetKind, SocketFallback, SslMode},
+    prelude::{FromRow, StatementLike},
     queryable::{
         query_result::{QueryResult, ResultSetMeta},
         transaction::TxStatus,
@@ -51,12 +68,44 @@ use self::routines::Routine;
 
 #[cfg(feature = "binlog")]
 pub mod binlog_stream;
+pub(crate) mod conn_info;
+pub(crate) mod connect_trace;
+mod escape;
+pub(crate) mod factory;
+pub(crate) mod innodb_status;
+pub(crate) mod load_data;
+pub(crate) mod local_infile;
 pub mod pool;
+pub(crate) mod process_list;
+pub(crate) mod query_cache;
+pub(crate) mod replica_status;
 pub mod routines;
+pub(crate) mod server_edition;
 pub mod stmt_cache;
+pub(crate) mod table_columns;
+pub(crate) mod table_maintenance;
 
 const DEFAULT_WAIT_TIMEOUT: usize = 28800;
 
+/// MariaDB extended capability that allows `COM_STMT_BULK_EXECUTE`.
+const MARIADB_CLIENT_STMT_BULK_OPERATIONS: u32 = 1 << 2;
+
+/// MariaDB extended capabilities announced by the client.
+const MARIADB_CLIENT_EXT_CAPABILITIES: u32 = MARIADB_CLIENT_STMT_BULK_OPERATIONS;
+
+/// Character set sent in the handshake response to MySql 5.5.3+.
+const DEFAULT_CHARSET: &str = "utf8mb4";
+
+/// Collation sent in the handshake response to MySql 5.5.3+.
+const DEFAULT_COLLATION: &str = "utf8mb4_general_ci";
+
+/// Server error codes, that mean that resource groups are disabled or unsupported
+/// (e.g. thread priorities are not available to the server process).
+const ER_RESOURCE_GROUPS_UNSUPPORTED: &[u16] = &[1246, 3658];
+
+/// `ER_UNKNOWN_SYSTEM_VARIABLE` server error code.
+const ER_UNKNOWN_SYSTEM_VARIABLE: u16 = 1193;
+
 /// Helper that asynchronously disconnects the givent connection on the default tokio executor.
 fn disconnect(mut conn: Conn) {
     let disconnected = conn.inner.disconnected;
@@ -96,12 +145,24 @@ struct ConnInner {
     stream: Option<Stream>,
     id: u32,
     is_mariadb: bool,
+    /// MariaDB extended capabilities negotiated during the handshake.
+    mariadb_ext_capabilities: u32,
     version: (u16, u16, u16),
+    /// Cached value of `@@version_comment`.
+    version_comment: Option<String>,
+    /// Character set of the connection (`@@character_set_client`) as tracked by the driver.
+    charset: Cow<'static, str>,
+    /// Collation of the connection (`@@collation_connection`) as tracked by the driver.
+    collation: Option<Cow<'static, str>>,
+    /// Handshake scramble, if exposed (see [`Opts::expose_scramble`]).
+    scramble: Option<Vec<u8>>,
     socket: Option<String>,
     capabilities: CapabilityFlags,
     status: StatusFlags,
     last_ok_packet: Option<OkPacket<'static>>,
     last_err_packet: Option<mysql_common::packets::ServerError<'static>>,
+    /// Whether rows of the last query result were discarded due to [`Opts::max_rows`].
+    truncated: bool,
     handshake_complete: bool,
     pool: Option<Pool>,
     pending_result: std::result::Result<Option<PendingResult>, ServerError>,
@@ -111,17 +172,35 @@ struct ConnInner {
     ttl_deadline: Option<Instant>,
     last_io: Instant,
     wait_timeout: Duration,
+    /// Server's `max_allowed_packet`, if loaded (see [`Conn::server_max_allowed_packet`]).
+    server_max_allowed_packet: Option<usize>,
     stmt_cache: StmtCache,
     nonce: Vec<u8>,
     auth_plugin: AuthPlugin<'static>,
     auth_switched: bool,
     server_key: Option<Vec<u8>>,
     active_since: Instant,
+    /// Start of the command in progress (see [`Conn::last_command_duration`]).
+    command_started: Option<Instant>,
+    /// Duration of the last completed command (see [`Conn::last_command_duration`]).
+    last_command_duration: Option<Duration>,
+    /// Client-side label (see [`Conn::set_label`]).
+    label: Option<String>,
+    /// Host the TCP connection was established to (one of [`Opts::hosts`]).
+    host: Option<String>,
     /// Connection is already disconnected.
     pub(crate) disconnected: bool,
     /// One-time connection-level infile handler.
     infile_handler:
         Option<Pin<Box<dyn Future<Output = crate::Result<InfileData>> + Send + Sync + 'static>>>,
+    /// Files named by `LOAD DATA LOCAL INFILE` statements of the last query
+    /// (see [`Opts::strict_local_infile`]).
+    expected_infiles: Vec<Vec<u8>>,
+    /// Connection-level query result cache.
+    query_cache: Option<Arc<dyn QueryCache>>,
+    /// Whether the session may have state that a reconnect wouldn't restore
+    /// (see [`Opts::reconnect_on_wait_timeout`]).
+    session_dirty: bool,
 }
 
 impl fmt::Debug for ConnInner {
@@ -149,16 +228,23 @@ impl ConnInner {
             status: StatusFlags::empty(),
             last_ok_packet: None,
             last_err_packet: None,
+            truncated: false,
             handshake_complete: false,
             stream: None,
             is_mariadb: false,
+            mariadb_ext_capabilities: 0,
             version: (0, 0, 0),
+            version_comment: None,
+            charset: Cow::Borrowed(DEFAULT_CHARSET),
+            collation: Some(Cow::Borrowed(DEFAULT_COLLATION)),
+            scramble: None,
             id: 0,
             pending_result: Ok(None),
             pool: None,
             tx_status: TxStatus::None,
             last_io: Instant::now(),
             wait_timeout: Duration::from_secs(0),
+            server_max_allowed_packet: None,
             stmt_cache: StmtCache::new(opts.stmt_cache_size()),
             socket: opts.socket().map(Into::into),
             opts,
@@ -169,8 +255,15 @@ impl ConnInner {
             disconnected: false,
             server_key: None,
             infile_handler: None,
+            expected_infiles: Vec::new(),
+            query_cache: None,
+            session_dirty: true,
             reset_upon_returning_to_a_pool: false,
             active_since: Instant::now(),
+            command_started: None,
+            last_command_duration: None,
+            label: None,
+            host: None,
         }
     }
 
@@ -196,6 +289,46 @@ impl Conn {
         self.inner.id
     }
 
+    /// Sets a human-readable label of this connection, e.g. to attribute it to a subsystem
+    /// when diagnosing connection leaks (see [`Conn::conn_info`]).
+    ///
+    /// The label is a purely client-side bookkeeping, so the server is unaware of it.
+    /// Connections of a pool might get an initial label from the pool
+    /// (see [`crate::PoolOpts::with_conn_label`]). Note that the label of a pooled connection
+    /// persists when the connection is returned to the pool, so set it after every
+    /// [`crate::Pool::get_conn`], if it's meant to describe the current holder.
+    pub fn set_label<T: Into<String>>(&mut self, label: T) {
+        self.inner.label = Some(label.into());
+    }
+
+    /// Returns the label of this connection, if any (see [`Conn::set_label`]).
+    pub fn label(&self) -> Option<&str> {
+        self.inner.label.as_deref()
+    }
+
+    /// Returns client-side information about this connection.
+    ///
+    /// ```
+    /// # use mysql_async::{test_misc::get_opts, Conn};
+    /// # #[tokio::main]
+    /// # async fn main() -> mysql_async::Result<()> {
+    /// let mut conn = Conn::new(get_opts()).await?;
+    /// conn.set_label("billing");
+    /// let info = conn.conn_info();
+    /// assert_eq!(info.id, conn.id());
+    /// assert_eq!(info.label.as_deref(), Some("billing"));
+    /// assert!(!info.pooled);
+    /// # conn.disconnect().await }
+    /// ```
+    pub fn conn_info(&self) -> ConnInfo {
+        ConnInfo {
+            id: self.inner.id,
+            label: self.inner.label.clone(),
+            server_version: self.inner.version,
+            pooled: self.inner.pool.is_some(),
+        }
+    }
+
     /// Returns the disconnected state of the connection.
     pub fn is_disconnected(&self) -> bool {
         self.inner.disconnected
@@ -230,6 +363,27 @@ impl Conn {
             .unwrap_or_else(|| "".into())
     }
 
+    /// Returns the number of rows matched by the last `UPDATE` query
+    /// (regardless of the [`Opts::client_found_rows`] option).
+    ///
+    /// The value is parsed from the [info string][Conn::info] of the last OK packet
+    /// (`Rows matched: 3  Changed: 1  Warnings: 0`). Returns `None` if there is no such string,
+    /// which is the case for statements other than `UPDATE` (and also for servers with non-English
+    /// `lc_messages`). In this case fall back to [`Conn::affected_rows`], which is the number
+    /// of matched rows if [`Opts::client_found_rows`] is enabled and the number of changed rows
+    /// otherwise.
+    pub fn matched_rows(&self) -> Option<u64> {
+        parse_update_info(&self.info()).map(|(matched, _)| matched)
+    }
+
+    /// Returns the number of rows actually changed by the last `UPDATE` query
+    /// (regardless of the [`Opts::client_found_rows`] option).
+    ///
+    /// See [`Conn::matched_rows`] for the details and the fallback.
+    pub fn changed_rows(&self) -> Option<u64> {
+        parse_update_info(&self.info()).map(|(_, changed)| changed)
+    }
+
     /// Session state change for SESSION_TRACK_GTIDS type as reported by the server
     /// in the last OK packet
     /// If called immediately after commit, should contain the GTID of the committed transaction.
@@ -248,6 +402,189 @@ impl Conn {
             .nth(0)
     }
 
+    /// Returns the auth challenge (scramble) sent by the server in the initial handshake.
+    ///
+    /// **Advanced.** Returns `None` unless [`Opts::expose_scramble`] is enabled
+    /// (see its docs for the security implications). The scramble is 20 bytes long
+    /// for `mysql_native_password` and `caching_sha2_password`. Note that the server might
+    /// send another challenge if it asks to switch the auth plugin – it's not exposed.
+    pub fn scramble(&self) -> Option<&[u8]> {
+        self.inner.scramble.as_deref()
+    }
+
+    /// Number of prepared statements open on the server side for this connection.
+    ///
+    /// Counts statements prepared by this connection (cached or not) that were not yet closed
+    /// (see [`Opts::max_prepared_statements`]). SQL-level prepared statements
+    /// (see [`Opts::sql_prepared_statements`]) are not counted.
+    pub fn prepared_statements_count(&self) -> usize {
+        self.inner.stmt_cache.open_count()
+    }
+
+    /// Character set of the connection as tracked by the driver (e.g. `utf8mb4`).
+    ///
+    /// Initially it's the character set sent in the handshake (`utf8mb4`, or `utf8`
+    /// for servers prior to 5.5.3). Changes made by the client (e.g. via `SET NAMES`) are
+    /// tracked only if `CLIENT_SESSION_TRACK` capability is enabled and the server reports
+    /// changes of `character_set_client` (see `session_track_system_variables`),
+    /// but the character set given in [`Opts::charset`] is always taken into account.
+    pub fn charset(&self) -> &str {
+        &self.inner.charset
+    }
+
+    /// Collation of the connection as tracked by the driver (e.g. `utf8mb4_general_ci`).
+    ///
+    /// Initially it's the collation sent in the handshake. Changes are tracked the same way
+    /// as for [`Conn::charset`], but the server reports changes of `collation_connection`
+    /// only if it's listed in `session_track_system_variables` (it's not by default).
+    /// So it returns `None` if the character set is known to have changed while the new
+    /// collation wasn't reported (e.g. after `SET NAMES latin1`).
+    pub fn collation(&self) -> Option<&str> {
+        self.inner.collation.as_deref()
+    }
+
+    /// Escapes `s` so that it could be safely used within a quoted string literal
+    /// of a text query (matches `mysql_real_escape_string` semantics).
+    ///
+    /// Escaping depends on the [character set of the connection][Conn::charset]
+    /// (multibyte characters are copied as is) and on the `NO_BACKSLASH_ESCAPES` SQL mode
+    /// (as reported by the server in status flags) – if it's set then only `'` is escaped
+    /// by doubling it. So the result must be enclosed in single quotes:
+    ///
+    /// ```
+    /// # use mysql_async::{prelude::*, test_misc::get_opts, Conn};
+    /// # #[tokio::main]
+    /// # async fn main() -> mysql_async::Result<()> {
+    /// let mut conn = Conn::new(get_opts()).await?;
+    /// let name = "O'Reilly \\ Sons";
+    /// let query = format!("SELECT '{}'", conn.escape_string(name)?);
+    /// let result: Option<String> = conn.query_first(query).await?;
+    /// assert_eq!(result.as_deref(), Some(name));
+    /// # conn.disconnect().await }
+    /// ```
+    ///
+    /// Prefer prepared statements or [`Value::as_sql`](crate::Value::as_sql) where possible.
+    ///
+    /// # Errors
+    ///
+    /// The string is assumed to be sent to the server as is (i.e. as UTF-8). If the connection
+    /// character set is one of `big5`, `cp932`, `gbk`, `gb18030`, `sjis`, then `s` might not
+    /// be a valid text in this character set (e.g. `—` in `gbk`), so that the escaped result
+    /// is not a valid UTF-8. [`DriverError::CantEscapeString`] is returned in this case.
+    /// Use [`Conn::escape_bytes`] for properly encoded data.
+    pub fn escape_string(&self, s: &str) -> Result<String> {
+        String::from_utf8(self.escape_bytes(s.as_bytes())).map_err(|_| {
+            DriverError::CantEscapeString {
+                charset: self.charset().into(),
+            }
+            .into()
+        })
+    }
+
+    /// Escapes `bytes` the same way as [`Conn::escape_string`] does.
+    ///
+    /// `bytes` are expected to be encoded using the
+    /// [character set of the connection][Conn::charset].
+    pub fn escape_bytes(&self, bytes: &[u8]) -> Vec<u8> {
+        let no_backslash_escapes = self
+            .status()
+            .contains(StatusFlags::SERVER_STATUS_NO_BACKSLASH_ESCAPES);
+        escape::escape_bytes(bytes, self.charset(), no_backslash_escapes)
+    }
+
+    /// Escapes `s` so that it could be used within a quoted `LIKE` pattern
+    /// to match `s` literally.
+    ///
+    /// LIKE metacharacters (`%`, `_`) and `escape_char` itself are prefixed with `escape_char`,
+    /// then the pattern is escaped using [`Conn::escape_string`], so it depends on the
+    /// `NO_BACKSLASH_ESCAPES` SQL mode the same way. Note that if the mode is set the server
+    /// has no default `LIKE` escape character, so always specify it via the `ESCAPE` clause:
+    ///
+    /// ```
+    /// # use mysql_async::{prelude::*, test_misc::get_opts, Conn};
+    /// # #[tokio::main]
+    /// # async fn main() -> mysql_async::Result<()> {
+    /// let mut conn = Conn::new(get_opts()).await?;
+    /// let query = format!(
+    ///     "SELECT '100% pure' LIKE '{}%' ESCAPE '!', '1000 pure' LIKE '{}%' ESCAPE '!'",
+    ///     conn.escape_like("100%", '!')?,
+    ///     conn.escape_like("100%", '!')?,
+    /// );
+    /// let result: Option<(bool, bool)> = conn.query_first(query).await?;
+    /// assert_eq!(result, Some((true, false)));
+    /// # conn.disconnect().await }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Same as [`Conn::escape_string`].
+    pub fn escape_like(&self, s: &str, escape_char: char) -> Result<String> {
+        let mut pattern = String::with_capacity(s.len() + s.len() / 8);
+        for c in s.chars() {
+            if c == '%' || c == '_' || c == escape_char {
+                pattern.push(escape_char);
+            }
+            pattern.push(c);
+        }
+        self.escape_string(&pattern)
+    }
+
+    /// Returns the character set sent in the handshake response.
+    fn handshake_charset(&self) -> &'static str {
+        if self.inner.version >= (5, 5, 3) {
+            DEFAULT_CHARSET
+        } else {
+            "utf8"
+        }
+    }
+
+    /// Returns the collation sent in the handshake response.
+    fn handshake_collation(&self) -> &'static str {
+        if self.inner.version >= (5, 5, 3) {
+            DEFAULT_COLLATION
+        } else {
+            "utf8_general_ci"
+        }
+    }
+
+    /// Resets the tracked character set and collation to the handshake ones.
+    fn reset_charset(&mut self) {
+        self.inner.charset = Cow::Borrowed(self.handshake_charset());
+        self.inner.collation = Some(Cow::Borrowed(self.handshake_collation()));
+    }
+
+    /// Updates the tracked character set and collation from session state changes
+    /// of the given OK packet.
+    fn track_charset(&mut self, ok_packet: &OkPacket<'_>) {
+        let changes = ok_packet.session_state_info().unwrap_or_default();
+        let mut charset = None;
+        let mut collation = None;
+        for change in changes {
+            if change.data_type() != SessionStateType::SESSION_TRACK_SYSTEM_VARIABLES {
+                continue;
+            }
+            if let Ok(SessionStateChange::SystemVariables(vars)) = change.decode() {
+                for var in vars {
+                    match var.name_bytes() {
+                        b"character_set_client" => charset = Some(var.value_str().into_owned()),
+                        b"collation_connection" => collation = Some(var.value_str().into_owned()),
+                        _ => (),
+                    }
+                }
+            }
+        }
+
+        if let Some(charset) = charset {
+            if *self.inner.charset != charset && collation.is_none() {
+                self.inner.collation = None;
+            }
+            self.inner.charset = Cow::Owned(charset);
+        }
+        if let Some(collation) = collation {
+            self.inner.collation = Some(Cow::Owned(collation));
+        }
+    }
+
     /// Number of warnings, as reported by the server in the last OK packet, or `0`.
     pub fn get_warnings(&self) -> u16 {
         self.inner
@@ -262,6 +599,61 @@ impl Conn {
         self.inner.last_ok_packet.as_ref()
     }
 
+    /// Returns `true` if rows of the last query result were discarded because of
+    /// the client-side row limit (see [`Opts::max_rows`]).
+    ///
+    /// ```
+    /// # use mysql_async::{prelude::*, test_misc::get_opts, Conn};
+    /// # #[tokio::main]
+    /// # async fn main() -> mysql_async::Result<()> {
+    /// let mut conn = Conn::new(get_opts().max_rows(2)).await?;
+    /// let rows: Vec<u8> = conn.query("SELECT 1 UNION ALL SELECT 2 UNION ALL SELECT 3").await?;
+    /// assert_eq!(rows, vec![1, 2]);
+    /// assert!(conn.was_truncated());
+    /// # conn.disconnect().await }
+    /// ```
+    pub fn was_truncated(&self) -> bool {
+        self.inner.truncated
+    }
+
+    pub(crate) fn set_truncated(&mut self) {
+        self.inner.truncated = true;
+    }
+
+    /// Returns the wall-clock duration of the last query or statement execution.
+    ///
+    /// The duration is measured from the moment the command is sent to the server until
+    /// its result is fully consumed (i.e. it includes the time spent reading rows, as well as
+    /// the time the caller spends between reading them), or until it fails.
+    ///
+    /// Returns `None` if no command has completed yet, or if a command is in progress.
+    ///
+    /// ```
+    /// # use mysql_async::{prelude::*, test_misc::get_opts, Conn};
+    /// # #[tokio::main]
+    /// # async fn main() -> mysql_async::Result<()> {
+    /// let mut conn = Conn::new(get_opts()).await?;
+    /// conn.query_drop("DO 1").await?;
+    /// assert!(conn.last_command_duration().is_some());
+    /// # conn.disconnect().await }
+    /// ```
+    pub fn last_command_duration(&self) -> Option<Duration> {
+        self.inner.last_command_duration
+    }
+
+    /// Starts measuring duration of a command (see [`Conn::last_command_duration`]).
+    pub(crate) fn start_command(&mut self) {
+        self.inner.command_started = Some(Instant::now());
+        self.inner.last_command_duration = None;
+    }
+
+    /// Records duration of the command in progress, if any.
+    pub(crate) fn finish_command(&mut self) {
+        if let Some(started) = self.inner.command_started.take() {
+            self.inner.last_command_duration = Some(started.elapsed());
+        }
+    }
+
     /// Turns on/off automatic connection reset (see [`crate::PoolOpts::with_reset_connection`]).
     ///
     /// Only makes sense for pooled connections.
@@ -296,9 +688,25 @@ impl Conn {
         }
     }
 
+    /// Prepares to read a response to a pipelined command
+    /// (see [`Stream::expect_pipelined_response`]).
+    pub(crate) fn expect_pipelined_response(&mut self) {
+        if let Some(stream) = self.inner.stream.as_mut() {
+            stream.expect_pipelined_response();
+        }
+    }
+
     /// Handles OK packet.
     pub(crate) fn handle_ok(&mut self, ok_packet: OkPacket<'static>) {
         self.inner.status = ok_packet.status_flags();
+        if self
+            .inner
+            .status
+            .contains(StatusFlags::SERVER_SESSION_STATE_CHANGED)
+        {
+            self.inner.session_dirty = true;
+            self.track_charset(&ok_packet);
+        }
         self.inner.last_err_packet = None;
         self.inner.last_ok_packet = Some(ok_packet);
     }
@@ -307,7 +715,9 @@ impl Conn {
     pub(crate) fn handle_err(&mut self, err_packet: ErrPacket<'_>) -> Result<()> {
         match err_packet {
             ErrPacket::Error(err) => {
-                self.inner.status = StatusFlags::empty();
+                // ERR packet carries no status flags, but an error usually
+                // leaves an active transaction open, so we keep assuming it is.
+                self.inner.status &= StatusFlags::SERVER_STATUS_IN_TRANS;
                 self.inner.last_ok_packet = None;
                 self.inner.last_err_packet = Some(err.clone().into_owned());
                 Err(Error::from(err))
@@ -356,6 +766,9 @@ impl Conn {
         &mut self,
         meta: Option<ResultSetMeta>,
     ) -> std::result::Result<Option<PendingResult>, ServerError> {
+        if meta.is_none() && !self.more_results_exists() {
+            self.finish_command();
+        }
         replace(
             &mut self.inner.pending_result,
             Ok(meta.map(PendingResult::Pending)),
@@ -366,6 +779,7 @@ impl Conn {
         &mut self,
         error: ServerError,
     ) -> std::result::Result<Option<PendingResult>, ServerError> {
+        self.finish_command();
         replace(&mut self.inner.pending_result, Err(error))
     }
 
@@ -392,15 +806,34 @@ impl Conn {
         self.inner.status
     }
 
+    /// Returns `true` if the server reports an active transaction for this session.
+    ///
+    /// Unlike [`Transaction`](crate::Transaction) tracking this also covers transactions
+    /// started or finished by raw SQL (e.g. `BEGIN`, `COMMIT` or a stored procedure),
+    /// because it is based on the status flags of the latest OK/EOF packet (see [`Conn::status`]).
+    ///
+    /// A connection returned to a [`Pool`] is rolled back if this function returns `true`.
+    pub fn in_transaction(&self) -> bool {
+        self.inner.tx_status != TxStatus::None
+            || self.status().contains(StatusFlags::SERVER_STATUS_IN_TRANS)
+    }
+
     pub(crate) async fn routine<'a, F, T>(&mut self, mut f: F) -> crate::Result<T>
     where
         F: Routine<T> + 'a,
     {
         self.inner.disconnected = true;
         let result = f.call(&mut *self).await;
+        if result.is_err() {
+            self.finish_command();
+        }
         match result {
-            result @ Ok(_) | result @ Err(crate::Error::Server(_)) => {
-                // either OK or non-fatal error
+            result @ Ok(_)
+            | result @ Err(crate::Error::Server(_))
+            | result @ Err(crate::Error::Driver(
+                DriverError::QueryTooLong { .. } | DriverError::ParamTooLong { .. },
+            )) => {
+                // either OK or non-fatal error (length limits are checked before sending anything)
                 self.inner.disconnected = false;
                 result
             }
@@ -418,6 +851,64 @@ impl Conn {
         self.inner.version
     }
 
+    /// Returns server's `max_allowed_packet` cached during connection initialization.
+    ///
+    /// The value is loaded if [`Opts::max_allowed_packet`] is `None` (default) or if
+    /// [`Opts::load_server_max_allowed_packet`] is `true`. Returns `None` otherwise.
+    ///
+    /// Note that the value is not updated if `max_allowed_packet` changes afterwards.
+    pub fn server_max_allowed_packet(&self) -> Option<usize> {
+        self.inner.server_max_allowed_packet
+    }
+
+    /// Returns `true` if `COM_STMT_BULK_EXECUTE` is negotiated during the handshake
+    /// (MariaDB 10.2.7+, see `MARIADB_CLIENT_STMT_BULK_OPERATIONS`).
+    pub(crate) fn supports_bulk_execute(&self) -> bool {
+        (self.inner.mariadb_ext_capabilities & MARIADB_CLIENT_STMT_BULK_OPERATIONS) != 0
+    }
+
+    /// Announces MariaDB extended capabilities in a serialized `SslRequest`
+    /// or `HandshakeResponse` (the last four bytes of its reserved area).
+    ///
+    /// MariaDB reads them only if `CLIENT_MYSQL` (aka `CLIENT_LONG_PASSWORD`) isn't negotiated.
+    fn announce_mariadb_ext_capabilities(&self, packet: &mut [u8]) {
+        let capabilities = self.inner.capabilities;
+        if capabilities.contains(CapabilityFlags::CLIENT_PROTOCOL_41)
+            && !capabilities.contains(CapabilityFlags::CLIENT_LONG_PASSWORD)
+        {
+            if let Some(reserved) = packet.get_mut(28..32) {
+                reserved.copy_from_slice(&MARIADB_CLIENT_EXT_CAPABILITIES.to_le_bytes());
+            }
+        }
+    }
+
+    /// Returns the value of `@@version_comment` server variable
+    /// (e.g. `MySQL Community Server - GPL`).
+    ///
+    /// The value is queried on the first call and cached for the lifetime of the connection.
+    pub async fn version_comment(&mut self) -> Result<String> {
+        if let Some(version_comment) = self.inner.version_comment.as_ref() {
+            return Ok(version_comment.clone());
+        }
+
+        let version_comment: Option<String> = self.query_first("SELECT @@version_comment").await?;
+        let version_comment = version_comment.unwrap_or_default();
+        self.inner.version_comment = Some(version_comment.clone());
+        Ok(version_comment)
+    }
+
+    /// Returns the server edition derived from `@@version_comment`
+    /// (see [`Conn::version_comment`]).
+    pub async fn server_edition(&mut self) -> Result<ServerEdition> {
+        if self.inner.is_mariadb {
+            return Ok(ServerEdition::MariaDb);
+        }
+
+        self.version_comment()
+            .await
+            .map(|x| ServerEdition::from_version_comment(&x))
+    }
+
     /// Returns connection options.
     pub fn opts(&self) -> &Opts {
         &self.inner.opts
@@ -437,6 +928,137 @@ impl Conn {
         self.inner.infile_handler = Some(Box::pin(handler));
     }
 
+    /// Bulk loads `rows` into `table` using `LOAD DATA LOCAL INFILE`.
+    ///
+    /// This is the fastest way to insert a large number of rows. Rows are serialized
+    /// into the `LOAD DATA` format (taking care of `NULL`s, embedded tabs, newlines and
+    /// backslashes) and streamed to the server via a _local_ `LOCAL INFILE` handler
+    /// (it'll overwrite existing _local_ handler, if any).
+    ///
+    /// * `table` – unquoted table name, that might be qualified with a database name
+    ///   (e.g. `db.tbl`, the part before the first `.` is the database name);
+    /// * `columns` – list of unquoted columns to load values into (all columns if empty);
+    /// * `rows` – rows, where each row is a tuple or a `Vec` of values
+    ///   (named parameters are not supported).
+    ///
+    /// Values are sent without character set conversion (`CHARACTER SET binary`).
+    /// The load fails if a row contains a NaN or an infinite float.
+    ///
+    /// Requires `local_infile` to be enabled on the server.
+    ///
+    /// ```no_run
+    /// # use mysql_async::{test_misc::get_opts, Conn, prelude::*};
+    /// # #[tokio::main]
+    /// # async fn main() -> mysql_async::Result<()> {
+    /// # let mut conn = Conn::new(get_opts()).await?;
+    /// conn.query_drop("CREATE TEMPORARY TABLE tmp (id INT, name TEXT)").await?;
+    /// let rows = (0..1000).map(|i| (i, format!("name\t{}", i)));
+    /// let result = conn.load_data("tmp", &["id", "name"], rows).await?;
+    /// assert_eq!(result.affected_rows, 1000);
+    /// # conn.disconnect().await }
+    /// ```
+    pub async fn load_data<I>(
+        &mut self,
+        table: &str,
+        columns: &[&str],
+        rows: I,
+    ) -> Result<LoadDataResult>
+    where
+        I: IntoIterator,
+        I::IntoIter: Send + 'static,
+        I::Item: Into<crate::Params> + Send + 'static,
+    {
+        // Mutex makes the handler `Sync` without requiring rows to be `Sync`.
+        let rows = std::sync::Mutex::new(rows.into_iter());
+        self.set_infile_handler(async move {
+            let rows = rows.into_inner().unwrap_or_else(|err| err.into_inner());
+            Ok(Box::pin(futures_util::stream::iter(load_data_chunks(rows))) as InfileData)
+        });
+
+        let result = self.query_drop(load_data_query(table, columns)).await;
+
+        // Handler won't be consumed if the server rejects the statement.
+        self.inner.infile_handler = None;
+        result?;
+
+        Ok(LoadDataResult {
+            affected_rows: self.affected_rows(),
+            warnings: self.get_warnings(),
+        })
+    }
+
+    /// Sets up a client-side cache of query results consulted by [`Conn::query_cached`]
+    /// and [`Conn::exec_cached`] (`None` removes the cache).
+    ///
+    /// The cache belongs to this connection – it's removed by [`Conn::reset`],
+    /// [`Conn::change_user`] and when the connection is dropped (e.g. returned to a pool).
+    ///
+    /// Staleness is the caller's responsibility (see [`QueryCache`]).
+    pub fn set_query_cache(&mut self, cache: Option<Arc<dyn QueryCache>>) {
+        self.inner.query_cache = cache;
+    }
+
+    /// Performs the given text query and collects the first result set, consulting
+    /// the query cache (see [`Conn::set_query_cache`]).
+    ///
+    /// Rows are taken from the cache if there is an entry for
+    /// the [normalized query][QueryCacheKey], so that the server isn't queried at all. Otherwise the query is performed as with
+    /// [`Queryable::query`] and its rows are put into the cache. Behaves as [`Queryable::query`]
+    /// if there is no cache.
+    ///
+    /// ## Conversion
+    ///
+    /// Rows are converted into `T` using its [`FromRow`] implementation
+    /// (see [`Queryable::query`]).
+    pub async fn query_cached<T, Q>(&mut self, query: Q) -> Result<Vec<T>>
+    where
+        Q: AsRef<str>,
+        T: FromRow + Send + 'static,
+    {
+        self.cached_rows(query.as_ref(), None).await
+    }
+
+    /// Executes the given statement with the given params and collects the first result set,
+    /// consulting the query cache (see [`Conn::query_cached`]).
+    ///
+    /// Params are a part of the cache key.
+    pub async fn exec_cached<T, Q, P>(&mut self, query: Q, params: P) -> Result<Vec<T>>
+    where
+        Q: AsRef<str>,
+        P: Into<crate::Params>,
+        T: FromRow + Send + 'static,
+    {
+        self.cached_rows(query.as_ref(), Some(params.into())).await
+    }
+
+    async fn cached_rows<T>(&mut self, query: &str, params: Option<crate::Params>) -> Result<Vec<T>>
+    where
+        T: FromRow + Send + 'static,
+    {
+        let cached = self
+            .inner
+            .query_cache
+            .clone()
+            .map(|cache| (QueryCacheKey::new(query, params.as_ref()), cache));
+
+        if let Some((ref key, ref cache)) = cached {
+            if let Some(rows) = cache.get(key) {
+                return Ok(rows.into_iter().map(crate::from_row).collect());
+            }
+        }
+
+        let rows: Vec<Row> = match params {
+            Some(params) => self.exec(query, params).await?,
+            None => self.query(query).await?,
+        };
+
+        if let Some((key, cache)) = cached {
+            cache.put(key, rows.clone());
+        }
+
+        Ok(rows.into_iter().map(crate::from_row).collect())
+    }
+
     fn take_stream(&mut self) -> Stream {
         self.inner.stream.take().unwrap()
     }
@@ -473,6 +1095,24 @@ impl Conn {
         false
     }
 
+    /// Returns `true` if the connection is encrypted using a resumed TLS session
+    /// (see [`SslOpts::with_disable_session_resumption`]).
+    ///
+    /// Always returns `false` unless the `rustls-tls` feature is enabled.
+    pub fn is_tls_resumed(&self) -> bool {
+        #[cfg(feature = "rustls-tls")]
+        {
+            self.inner
+                .stream
+                .as_ref()
+                .map(|x| x.is_tls_resumed())
+                .unwrap_or_default()
+        }
+
+        #[cfg(not(feature = "rustls-tls"))]
+        false
+    }
+
     /// Returns true if io stream is socket.
     fn is_socket(&self) -> bool {
         #[cfg(unix)]
@@ -523,8 +1163,19 @@ impl Conn {
             nonce.resize(20, 0);
             nonce
         };
+        if self.inner.opts.expose_scramble() {
+            self.inner.scramble = Some(self.inner.nonce.clone());
+        }
 
         self.inner.capabilities = handshake.capabilities() & self.inner.opts.get_capabilities();
+        self.inner.mariadb_ext_capabilities = if handshake
+            .capabilities()
+            .contains(CapabilityFlags::CLIENT_LONG_PASSWORD)
+        {
+            0
+        } else {
+            mariadb_ext_capabilities(&packet) & MARIADB_CLIENT_EXT_CAPABILITIES
+        };
         self.inner.version = handshake
             .maria_db_server_version_parsed()
             .inspect(|_| self.inner.is_mariadb = true)
@@ -532,18 +1183,42 @@ impl Conn {
             .unwrap_or((0, 0, 0));
         self.inner.id = handshake.connection_id();
         self.inner.status = handshake.status_flags();
+        self.reset_charset();
 
         // Allow only CachingSha2Password and MysqlNativePassword here
         // because sha256_password is deprecated and other plugins won't
         // appear here.
-        self.inner.auth_plugin = match handshake.auth_plugin() {
-            Some(AuthPlugin::CachingSha2Password) => AuthPlugin::CachingSha2Password,
-            _ => AuthPlugin::MysqlNativePassword,
+        self.inner.auth_plugin = match self.inner.opts.auth_plugin() {
+            Some("mysql_native_password") => AuthPlugin::MysqlNativePassword,
+            Some("caching_sha2_password") => AuthPlugin::CachingSha2Password,
+            Some(name) => {
+                return Err(DriverError::UnknownAuthPlugin {
+                    name: name.to_owned(),
+                }
+                .into())
+            }
+            None => match handshake.auth_plugin() {
+                Some(AuthPlugin::CachingSha2Password) => AuthPlugin::CachingSha2Password,
+                _ => AuthPlugin::MysqlNativePassword,
+            },
         };
 
         Ok(())
     }
 
+    /// Returns connection attributes to send to the server,
+    /// if `CLIENT_CONNECT_ATTRS` capability is negotiated.
+    pub(crate) fn connect_attrs(&self) -> Option<HashMap<String, String>> {
+        if self
+            .capabilities()
+            .contains(CapabilityFlags::CLIENT_CONNECT_ATTRS)
+        {
+            Some(self.inner.opts.connect_attrs().clone())
+        } else {
+            None
+        }
+    }
+
     async fn switch_to_ssl_if_needed(&mut self) -> Result<()> {
         if self
             .inner
@@ -556,6 +1231,14 @@ impl Conn {
                 .capabilities
                 .contains(CapabilityFlags::CLIENT_SSL)
             {
+                let preferred = self
+                    .inner
+                    .opts
+                    .ssl_opts()
+                    .is_some_and(|x| x.mode() == SslMode::Preferred);
+                if preferred {
+                    return Ok(());
+                }
                 return Err(DriverError::NoClientSslFlagFromServer.into());
             }
 
@@ -570,12 +1253,16 @@ impl Conn {
                 DEFAULT_MAX_ALLOWED_PACKET as u32,
                 collation as u8,
             );
-            self.write_struct(&ssl_request).await?;
+            let mut buf = crate::buffer_pool().get();
+            ssl_request.serialize(buf.as_mut());
+            self.announce_mariadb_ext_capabilities(buf.as_mut());
+            self.write_packet(buf).await?;
             let conn = self;
             let ssl_opts = conn.opts().ssl_opts_and_connector().expect("unreachable");
             let domain = ssl_opts
                 .ssl_opts()
                 .tls_hostname_override()
+                .or(conn.inner.host.as_deref())
                 .unwrap_or_else(|| conn.opts().ip_or_hostname())
                 .into();
             let tls_connector = ssl_opts.build_tls_connector().await?;
@@ -601,7 +1288,7 @@ impl Conn {
             self.inner.opts.db_name().map(|x| x.as_bytes()),
             Some(self.inner.auth_plugin.borrow()),
             self.capabilities(),
-            self.inner.opts.connect_attributes().cloned(),
+            self.connect_attrs(),
             self.inner
                 .opts
                 .max_allowed_packet()
@@ -611,6 +1298,7 @@ impl Conn {
         // Serialize here to satisfy borrow checker.
         let mut buf = crate::buffer_pool().get();
         handshake_response.serialize(buf.as_mut());
+        self.announce_mariadb_ext_capabilities(buf.as_mut());
 
         self.write_packet(buf).await?;
         self.inner.handshake_complete = true;
@@ -910,13 +1598,20 @@ impl Conn {
 
     pub async fn read_packet(&mut self) -> Result<PooledBuf> {
         loop {
-            let packet = crate::io::ReadPacket::new(&mut *self)
-                .await
-                .map_err(|io_err| {
-                    self.inner.stream.take();
-                    self.inner.disconnected = true;
-                    Error::from(io_err)
-                })?;
+            let read_timeout = self.inner.opts.read_timeout();
+            let read = crate::io::ReadPacket::new(&mut *self);
+            let result = match read_timeout {
+                Some(timeout) => match tokio::time::timeout(timeout, read).await {
+                    Ok(result) => result.map_err(Error::from),
+                    Err(_) => Err(DriverError::ReadTimeout { timeout }.into()),
+                },
+                None => read.await.map_err(Error::from),
+            };
+            let packet = result.map_err(|err| {
+                self.inner.stream.take();
+                self.inner.disconnected = true;
+                err
+            })?;
             if self.handle_packet(&packet)? {
                 // ignore progress report
                 continue;
@@ -936,13 +1631,20 @@ impl Conn {
     }
 
     pub(crate) async fn write_packet(&mut self, data: PooledBuf) -> Result<()> {
-        crate::io::WritePacket::new(&mut *self, data)
-            .await
-            .map_err(|io_err| {
-                self.inner.stream.take();
-                self.inner.disconnected = true;
-                From::from(io_err)
-            })
+        let write_timeout = self.inner.opts.write_timeout();
+        let write = crate::io::WritePacket::new(&mut *self, data);
+        let result = match write_timeout {
+            Some(timeout) => match tokio::time::timeout(timeout, write).await {
+                Ok(result) => result.map_err(Error::from),
+                Err(_) => Err(DriverError::WriteTimeout { timeout }.into()),
+            },
+            None => write.await.map_err(Error::from),
+        };
+        result.map_err(|err| {
+            self.inner.stream.take();
+            self.inner.disconnected = true;
+            err
+        })
     }
 
     /// Writes bytes to a server.
@@ -961,6 +1663,7 @@ impl Conn {
     /// Sends a command to a server.
     pub async fn write_command<T: MySerialize>(&mut self, cmd: &T) -> Result<()> {
         self.clean_dirty().await?;
+        self.inner.expected_infiles.clear();
         self.reset_seq_id();
         self.write_struct(cmd).await
     }
@@ -969,6 +1672,7 @@ impl Conn {
     pub async fn write_command_raw(&mut self, body: PooledBuf) -> Result<()> {
         debug_assert!(!body.is_empty());
         self.clean_dirty().await?;
+        self.inner.expected_infiles.clear();
         self.reset_seq_id();
         self.write_packet(body).await
     }
@@ -991,6 +1695,27 @@ impl Conn {
         Ok(())
     }
 
+    /// Sets `sql_mode` and `time_zone` of this connection, if any
+    /// (see [`Opts::sql_mode`] and [`Opts::time_zone`]).
+    async fn set_session_vars(&mut self) -> Result<()> {
+        let vars = [
+            ("sql_mode", self.inner.opts.sql_mode()),
+            ("time_zone", self.inner.opts.time_zone()),
+        ];
+        let mut assignments = Vec::new();
+        for (name, value) in vars {
+            if let Some(value) = value {
+                assignments.push(format!("{} = '{}'", name, self.escape_string(value)?));
+            }
+        }
+
+        if !assignments.is_empty() {
+            let query = format!("SET SESSION {}", assignments.join(", "));
+            self.query_drop(query).await?;
+        }
+        Ok(())
+    }
+
     async fn run_init_commands(&mut self) -> Result<()> {
         let mut init = self.inner.opts.init().to_vec();
 
@@ -1001,73 +1726,441 @@ impl Conn {
         Ok(())
     }
 
-    async fn run_setup_commands(&mut self) -> Result<()> {
-        let mut setup = self.inner.opts.setup().to_vec();
+    /// Sets up the session as configured in [`Opts`] (character set, resource group etc.).
+    ///
+    /// User-defined [`Opts::setup`] commands are only executed if `user_setup` is `true`.
+    async fn run_setup_commands(&mut self, user_setup: bool) -> Result<()> {
+        let track_session_state = self.inner.opts.reconnect_on_wait_timeout()
+            && self
+                .capabilities()
+                .contains(CapabilityFlags::CLIENT_SESSION_TRACK);
+        if track_session_state {
+            self.query_drop("SET SESSION session_track_state_change = ON")
+                .await?;
+        }
+        self.set_resource_group().await?;
+        self.set_connection_memory_limit().await?;
+        self.set_wait_timeout().await?;
+        // `SET NAMES` also resets `character_set_results`, so it goes first.
+        self.set_names().await?;
+
+        if self.inner.opts.binary_results() {
+            self.query_drop("SET character_set_results = binary")
+                .await?;
+        }
+
+        if user_setup {
+            let mut setup = self.inner.opts.setup().to_vec();
 
-        while let Some(query) = setup.pop() {
-            self.query_drop(query).await?;
+            while let Some(query) = setup.pop() {
+                self.query_drop(query).await?;
+            }
         }
 
+        // whatever is set up so far is restored on reconnect, and further changes are reported
+        self.inner.session_dirty = !track_session_state;
+
         Ok(())
     }
 
-    /// Returns a future that resolves to [`Conn`].
-    pub fn new<T: Into<Opts>>(opts: T) -> crate::BoxFuture<'static, Conn> {
-        let opts = opts.into();
-        async move {
-            let mut conn = Conn::empty(opts.clone());
-
-            let stream = if let Some(_path) = opts.socket() {
-                #[cfg(unix)]
-                {
-                    Stream::connect_socket(_path.to_owned()).await?
-                }
-                #[cfg(not(unix))]
-                return Err(crate::DriverError::NamedPipesDisabled.into());
-            } else {
-                let keepalive = opts
-                    .tcp_keepalive()
-                    .map(|x| std::time::Duration::from_millis(x.into()));
-                Stream::connect_tcp(opts.hostport_or_url(), keepalive).await?
-            };
+    /// Sets the character set of this connection, if any (see [`Opts::charset`]).
+    async fn set_names(&mut self) -> Result<()> {
+        let charset = match self.inner.opts.charset() {
+            Some(charset) => charset.to_ascii_lowercase(),
+            None => return Ok(()),
+        };
 
-            conn.inner.stream = Some(stream);
-            conn.setup_stream()?;
-            conn.handle_handshake().await?;
-            conn.switch_to_ssl_if_needed().await?;
-            conn.do_handshake_response().await?;
-            conn.continue_auth().await?;
-            conn.switch_to_compression()?;
-            conn.read_settings().await?;
-            conn.reconnect_via_socket_if_needed().await?;
-            conn.run_init_commands().await?;
-            conn.run_setup_commands().await?;
+        let query = format!("SET NAMES `{}`", charset.replace('`', "``"));
+        self.query_drop(query).await?;
 
-            Ok(conn)
+        // The change may not have been reported by the server (see `Conn::charset`).
+        if self.inner.charset != charset {
+            self.inner.charset = Cow::Owned(charset);
+            self.inner.collation = None;
         }
-        .boxed()
-    }
 
-    /// Returns a future that resolves to [`Conn`].
-    pub async fn from_url<T: AsRef<str>>(url: T) -> Result<Conn> {
-        Conn::new(Opts::from_str(url.as_ref())?).await
+        Ok(())
     }
 
-    /// Will try to reconnect via socket using socket address in `self.inner.socket`.
-    ///
-    /// Won't try to reconnect if socket connection is already enforced in [`Opts`].
-    async fn reconnect_via_socket_if_needed(&mut self) -> Result<()> {
-        if let Some(socket) = self.inner.socket.as_ref() {
-            let opts = self.inner.opts.clone();
-            if opts.socket().is_none() {
-                let opts = OptsBuilder::from_opts(opts).socket(Some(&**socket));
-                if let Ok(conn) = Conn::new(opts).await {
-                    let old_conn = std::mem::replace(self, conn);
-                    // tidy up the old connection
-                    old_conn.close_conn().await?;
+    /// Assigns this connection to the resource group, if any (see [`Opts::resource_group`]).
+    async fn set_resource_group(&mut self) -> Result<()> {
+        let name = match self.inner.opts.resource_group() {
+            Some(name) => name.replace('`', "``"),
+            None => return Ok(()),
+        };
+
+        if self.inner.is_mariadb || self.inner.version < (8, 0, 3) {
+            return Err(DriverError::ResourceGroupsNotSupported { server_error: None }.into());
+        }
+
+        let query = format!("SET RESOURCE GROUP `{}`", name);
+        match self.query_drop(query).await {
+            Err(Error::Server(server_error))
+                if ER_RESOURCE_GROUPS_UNSUPPORTED.contains(&server_error.code) =>
+            {
+                Err(DriverError::ResourceGroupsNotSupported {
+                    server_error: Some(server_error),
                 }
+                .into())
             }
+            result => result,
         }
+    }
+
+    /// Overrides the session `wait_timeout`, if given (see [`Opts::wait_timeout`]).
+    async fn set_wait_timeout(&mut self) -> Result<()> {
+        if let Some(wait_timeout) = self.inner.opts.wait_timeout() {
+            let query = format!("SET SESSION wait_timeout = {}", wait_timeout);
+            self.query_drop(query).await?;
+        }
+        Ok(())
+    }
+
+    /// Sets the memory limit of this connection, if any (see [`Opts::connection_memory_limit`]).
+    async fn set_connection_memory_limit(&mut self) -> Result<()> {
+        let limit = match self.inner.opts.connection_memory_limit() {
+            Some(limit) => limit,
+            None => return Ok(()),
+        };
+
+        if self.inner.is_mariadb || self.inner.version < (8, 0, 28) {
+            return Err(
+                DriverError::ConnectionMemoryLimitNotSupported { server_error: None }.into(),
+            );
+        }
+
+        let query = format!("SET SESSION connection_memory_limit = {}", limit);
+        match self.query_drop(query).await {
+            Err(Error::Server(server_error)) if server_error.code == ER_UNKNOWN_SYSTEM_VARIABLE => {
+                Err(DriverError::ConnectionMemoryLimitNotSupported {
+                    server_error: Some(server_error),
+                }
+                .into())
+            }
+            result => result,
+        }
+    }
+
+    /// Returns a future that resolves to [`Conn`].
+    pub fn new<T: Into<Opts>>(opts: T) -> crate::BoxFuture<'static, Conn> {
+        Conn::new_with_init(opts.into(), true)
+    }
+
+    /// Same as [`Conn::new`], but `init` and `setup` commands (as well as the `on_connect`
+    /// function) will only run if `init` is `true`
+    /// (see [`PoolOpts::with_reinit_on_reconnect`](crate::PoolOpts::with_reinit_on_reconnect)).
+    pub(crate) fn new_with_init(opts: Opts, init: bool) -> crate::BoxFuture<'static, Conn> {
+        async move {
+            let mut tracer = ConnectTracer::new();
+            let result = Conn::connect(opts.clone(), &mut tracer, init).await;
+
+            if let Some(hook) = opts.connect_trace_hook() {
+                let outcome = match &result {
+                    Ok(_) => ConnectOutcome::Connected,
+                    Err(err) => ConnectOutcome::Failed(err.to_string()),
+                };
+                hook.call(&tracer.finish(outcome));
+            }
+
+            result
+        }
+        .boxed()
+    }
+
+    /// Establishes a connection recording phase timings into the given `tracer`.
+    async fn connect(opts: Opts, tracer: &mut ConnectTracer, init: bool) -> Result<Conn> {
+        let mut conn = Conn::empty(opts.clone());
+
+        let stream = if let Some(_path) = opts.socket() {
+            #[cfg(unix)]
+            {
+                tracer.start_phase();
+                let stream = Stream::connect_socket(_path.to_owned()).await?;
+                tracer.tcp = Some(tracer.phase_elapsed());
+                stream
+            }
+            #[cfg(not(unix))]
+            return Err(crate::DriverError::NamedPipesDisabled.into());
+        } else {
+            let keepalive = opts
+                .tcp_keepalive()
+                .map(|x| std::time::Duration::from_millis(x.into()));
+
+            let happy_eyeballs_delay = opts.happy_eyeballs_delay();
+
+            // hosts are tried in order until one of them accepts the connection
+            let address = Stream::resolve_srv(opts.hostport_or_url()).await?;
+            let mut candidates = address.candidates().into_iter().peekable();
+            loop {
+                let candidate = candidates.next().expect("at least one host");
+
+                let connect = if let Some(proxy) = opts.proxy() {
+                    // the proxy resolves the host name
+                    tracer.start_phase();
+                    Stream::connect_proxy(
+                        proxy,
+                        candidate.get_ip_or_hostname(),
+                        candidate.get_tcp_port(),
+                        keepalive,
+                    )
+                    .boxed()
+                } else {
+                    tracer.start_phase();
+                    let resolved = opts.addr_cache().resolve(
+                        &candidate,
+                        opts.resolve_on_reconnect(),
+                        Stream::resolve_tcp,
+                    );
+                    let addrs = match resolved.await {
+                        Ok(addrs) => addrs,
+                        Err(_) if candidates.peek().is_some() => continue,
+                        Err(err) => return Err(err.into()),
+                    };
+                    let mut addrs =
+                        order_addrs(addrs, opts.ip_preference(), happy_eyeballs_delay.is_some());
+                    tracer.dns = Some(tracer.phase_elapsed());
+
+                    if let Some(bind_address) = opts.bind_address() {
+                        // the local address can only be used with addresses of its IP family
+                        addrs.retain(|addr| addr.is_ipv4() == bind_address.is_ipv4());
+                        if addrs.is_empty() {
+                            if candidates.peek().is_some() {
+                                continue;
+                            }
+                            return Err(DriverError::BindAddressFamilyMismatch {
+                                bind_address,
+                                host: candidate.get_ip_or_hostname().into(),
+                            }
+                            .into());
+                        }
+                    }
+
+                    tracer.start_phase();
+                    let bind_address = opts.bind_address();
+                    async move {
+                        Stream::connect_tcp(&addrs, keepalive, happy_eyeballs_delay, bind_address)
+                            .await
+                    }
+                    .boxed()
+                };
+                let result = match opts.connect_timeout() {
+                    Some(timeout) => match tokio::time::timeout(timeout, connect).await {
+                        Ok(result) => result.map_err(Error::from),
+                        Err(_) => Err(DriverError::ConnectTimeout { timeout }.into()),
+                    },
+                    None => connect.await.map_err(Error::from),
+                };
+                match result {
+                    Ok(stream) => {
+                        tracer.tcp = Some(tracer.phase_elapsed());
+                        conn.inner.host = Some(candidate.get_ip_or_hostname().into());
+                        break stream;
+                    }
+                    Err(err) => {
+                        // the cached addresses might be stale
+                        opts.addr_cache().forget(&candidate);
+                        if candidates.peek().is_some() {
+                            continue;
+                        }
+                        return Err(err);
+                    }
+                }
+            }
+        };
+
+        conn.inner.stream = Some(stream);
+        conn.setup_stream()?;
+        conn.establish(tracer, init).await?;
+
+        Ok(conn)
+    }
+
+    /// Performs the handshake and initializes the connection over an established stream.
+    ///
+    /// `init` and setup commands are skipped unless `init` is `true`.
+    async fn establish(&mut self, tracer: &mut ConnectTracer, init: bool) -> Result<()> {
+        tracer.start_phase();
+        self.handle_handshake().await?;
+        let mut handshake = tracer.phase_elapsed();
+
+        if self
+            .inner
+            .opts
+            .get_capabilities()
+            .contains(CapabilityFlags::CLIENT_SSL)
+        {
+            tracer.start_phase();
+            self.switch_to_ssl_if_needed().await?;
+            tracer.tls = Some(tracer.phase_elapsed());
+        }
+
+        tracer.start_phase();
+        self.do_handshake_response().await?;
+        self.continue_auth().await?;
+        handshake += tracer.phase_elapsed();
+        tracer.handshake = Some(handshake);
+
+        self.switch_to_compression()?;
+        self.read_settings().await?;
+        self.reconnect_via_socket_if_needed(init).await?;
+        // `sql_mode` and `time_zone` are set before `init` commands and regardless of them
+        self.set_session_vars().await?;
+        if init {
+            self.run_init_commands().await?;
+            if let Some(on_connect) = self.inner.opts.on_connect().cloned() {
+                on_connect.call(self).await?;
+            }
+        }
+        // session settings are applied even if user-defined commands are skipped
+        self.run_setup_commands(init).await?;
+        if self.inner.opts.validate_on_connect() {
+            let query = self.inner.opts.validation_query().to_owned();
+            self.query_drop(query).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Performs the MySql handshake over a caller-provided stream instead of opening a socket.
+    ///
+    /// This decouples the transport from the protocol, e.g. to connect through a tunnel
+    /// or a custom transport, or to use an in-memory stream in tests. Network-related
+    /// options (host, port, socket, `prefer_socket`, keepalive etc.) are ignored, but
+    /// credentials, init/setup commands, compression and other protocol options apply.
+    ///
+    /// TLS is the caller's responsibility (i.e. the stream should already be encrypted, if
+    /// necessary), so `opts` with [`Opts::ssl_opts`] will be rejected with
+    /// [`DriverError::TlsOverCustomStream`].
+    ///
+    /// Note that the connection can't be checked for liveness, and pooled connections
+    /// are always opened by the pool itself, so this is for standalone connections only.
+    ///
+    /// ```
+    /// # use mysql_async::{prelude::*, test_misc::get_opts, Conn, Opts};
+    /// # #[tokio::main]
+    /// # async fn main() -> mysql_async::Result<()> {
+    /// # let opts = Opts::from(get_opts().ssl_opts(None::<mysql_async::SslOpts>));
+    /// // Any `AsyncRead + AsyncWrite` transport will do, e.g. a tunnel.
+    /// # let addr = (opts.ip_or_hostname(), opts.tcp_port());
+    /// let stream = tokio::net::TcpStream::connect(addr).await?;
+    /// let mut conn = Conn::from_stream(stream, opts).await?;
+    /// assert_eq!(conn.query_first::<u8, _>("SELECT 1").await?, Some(1));
+    /// # conn.disconnect().await }
+    /// ```
+    pub async fn from_stream<S, T>(stream: S, opts: T) -> Result<Conn>
+    where
+        S: AsyncRead + AsyncWrite + Send + Sync + 'static,
+        T: Into<Opts>,
+    {
+        let opts = opts.into();
+        if opts
+            .ssl_opts()
+            .is_some_and(|x| x.mode() != SslMode::Disabled)
+        {
+            return Err(DriverError::TlsOverCustomStream.into());
+        }
+
+        let mut conn = Conn::empty(opts);
+        conn.inner.stream = Some(Stream::from_custom(stream));
+        conn.establish(&mut ConnectTracer::new(), true).await?;
+
+        Ok(conn)
+    }
+
+    /// Returns a future that resolves to [`Conn`].
+    pub async fn from_url<T: AsRef<str>>(url: T) -> Result<Conn> {
+        Conn::new(Opts::from_str(url.as_ref())?).await
+    }
+
+    /// Will try to reconnect via socket using socket address in `self.inner.socket`.
+    ///
+    /// Won't try to reconnect if socket connection is already enforced in [`Opts`].
+    /// Failure is handled according to [`Opts::socket_fallback`].
+    async fn reconnect_via_socket_if_needed(&mut self, init: bool) -> Result<()> {
+        if self.inner.stream.as_ref().is_some_and(Stream::is_custom) {
+            // caller-provided transport must be kept
+            return Ok(());
+        }
+
+        if let Some(socket) = self.inner.socket.clone() {
+            let opts = self.inner.opts.clone();
+            if opts.socket().is_none() {
+                let fallback = opts.socket_fallback();
+                let opts = OptsBuilder::from_opts(opts).socket(Some(&*socket));
+                match Conn::new_with_init(opts.into(), init).await {
+                    Ok(conn) => {
+                        let old_conn = std::mem::replace(self, conn);
+                        // tidy up the old connection
+                        old_conn.close_conn().await?;
+                    }
+                    Err(error) => match fallback {
+                        SocketFallback::Warn => {
+                            #[cfg(feature = "tracing")]
+                            tracing::warn!(
+                                "unable to connect via socket `{}`, falling back to TCP: {}",
+                                socket,
+                                error
+                            );
+                        }
+                        SocketFallback::Error => {
+                            return Err(DriverError::SocketUnavailable {
+                                socket,
+                                error: Box::new(error),
+                            }
+                            .into());
+                        }
+                        SocketFallback::Silent => (),
+                    },
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Replaces this connection with a new one if it has been idle for longer than
+    /// `wait_timeout` (see [`Opts::reconnect_on_wait_timeout`]).
+    ///
+    /// Must only be called before a command is sent.
+    pub(crate) async fn reconnect_if_idle_killed(&mut self) -> Result<()> {
+        let wait_timeout = self.inner.wait_timeout;
+        if !self.inner.opts.reconnect_on_wait_timeout()
+            || wait_timeout.is_zero()
+            || self.idling() <= wait_timeout
+            || self.in_transaction()
+            || self.inner.session_dirty
+            || !matches!(self.inner.pending_result, Ok(None))
+            || self.inner.stream.as_ref().map_or(true, Stream::is_custom)
+        {
+            return Ok(());
+        }
+
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            "connection {} was idle for {:?} (wait_timeout is {:?}), reconnecting",
+            self.id(),
+            self.idling(),
+            wait_timeout
+        );
+
+        let conn = Conn::new_with_init(self.inner.opts.clone(), true).await?;
+        let mut old_conn = std::mem::replace(self, conn);
+
+        // the new connection takes the place of the old one
+        self.inner.pool = old_conn.inner.pool.take();
+        self.inner.reset_upon_returning_to_a_pool = old_conn.inner.reset_upon_returning_to_a_pool;
+        self.inner.label = old_conn.inner.label.take();
+        self.inner.infile_handler = old_conn.inner.infile_handler.take();
+        self.inner.query_cache = old_conn.inner.query_cache.take();
+        if let Some(ref pool) = self.inner.pool {
+            pool.replace_connection(old_conn.id(), self.id());
+        }
+
+        // the server has most likely closed the old connection already
+        old_conn.inner.disconnected = true;
+        if old_conn.inner.stream.is_some() {
+            let _ = old_conn.take_stream().close().await;
+        }
+
         Ok(())
     }
 
@@ -1077,6 +2170,7 @@ impl Conn {
     ///   already in [`Opts`] or if `prefer_socket` is `false`.
     ///
     /// * It reads and stores `max_allowed_packet` in the connection unless it's already in [`Opts`]
+    ///   (server value is also cached if [`Opts::load_server_max_allowed_packet`] is `true`)
     ///
     /// * It reads and stores `wait_timeout` in the connection unless it's already in [`Opts`]
     ///
@@ -1109,6 +2203,7 @@ impl Conn {
         enum Cfg {
             Socket,
             MaxAllowedPacket,
+            ServerMaxAllowedPacket,
             WaitTimeout,
         }
 
@@ -1116,7 +2211,7 @@ impl Conn {
             const fn name(&self) -> &'static str {
                 match self {
                     Self::Socket => "@@socket",
-                    Self::MaxAllowedPacket => "@@max_allowed_packet",
+                    Self::MaxAllowedPacket | Self::ServerMaxAllowedPacket => "@@max_allowed_packet",
                     Self::WaitTimeout => "@@wait_timeout",
                 }
             }
@@ -1127,14 +2222,18 @@ impl Conn {
                         conn.inner.socket = value.and_then(crate::from_value);
                     }
                     Cfg::MaxAllowedPacket => {
+                        conn.inner.server_max_allowed_packet = value.and_then(crate::from_value);
                         if let Some(stream) = conn.inner.stream.as_mut() {
                             stream.set_max_allowed_packet(
-                                value
-                                    .and_then(crate::from_value)
+                                conn.inner
+                                    .server_max_allowed_packet
                                     .unwrap_or(DEFAULT_MAX_ALLOWED_PACKET),
                             );
                         }
                     }
+                    Cfg::ServerMaxAllowedPacket => {
+                        conn.inner.server_max_allowed_packet = value.and_then(crate::from_value);
+                    }
                     Cfg::WaitTimeout => {
                         conn.inner.wait_timeout = Duration::from_secs(
                             value
@@ -1159,7 +2258,16 @@ impl Conn {
             },
         ];
 
-        if self.inner.opts.prefer_socket() && self.inner.socket.is_none() {
+        if self.opts().max_allowed_packet().is_some()
+            && self.opts().load_server_max_allowed_packet()
+        {
+            actions.push(Action::Load(Cfg::ServerMaxAllowedPacket))
+        }
+
+        if self.inner.opts.prefer_socket()
+            && self.inner.opts.proxy().is_none()
+            && self.inner.socket.is_none()
+        {
             actions.push(Action::Load(Cfg::Socket))
         }
 
@@ -1224,7 +2332,8 @@ impl Conn {
     /// Executes [`COM_RESET_CONNECTION`][1].
     ///
     /// Returns `false` if command is not supported (requires MySql >5.7.2, MariaDb >10.2.3).
-    /// For older versions consider using [`Conn::change_user`].
+    /// For older versions consider using [`Conn::change_user`] (or [`Conn::reset_using`]
+    /// that falls back to it).
     ///
     /// [1]: https://dev.mysql.com/doc/c-api/5.7/en/mysql-reset-connection.html
     pub async fn reset(&mut self) -> Result<bool> {
@@ -1237,14 +2346,46 @@ impl Conn {
 
         if supports_com_reset_connection {
             self.routine(routines::ResetRoutine).await?;
+            self.reset_charset();
             self.inner.stmt_cache.clear();
             self.inner.infile_handler = None;
-            self.run_setup_commands().await?;
+            self.inner.query_cache = None;
+            self.set_session_vars().await?;
+            self.run_setup_commands(true).await?;
         }
 
         Ok(supports_com_reset_connection)
     }
 
+    /// Resets the connection using the given [`ResetKind`].
+    ///
+    /// [`ResetKind::ResetConnection`] falls back to [`ResetKind::ChangeUser`]
+    /// if `COM_RESET_CONNECTION` is not supported by the server. Returns the kind
+    /// that was actually used. See [`ResetKind`] for the list of things that get reset.
+    ///
+    /// Connection options are left intact and setup commands (see [`Opts::setup`]) are executed
+    /// after the reset.
+    ///
+    /// ```
+    /// # use mysql_async::{prelude::*, test_misc::get_opts, Conn, ResetKind};
+    /// # #[tokio::main]
+    /// # async fn main() -> mysql_async::Result<()> {
+    /// # let mut conn = Conn::new(get_opts()).await?;
+    /// conn.query_drop("SET @foo = 42").await?;
+    /// let used = conn.reset_using(ResetKind::ResetConnection).await?;
+    /// # let _ = used;
+    /// let foo: Option<u8> = conn.query_first("SELECT @foo").await?.unwrap();
+    /// assert_eq!(foo, None);
+    /// # conn.disconnect().await }
+    /// ```
+    pub async fn reset_using(&mut self, kind: ResetKind) -> Result<ResetKind> {
+        if kind == ResetKind::ResetConnection && self.reset().await? {
+            return Ok(ResetKind::ResetConnection);
+        }
+        self.change_user(Default::default()).await?;
+        Ok(ResetKind::ChangeUser)
+    }
+
     /// Executes [`COM_CHANGE_USER`][1].
     ///
     /// This might be used as an older and slower alternative to `COM_RESET_CONNECTION` that
@@ -1271,7 +2412,7 @@ impl Conn {
             };
             if opts_changed {
                 if let Some(pool) = self.inner.pool.take() {
-                    pool.cancel_connection();
+                    pool.close_connection(self.id());
                 }
             }
         }
@@ -1279,25 +2420,244 @@ impl Conn {
         let conn_opts = &mut self.inner.opts;
         opts.update_opts(conn_opts);
         self.routine(routines::ChangeUser).await?;
+        self.reset_charset();
         self.inner.stmt_cache.clear();
         self.inner.infile_handler = None;
-        self.run_setup_commands().await?;
+        self.inner.query_cache = None;
+        self.set_session_vars().await?;
+        self.run_setup_commands(true).await?;
         Ok(())
     }
 
+    /// Returns replication status of the server, or `None` if the server is not a replica.
+    ///
+    /// Executes `SHOW REPLICA STATUS` (`SHOW SLAVE STATUS` on MySql prior to 8.0.22
+    /// and MariaDb prior to 10.5.1). Requires `REPLICATION CLIENT` privilege (`SLAVE MONITOR`
+    /// on MariaDb 10.5.9+). For multi-source replicas the status of the first channel
+    /// is returned.
+    ///
+    /// Useful as a health check for replicas in a read/write splitting setup:
+    ///
+    /// ```
+    /// # use mysql_async::{test_misc::get_opts, Conn};
+    /// # use std::time::Duration;
+    /// # #[tokio::main]
+    /// # async fn main() -> mysql_async::Result<()> {
+    /// # let mut conn = Conn::new(get_opts()).await?;
+    /// let usable_for_reads = match conn.replica_status().await? {
+    ///     Some(status) => status
+    ///         .seconds_behind_source
+    ///         .map(|lag| lag < Duration::from_secs(5))
+    ///         .unwrap_or(false),
+    ///     None => true, // not a replica
+    /// };
+    /// # let _ = usable_for_reads;
+    /// # conn.disconnect().await }
+    /// ```
+    pub async fn replica_status(&mut self) -> Result<Option<ReplicaStatus>> {
+        let supports_show_replica_status = if self.inner.is_mariadb {
+            self.inner.version >= (10, 5, 1)
+        } else {
+            self.inner.version >= (8, 0, 22)
+        };
+
+        let query = if supports_show_replica_status {
+            "SHOW REPLICA STATUS"
+        } else {
+            "SHOW SLAVE STATUS"
+        };
+
+        let row: Option<Row> = self.query_first(query).await?;
+        Ok(row.as_ref().map(ReplicaStatus::from_row))
+    }
+
+    /// Returns the output of `SHOW ENGINE INNODB STATUS`.
+    ///
+    /// The text is large and meant for humans, but it's the only place that describes
+    /// the latest deadlock (see [`InnodbDeadlock::parse`](crate::InnodbDeadlock::parse)).
+    /// Requires the `PROCESS` privilege.
+    ///
+    /// Invalid UTF-8 (e.g. binary query text echoed in the deadlock section) is replaced
+    /// with `U+FFFD`.
+    ///
+    /// ```
+    /// # use mysql_async::{test_misc::get_opts, Conn};
+    /// # #[tokio::main]
+    /// # async fn main() -> mysql_async::Result<()> {
+    /// # let mut conn = Conn::new(get_opts()).await?;
+    /// let status = conn.innodb_status().await?;
+    /// assert!(status.contains("INNODB MONITOR OUTPUT"));
+    /// # conn.disconnect().await }
+    /// ```
+    pub async fn innodb_status(&mut self) -> Result<String> {
+        // columns are `Type`, `Name` and `Status`; the status may echo non-UTF-8 query text
+        let row: Option<(Vec<u8>, Vec<u8>, Vec<u8>)> =
+            self.query_first("SHOW ENGINE INNODB STATUS").await?;
+        Ok(row
+            .map(|(_, _, status)| String::from_utf8_lossy(&status).into_owned())
+            .unwrap_or_default())
+    }
+
+    /// Returns the list of threads running within the server (`SHOW FULL PROCESSLIST`).
+    ///
+    /// Threads of other users are only visible with the `PROCESS` privilege.
+    /// [`ProcessInfo::info`](crate::ProcessInfo::info) contains the full text
+    /// of the current statement (it's not truncated).
+    ///
+    /// ```
+    /// # use mysql_async::{test_misc::get_opts, Conn};
+    /// # #[tokio::main]
+    /// # async fn main() -> mysql_async::Result<()> {
+    /// # let mut conn = Conn::new(get_opts()).await?;
+    /// let long_running = conn
+    ///     .process_list()
+    ///     .await?
+    ///     .into_iter()
+    ///     .filter(|p| p.command == "Query" && p.time.as_secs() > 60)
+    ///     .map(|p| p.id)
+    ///     .collect::<Vec<_>>();
+    /// # let _ = long_running;
+    /// # conn.disconnect().await }
+    /// ```
+    pub async fn process_list(&mut self) -> Result<Vec<ProcessInfo>> {
+        let rows: Vec<Row> = self.query("SHOW FULL PROCESSLIST").await?;
+        Ok(rows.iter().map(ProcessInfo::from_row).collect())
+    }
+
+    /// Returns definitions of columns of the given table ordered by their position
+    /// (see [`ColumnDef`](crate::ColumnDef)).
+    ///
+    /// Uses a parameterized query to `information_schema.COLUMNS`, so the result is empty
+    /// if there is no such table (or it's not visible to the current user). Note that temporary
+    /// tables are not listed in `information_schema`.
+    ///
+    /// ```
+    /// # use mysql_async::{test_misc::get_opts, Conn};
+    /// # #[tokio::main]
+    /// # async fn main() -> mysql_async::Result<()> {
+    /// # let mut conn = Conn::new(get_opts()).await?;
+    /// let columns = conn.table_columns("mysql", "db").await?;
+    /// assert!(columns.iter().any(|c| c.name.eq_ignore_ascii_case("host") && c.is_primary_key()));
+    /// # conn.disconnect().await }
+    /// ```
+    pub async fn table_columns(&mut self, schema: &str, table: &str) -> Result<Vec<ColumnDef>> {
+        let rows: Vec<TableColumnsRow> = self.exec(TABLE_COLUMNS_QUERY, (schema, table)).await?;
+        let is_mariadb = self.inner.is_mariadb;
+        Ok(rows
+            .into_iter()
+            .map(|row| ColumnDef::from_row(row, is_mariadb))
+            .collect())
+    }
+
+    /// Runs `CHECK TABLE` for the given tables (see [`TableMaintenanceMessage`]).
+    ///
+    /// A table name might be qualified with a schema (`db.tbl`). Names are quoted, so a name
+    /// that contains a dot must be qualified. Doesn't run anything if `tables` is empty.
+    ///
+    /// ```
+    /// # use mysql_async::{test_misc::get_opts, Conn};
+    /// # #[tokio::main]
+    /// # async fn main() -> mysql_async::Result<()> {
+    /// # let mut conn = Conn::new(get_opts()).await?;
+    /// let messages = conn.check_tables(["mysql.db"]).await?;
+    /// let corrupted = messages.iter().any(|m| m.is_error());
+    /// # assert!(!corrupted);
+    /// # conn.disconnect().await }
+    /// ```
+    pub async fn check_tables<I, T>(&mut self, tables: I) -> Result<Vec<TableMaintenanceMessage>>
+    where
+        I: IntoIterator<Item = T>,
+        T: AsRef<str>,
+    {
+        self.maintain_tables("CHECK", tables).await
+    }
+
+    /// Runs `OPTIMIZE TABLE` for the given tables (see [`Conn::check_tables`]).
+    pub async fn optimize_tables<I, T>(&mut self, tables: I) -> Result<Vec<TableMaintenanceMessage>>
+    where
+        I: IntoIterator<Item = T>,
+        T: AsRef<str>,
+    {
+        self.maintain_tables("OPTIMIZE", tables).await
+    }
+
+    /// Runs `ANALYZE TABLE` for the given tables (see [`Conn::check_tables`]).
+    pub async fn analyze_tables<I, T>(&mut self, tables: I) -> Result<Vec<TableMaintenanceMessage>>
+    where
+        I: IntoIterator<Item = T>,
+        T: AsRef<str>,
+    {
+        self.maintain_tables("ANALYZE", tables).await
+    }
+
+    async fn maintain_tables<I, T>(
+        &mut self,
+        statement: &str,
+        tables: I,
+    ) -> Result<Vec<TableMaintenanceMessage>>
+    where
+        I: IntoIterator<Item = T>,
+        T: AsRef<str>,
+    {
+        match table_maintenance_query(statement, tables) {
+            Some(query) => {
+                let rows: Vec<TableMaintenanceRow> = self.query(query).await?;
+                Ok(rows.into_iter().map(From::from).collect())
+            }
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// Returns `true` if the server supports `INSERT ... RETURNING` (MariaDb 10.5.0+).
+    pub fn supports_insert_returning(&self) -> bool {
+        self.inner.is_mariadb && self.inner.version >= (10, 5, 0)
+    }
+
+    /// Executes an `INSERT ... RETURNING` (or `REPLACE ... RETURNING`) statement
+    /// and collects the returned rows.
+    ///
+    /// It's the same as [`Queryable::exec`], except that it fails
+    /// with [`DriverError::InsertReturningNotSupported`] without sending anything,
+    /// if the server doesn't support the syntax (see [`Conn::supports_insert_returning`]).
+    ///
+    /// ```
+    /// # use mysql_async::{prelude::*, test_misc::get_opts, Conn};
+    /// # #[tokio::main]
+    /// # async fn main() -> mysql_async::Result<()> {
+    /// # let mut conn = Conn::new(get_opts()).await?;
+    /// # if !conn.supports_insert_returning() { return conn.disconnect().await; }
+    /// conn.query_drop("CREATE TEMPORARY TABLE tmp (id INT PRIMARY KEY AUTO_INCREMENT, name TEXT)")
+    ///     .await?;
+    /// let ids: Vec<u32> = conn
+    ///     .exec_returning("INSERT INTO tmp (name) VALUES (?), (?) RETURNING id", ("a", "b"))
+    ///     .await?;
+    /// assert_eq!(ids, [1, 2]);
+    /// # conn.disconnect().await }
+    /// ```
+    pub async fn exec_returning<T, S, P>(&mut self, stmt: S, params: P) -> Result<Vec<T>>
+    where
+        S: StatementLike,
+        P: Into<crate::Params> + Send,
+        T: FromRow + Send + 'static,
+    {
+        if !self.supports_insert_returning() {
+            return Err(DriverError::InsertReturningNotSupported.into());
+        }
+        self.exec(stmt, params).await
+    }
+
     /// Resets the connection upon returning it to a pool.
     ///
-    /// Will invoke `COM_CHANGE_USER` if `COM_RESET_CONNECTION` is not supported.
+    /// Uses [`PoolOpts::reset_kind`](crate::PoolOpts::reset_kind) (see [`Conn::reset_using`]).
     async fn reset_for_pool(mut self) -> Result<Self> {
-        if !self.reset().await? {
-            self.change_user(Default::default()).await?;
-        }
+        let kind = self.inner.opts.pool_opts().reset_kind();
+        self.reset_using(kind).await?;
         Ok(self)
     }
 
-    /// Requires that `self.inner.tx_status != TxStatus::None`
+    /// Requires that [`Conn::in_transaction`] is `true`.
     pub(crate) async fn rollback_transaction(&mut self) -> Result<()> {
-        debug_assert_ne!(self.inner.tx_status, TxStatus::None);
+        debug_assert!(self.in_transaction());
         self.inner.tx_status = TxStatus::None;
         self.query_drop("ROLLBACK").await
     }
@@ -1315,7 +2675,7 @@ impl Conn {
     /// Requires that there are no other references to the pending result.
     pub(crate) async fn drop_result(&mut self) -> Result<()> {
         // Map everything into `PendingResult::Pending`
-        let meta = match self.set_pending_result(None)? {
+        let meta = match replace(&mut self.inner.pending_result, Ok(None))? {
             Some(PendingResult::Pending(meta)) => Some(meta),
             Some(PendingResult::Taken(meta)) => {
                 // This also asserts that there is only one reference left to the taken ResultSetMeta,
@@ -1349,10 +2709,17 @@ impl Conn {
     ///
     /// The purpose of this function, is to cleanup the connection while returning it to a [`Pool`].
     async fn cleanup_for_pool(mut self) -> Result<Self> {
+        let mut rolled_back = false;
         loop {
             let result = if self.has_pending_result() {
                 self.drop_result().await
-            } else if self.inner.tx_status != TxStatus::None {
+            } else if self.in_transaction() {
+                if rolled_back {
+                    // The server still reports an active transaction,
+                    // so the connection shouldn't return to a pool.
+                    return Err(DriverError::TransactionNotRolledBack.into());
+                }
+                rolled_back = true;
                 self.rollback_transaction().await
             } else {
                 break;
@@ -1373,6 +2740,34 @@ impl Conn {
     }
 }
 
+/// Parses `(matched, changed)` out of the info string of an `UPDATE` statement.
+fn parse_update_info(info: &str) -> Option<(u64, u64)> {
+    let rest = info.strip_prefix("Rows matched: ")?;
+    let (matched, rest) = rest.split_once(' ')?;
+    let rest = rest.trim_start().strip_prefix("Changed: ")?;
+    let changed = rest.split(' ').next()?;
+    Some((matched.parse().ok()?, changed.parse().ok()?))
+}
+
+/// Parses MariaDB extended capabilities out of the initial handshake packet
+/// (the last four bytes of its reserved area).
+///
+/// Returns `0` if the packet is too short.
+fn mariadb_ext_capabilities(packet: &[u8]) -> u32 {
+    // protocol version (1) is followed by the NUL-terminated server version
+    let version_len = match packet.get(1..).and_then(|x| x.iter().position(|&b| b == 0)) {
+        Some(len) => len,
+        None => return 0,
+    };
+    // connection id (4), scramble (8), filler (1), capabilities (2), charset (1), status (2),
+    // upper capabilities (2), scramble length (1) and the first six reserved bytes
+    let offset = 1 + version_len + 1 + 4 + 8 + 1 + 2 + 1 + 2 + 2 + 1 + 6;
+    packet
+        .get(offset..offset + 4)
+        .map(|x| u32::from_le_bytes([x[0], x[1], x[2], x[3]]))
+        .unwrap_or(0)
+}
+
 #[cfg(test)]
 mod test {
     use bytes::Bytes;
@@ -1384,8 +2779,9 @@ mod test {
     use mysql_common::constants::CapabilityFlags;
 
     use crate::{
-        from_row, params, prelude::*, test_misc::get_opts, ChangeUserOpts, Conn, Error,
-        OptsBuilder, Pool, ServerError, TxOpts, Value, WhiteListFsHandler,
+        from_row, params, prelude::*, test_misc::get_opts, ChangeUserOpts, Conn, DriverError,
+        Error, Opts, OptsBuilder, Pool, ResetKind, Row, ServerError, SslOpts, TxOpts, Value,
+        WhiteListFsHandler,
     };
 
     #[tokio::test]
@@ -1440,6 +2836,124 @@ mod test {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn should_return_matched_and_changed_rows() -> super::Result<()> {
+        for client_found_rows in [false, true] {
+            let opts = get_opts().client_found_rows(client_found_rows);
+            let mut conn = Conn::new(opts).await?;
+
+            conn.query_drop("CREATE TEMPORARY TABLE tmp (id INT PRIMARY KEY, val INT)")
+                .await?;
+            conn.query_drop("INSERT INTO tmp VALUES (1, 1), (2, 1), (3, 2)")
+                .await?;
+            assert_eq!(conn.matched_rows(), None);
+            assert_eq!(conn.changed_rows(), None);
+
+            let result = conn
+                .query_iter("UPDATE tmp SET val = 1 WHERE val IN (1, 2)")
+                .await?;
+            assert_eq!(result.matched_rows(), Some(3));
+            assert_eq!(result.changed_rows(), Some(1));
+            drop(result);
+
+            assert_eq!(conn.matched_rows(), Some(3));
+            assert_eq!(conn.changed_rows(), Some(1));
+            let affected = if client_found_rows { 3 } else { 1 };
+            assert_eq!(conn.affected_rows(), affected);
+
+            conn.exec_drop("UPDATE tmp SET val = ? WHERE id > ?", (3, 5))
+                .await?;
+            assert_eq!(conn.matched_rows(), Some(0));
+            assert_eq!(conn.changed_rows(), Some(0));
+
+            conn.query_drop("DELETE FROM tmp").await?;
+            assert_eq!(conn.affected_rows(), 3);
+            assert_eq!(conn.matched_rows(), None);
+            assert_eq!(conn.changed_rows(), None);
+
+            conn.disconnect().await?;
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn should_serve_query_from_cache() -> super::Result<()> {
+        use std::{
+            collections::HashMap,
+            sync::{Arc, Mutex},
+        };
+
+        use crate::{QueryCache, QueryCacheKey};
+
+        #[derive(Default)]
+        struct MemoryCache(Mutex<HashMap<QueryCacheKey, Vec<Row>>>);
+
+        impl QueryCache for MemoryCache {
+            fn get(&self, key: &QueryCacheKey) -> Option<Vec<Row>> {
+                self.0.lock().unwrap().get(key).cloned()
+            }
+
+            fn put(&self, key: QueryCacheKey, rows: Vec<Row>) {
+                self.0.lock().unwrap().insert(key, rows);
+            }
+        }
+
+        let mut conn = Conn::new(get_opts()).await?;
+        let cache = Arc::new(MemoryCache::default());
+        conn.set_query_cache(Some(cache.clone()));
+
+        // `RAND()` differs on every execution, so equal results come from the cache
+        let first: Vec<f64> = conn.query_cached("SELECT RAND()").await?;
+        let second: Vec<f64> = conn.query_cached("  SELECT\n RAND() ;").await?;
+        assert_eq!(first.len(), 1);
+        assert_eq!(first, second);
+
+        let first: Vec<f64> = conn.exec_cached("SELECT RAND() + ?", (1,)).await?;
+        let second: Vec<f64> = conn.exec_cached("SELECT RAND() + ?", (1,)).await?;
+        let other: Vec<f64> = conn.exec_cached("SELECT RAND() + ?", (2,)).await?;
+        assert_eq!(first, second);
+        assert_ne!(first, other);
+        assert_eq!(cache.0.lock().unwrap().len(), 3);
+
+        // cached rows are converted as usual
+        let rows: Vec<(String, u8)> = conn.exec_cached("SELECT ?, 1", ("foo",)).await?;
+        let cached: Vec<(String, u8)> = conn.exec_cached("SELECT ?, 1", ("foo",)).await?;
+        assert_eq!(rows, cached);
+        assert_eq!(rows, [("foo".to_owned(), 1)]);
+
+        // every query hits the server without a cache
+        conn.set_query_cache(None);
+        let first: Vec<f64> = conn.query_cached("SELECT RAND()").await?;
+        let second: Vec<f64> = conn.query_cached("SELECT RAND()").await?;
+        assert_ne!(first, second);
+
+        // reset removes the cache
+        conn.set_query_cache(Some(cache.clone()));
+        if conn.reset().await? {
+            assert!(conn.inner.query_cache.is_none());
+        }
+
+        conn.disconnect().await
+    }
+
+    #[test]
+    fn should_parse_update_info() {
+        use super::parse_update_info;
+
+        assert_eq!(
+            parse_update_info("Rows matched: 3  Changed: 1  Warnings: 0"),
+            Some((3, 1))
+        );
+        assert_eq!(
+            parse_update_info("Rows matched: 18446744073709551615  Changed: 0  Warnings: 2"),
+            Some((u64::MAX, 0))
+        );
+        assert!(parse_update_info("Records: 2  Duplicates: 0  Warnings: 0").is_none());
+        assert!(parse_update_info("Rows matched: x  Changed: 1  Warnings: 0").is_none());
+        assert_eq!(parse_update_info(""), None);
+    }
+
     #[test]
     fn opts_should_satisfy_send_and_sync() {
         struct A<T: Sync + Send>(T);
@@ -1492,31 +3006,983 @@ mod test {
     }
 
     #[tokio::test]
-    async fn should_connect() -> super::Result<()> {
-        let mut conn: Conn = Conn::new(get_opts()).await?;
-        conn.ping().await?;
-        let plugins: Vec<String> = conn
-            .query_map("SHOW PLUGINS", |mut row: crate::Row| {
-                row.take("Name").unwrap()
-            })
-            .await?;
-
-        // Should connect with any combination of supported plugin and empty-nonempty password.
-        let variants = vec![
-            ("caching_sha2_password", 2_u8, "non-empty"),
-            ("caching_sha2_password", 2_u8, ""),
-            ("mysql_native_password", 0_u8, "non-empty"),
-            ("mysql_native_password", 0_u8, ""),
-        ]
-        .into_iter()
-        .filter(|variant| plugins.iter().any(|p| p == variant.0));
+    async fn should_report_connect_trace() -> super::Result<()> {
+        use std::{
+            sync::{Arc, Mutex},
+            time::Duration,
+        };
 
-        for (plug, val, pass) in variants {
-            dbg!((plug, val, pass, conn.inner.version));
+        let traces: Arc<Mutex<Vec<crate::ConnectTrace>>> = Default::default();
+        let hook = {
+            let traces = traces.clone();
+            move |trace: &crate::ConnectTrace| traces.lock().unwrap().push(trace.clone())
+        };
 
-            if plug == "mysql_native_password" && conn.inner.version >= (8, 4, 0) {
-                continue;
-            }
+        let opts = get_opts()
+            .prefer_socket(false)
+            .connect_trace_hook(Some(hook));
+        let conn = Conn::new(opts.clone()).await?;
+
+        {
+            let traces = traces.lock().unwrap();
+            assert_eq!(traces.len(), 1);
+            let trace = &traces[0];
+            assert_eq!(trace.outcome, crate::ConnectOutcome::Connected);
+            let phases = [trace.dns, trace.tcp, trace.handshake];
+            assert!(phases.iter().all(Option::is_some));
+            assert!(trace.handshake.unwrap() > Duration::ZERO);
+            assert_eq!(
+                trace.tls.is_some(),
+                crate::Opts::from(opts.clone()).ssl_opts().is_some()
+            );
+            let sum: Duration =
+                phases.iter().flatten().sum::<Duration>() + trace.tls.unwrap_or_default();
+            assert!(trace.total >= sum);
+        }
+        conn.disconnect().await?;
+
+        let opts = opts.pass(Some("definitely the wrong password"));
+        assert!(Conn::new(opts).await.is_err());
+
+        let traces = traces.lock().unwrap();
+        assert_eq!(traces.len(), 2);
+        assert!(!traces[1].outcome.is_connected());
+        assert!(traces[1].tcp.is_some());
+
+        Ok(())
+    }
+
+    #[cfg(feature = "rustls-tls")]
+    #[tokio::test]
+    async fn should_resume_tls_session() -> super::Result<()> {
+        let opts = Opts::from(get_opts());
+        let ssl_opts = match opts.ssl_opts() {
+            Some(ssl_opts) => ssl_opts.clone(),
+            None => return Ok(()),
+        };
+
+        let mut conn = Conn::new(opts.clone()).await?;
+        assert!(!conn.is_tls_resumed());
+        // session tickets are only sent by servers that support them
+        let cache_mode: Option<String> = conn
+            .query_first("SELECT @@ssl_session_cache_mode")
+            .await
+            .unwrap_or_default();
+        conn.disconnect().await?;
+        if !matches!(cache_mode.as_deref(), Some("ON" | "1")) {
+            return Ok(());
+        }
+
+        // connections share the session cache of the connector
+        let conn = Conn::new(opts.clone()).await?;
+        assert!(conn.is_tls_resumed());
+        conn.disconnect().await?;
+
+        let opts = Opts::from(
+            OptsBuilder::from_opts(opts).ssl_opts(ssl_opts.with_disable_session_resumption(true)),
+        );
+        for _ in 0..2 {
+            let conn = Conn::new(opts.clone()).await?;
+            assert!(!conn.is_tls_resumed());
+            conn.disconnect().await?;
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn should_request_auth_plugin() -> super::Result<()> {
+        for plugin in ["mysql_native_password", "caching_sha2_password"] {
+            // the server switches to the plugin of the account if it differs
+            let mut conn = Conn::new(get_opts().auth_plugin(Some(plugin))).await?;
+            assert_eq!(conn.query_first("SELECT 1").await?, Some(1_u8));
+            conn.disconnect().await?;
+        }
+
+        let err = Conn::new(get_opts().auth_plugin(Some("dialog")))
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            Error::Driver(DriverError::UnknownAuthPlugin { ref name }) if name == "dialog"
+        ));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn should_cache_server_max_allowed_packet() -> super::Result<()> {
+        // loaded by default since it's not given in options
+        let mut conn = Conn::new(get_opts()).await?;
+        let expected: Option<usize> = conn.query_first("SELECT @@max_allowed_packet").await?;
+        assert!(expected.is_some());
+        assert_eq!(conn.server_max_allowed_packet(), expected);
+        conn.disconnect().await?;
+
+        let opts = get_opts().max_allowed_packet(Some(1024 * 1024));
+        let conn = Conn::new(opts.clone()).await?;
+        assert_eq!(conn.server_max_allowed_packet(), None);
+        conn.disconnect().await?;
+
+        let conn = Conn::new(opts.load_server_max_allowed_packet(true)).await?;
+        assert_eq!(conn.server_max_allowed_packet(), expected);
+        conn.disconnect().await?;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn should_validate_new_connection() -> super::Result<()> {
+        let conn = Conn::new(get_opts().validate_on_connect(true)).await?;
+        conn.disconnect().await?;
+
+        let opts = get_opts()
+            .validate_on_connect(true)
+            .validation_query("SELECT * FROM mysql.no_such_table");
+        match Conn::new(opts.clone()).await {
+            Err(Error::Server(err)) => assert_eq!(err.code, 1146),
+            other => panic!("expected a server error, got {:?}", other),
+        }
+
+        // the query doesn't run unless enabled
+        let conn = Conn::new(opts.validate_on_connect(false)).await?;
+        conn.disconnect().await?;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn should_enforce_max_query_len() -> super::Result<()> {
+        let mut conn = Conn::new(get_opts().max_query_len(64)).await?;
+
+        let query = format!("SELECT '{}'", "x".repeat(64));
+        match conn.query_drop(&query).await {
+            Err(Error::Driver(DriverError::QueryTooLong { len, max: 64 })) => {
+                assert_eq!(len, query.len())
+            }
+            other => panic!("expected QueryTooLong, got {:?}", other),
+        }
+        match conn.prep(query.as_str()).await {
+            Err(Error::Driver(DriverError::QueryTooLong { .. })) => (),
+            other => panic!("expected QueryTooLong, got {:?}", other),
+        }
+
+        // nothing was sent, so the connection is still usable
+        assert_eq!(conn.query_first("SELECT 1").await?, Some(1));
+
+        conn.disconnect().await?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn should_enforce_max_param_len() -> super::Result<()> {
+        let mut conn = Conn::new(get_opts().max_param_len(16)).await?;
+
+        let result = conn
+            .exec_drop("SELECT ?, ?", ("short", vec![0_u8; 17]))
+            .await;
+        match result {
+            Err(Error::Driver(DriverError::ParamTooLong {
+                index: 1,
+                len: 17,
+                max: 16,
+            })) => (),
+            other => panic!("expected ParamTooLong, got {:?}", other),
+        }
+
+        let value: Option<String> = conn.exec_first("SELECT ?", ("x".repeat(16),)).await?;
+        assert_eq!(value, Some("x".repeat(16)));
+
+        conn.disconnect().await?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn should_record_last_command_duration() -> super::Result<()> {
+        const SLEEP: std::time::Duration = std::time::Duration::from_millis(200);
+
+        let mut conn = Conn::new(get_opts()).await?;
+
+        conn.query_drop("SELECT SLEEP(0.2)").await?;
+        let duration = conn.last_command_duration().unwrap();
+        assert!(duration >= SLEEP, "{:?}", duration);
+
+        conn.exec_drop("SELECT SLEEP(?)", (0.2,)).await?;
+        let duration = conn.last_command_duration().unwrap();
+        assert!(duration >= SLEEP, "{:?}", duration);
+
+        // the duration covers a result that is dropped without being read
+        let result = conn.query_iter("SELECT SLEEP(0.2)").await?;
+        result.drop_result().await?;
+        assert!(conn.last_command_duration().unwrap() >= SLEEP);
+
+        // failed commands are measured as well
+        conn.query_drop("SELECT * FROM mysql.no_such_table")
+            .await
+            .unwrap_err();
+        assert!(conn.last_command_duration().is_some());
+
+        conn.disconnect().await?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn should_time_out_read() -> super::Result<()> {
+        const TIMEOUT: std::time::Duration = std::time::Duration::from_millis(500);
+
+        let mut conn = Conn::new(get_opts().read_timeout(Some(TIMEOUT))).await?;
+        // fast queries aren't affected
+        conn.query_drop("SELECT 1").await?;
+
+        match conn.query_drop("DO SLEEP(3)").await {
+            Err(Error::Driver(DriverError::ReadTimeout { timeout })) => {
+                assert_eq!(timeout, TIMEOUT)
+            }
+            other => panic!("expected ReadTimeout, got {:?}", other),
+        }
+        assert!(conn.inner.disconnected);
+        assert!(conn.query_drop("SELECT 1").await.is_err());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn should_time_out_tcp_connect() -> super::Result<()> {
+        const TIMEOUT: std::time::Duration = std::time::Duration::from_millis(500);
+
+        // connection attempt will hang until the OS-level timeout
+        let (addr, _listener) =
+            crate::test_misc::unresponsive_listener(([127, 0, 0, 1], 0).into())?;
+        let opts = get_opts()
+            .ip_or_hostname(addr.ip().to_string())
+            .tcp_port(addr.port())
+            .prefer_socket(false)
+            .connect_timeout(Some(TIMEOUT));
+
+        let start = std::time::Instant::now();
+        match Conn::new(opts).await {
+            Err(Error::Driver(DriverError::ConnectTimeout { timeout })) => {
+                assert_eq!(timeout, TIMEOUT)
+            }
+            other => panic!("expected ConnectTimeout, got {:?}", other),
+        }
+        assert!(start.elapsed() < TIMEOUT * 4);
+
+        // the timeout doesn't affect reachable hosts
+        let conn = Conn::new(get_opts().connect_timeout(Some(TIMEOUT * 10))).await?;
+        conn.disconnect().await
+    }
+
+    #[tokio::test]
+    async fn should_override_session_wait_timeout() -> super::Result<()> {
+        let mut conn = Conn::new(get_opts().wait_timeout(Some(1234))).await?;
+        let wait_timeout: Option<usize> = conn.query_first("SELECT @@wait_timeout").await?;
+        assert_eq!(wait_timeout, Some(1234));
+        assert_eq!(
+            conn.inner.wait_timeout,
+            std::time::Duration::from_secs(1234)
+        );
+
+        // the override survives a reset
+        conn.query_drop("SET SESSION wait_timeout = 10").await?;
+        conn.reset().await?;
+        let wait_timeout: Option<usize> = conn.query_first("SELECT @@wait_timeout").await?;
+        assert_eq!(wait_timeout, Some(1234));
+        conn.disconnect().await?;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn should_reconnect_on_wait_timeout() -> super::Result<()> {
+        const IDLE: std::time::Duration = std::time::Duration::from_secs(3);
+
+        let opts = get_opts()
+            .wait_timeout(Some(1))
+            .init(vec!["SET @init_var = 42"]);
+
+        // idle-killed connection is broken by default
+        // (`wait_timeout` option sets the session variable)
+        let mut conn = Conn::new(opts.clone()).await?;
+        tokio::time::sleep(IDLE).await;
+        conn.query_drop("SELECT 1").await.unwrap_err();
+        drop(conn);
+
+        let opts = opts.reconnect_on_wait_timeout(true);
+        let mut conn = Conn::new(opts.clone()).await?;
+        let id = conn.id();
+
+        tokio::time::sleep(IDLE).await;
+        let init_var: Option<u8> = conn.query_first("SELECT @init_var").await?;
+        assert_eq!(init_var, Some(42));
+        assert_ne!(conn.id(), id);
+
+        // the same applies to prepared statements
+        let id = conn.id();
+
+        tokio::time::sleep(IDLE).await;
+        let value: Option<u8> = conn.exec_first("SELECT ?", (1,)).await?;
+        assert_eq!(value, Some(1));
+        assert_ne!(conn.id(), id);
+
+        // it never happens within a transaction
+        let mut tx = conn.start_transaction(Default::default()).await?;
+        tokio::time::sleep(IDLE).await;
+        tx.query_drop("SELECT 1").await.unwrap_err();
+        drop(tx);
+        drop(conn);
+
+        // including one started by raw SQL
+        let mut conn = Conn::new(opts.clone()).await?;
+        conn.query_drop("BEGIN").await?;
+        assert!(conn.in_transaction());
+        tokio::time::sleep(IDLE).await;
+        conn.query_drop("SELECT 1").await.unwrap_err();
+        drop(conn);
+
+        // nor after the session state has changed
+        let mut conn = Conn::new(opts).await?;
+        conn.query_drop("SET @user_var = 1").await?;
+        tokio::time::sleep(IDLE).await;
+        conn.query_drop("SELECT @user_var").await.unwrap_err();
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn should_get_replica_status() -> super::Result<()> {
+        // The test server is not expected to be a replica.
+        let mut conn = Conn::new(get_opts()).await?;
+        if let Some(status) = conn.replica_status().await? {
+            assert!(status.source_host.is_some());
+        }
+        conn.disconnect().await?;
+
+        // Use `REPLICA_DATABASE_URL` to point this test to a configured replica.
+        if let Ok(url) = std::env::var("REPLICA_DATABASE_URL") {
+            let mut conn = Conn::from_url(url).await?;
+            let status = conn.replica_status().await?.expect("not a replica");
+            assert!(status.io_running);
+            assert!(status.sql_running);
+            assert!(status.seconds_behind_source.is_some());
+            assert!(status.source_log_file.is_some());
+            assert!(status.exec_source_log_pos.is_some());
+            conn.disconnect().await?;
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn should_get_innodb_status() -> super::Result<()> {
+        let mut conn = Conn::new(get_opts()).await?;
+        let status = conn.innodb_status().await?;
+        assert!(status.contains("INNODB MONITOR OUTPUT"), "{}", status);
+        assert!(status.contains("TRANSACTIONS"));
+        // parsing never fails, whether there was a deadlock or not
+        let _ = crate::InnodbDeadlock::parse(&status);
+        conn.disconnect().await?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn should_get_process_list() -> super::Result<()> {
+        let mut conn = Conn::new(get_opts()).await?;
+        let mut other = Conn::new(get_opts()).await?;
+
+        let user: String = conn.query_first("SELECT CURRENT_USER()").await?.unwrap();
+        let user = user.split('@').next().unwrap().to_owned();
+        let list = conn.process_list().await?;
+
+        let me = list
+            .iter()
+            .find(|p| p.id == u64::from(conn.id()))
+            .expect("own connection is not in the process list");
+        assert_eq!(me.user, user);
+        assert_eq!(me.command, "Query");
+        assert_eq!(me.db.as_deref(), conn.opts().db_name());
+        assert!(!me.host.is_empty());
+        assert_eq!(me.info.as_deref(), Some("SHOW FULL PROCESSLIST"));
+
+        let other_info = list
+            .iter()
+            .find(|p| p.id == u64::from(other.id()))
+            .expect("other connection is not in the process list");
+        assert_eq!(other_info.command, "Sleep");
+        assert!(other_info.info.is_none());
+
+        other.disconnect().await?;
+        conn.disconnect().await
+    }
+
+    #[tokio::test]
+    async fn should_get_table_columns() -> super::Result<()> {
+        let mut conn = Conn::new(get_opts()).await?;
+        let schema: String = conn.query_first("SELECT DATABASE()").await?.unwrap();
+
+        conn.query_drop("DROP TABLE IF EXISTS table_columns_test")
+            .await?;
+        conn.query_drop(
+            r"CREATE TABLE table_columns_test (
+                id INT UNSIGNED NOT NULL AUTO_INCREMENT PRIMARY KEY,
+                name VARCHAR(64) CHARACTER SET utf8mb4 NOT NULL DEFAULT 'it''s',
+                score DOUBLE NULL,
+                code CHAR(3) CHARACTER SET latin1 COLLATE latin1_bin NOT NULL,
+                UNIQUE KEY (name)
+            )",
+        )
+        .await?;
+
+        let columns = conn.table_columns(&schema, "table_columns_test").await;
+        conn.query_drop("DROP TABLE table_columns_test").await?;
+        let columns = columns?;
+
+        let names = columns.iter().map(|c| &*c.name).collect::<Vec<_>>();
+        assert_eq!(names, ["id", "name", "score", "code"]);
+        let positions = columns.iter().map(|c| c.position).collect::<Vec<_>>();
+        assert_eq!(positions, [1, 2, 3, 4]);
+
+        let id = &columns[0];
+        assert_eq!(id.data_type, "int");
+        // display width is reported by older servers
+        assert!(id.column_type.starts_with("int") && id.column_type.ends_with("unsigned"));
+        assert!(!id.nullable);
+        assert_eq!(id.default, None);
+        assert!(id.is_primary_key());
+        assert!(id.is_auto_increment());
+        assert_eq!(id.character_set, None);
+
+        let name = &columns[1];
+        assert_eq!(name.data_type, "varchar");
+        assert_eq!(name.column_type, "varchar(64)");
+        assert!(!name.nullable);
+        assert_eq!(name.default.as_deref(), Some("it's"));
+        assert_eq!(name.key, "UNI");
+        assert!(!name.is_auto_increment());
+        assert_eq!(name.character_set.as_deref(), Some("utf8mb4"));
+
+        let score = &columns[2];
+        assert_eq!(score.data_type, "double");
+        assert!(score.nullable);
+        assert_eq!(score.default, None);
+        assert_eq!(score.key, "");
+        assert_eq!(score.character_set, None);
+
+        let code = &columns[3];
+        assert_eq!(code.column_type, "char(3)");
+        assert_eq!(code.character_set.as_deref(), Some("latin1"));
+        assert_eq!(code.collation.as_deref(), Some("latin1_bin"));
+
+        let missing = conn.table_columns(&schema, "no_such_table").await?;
+        assert!(missing.is_empty());
+
+        conn.disconnect().await
+    }
+
+    #[tokio::test]
+    async fn should_run_table_maintenance() -> super::Result<()> {
+        let mut conn = Conn::new(get_opts()).await?;
+
+        let messages = conn.analyze_tables(["mysql.db"]).await?;
+        assert!(!messages.is_empty());
+        for message in &messages {
+            assert_eq!(message.table, "mysql.db");
+            assert_eq!(message.op, "analyze");
+            assert!(!message.is_error(), "{:?}", message);
+        }
+        let status = messages.last().unwrap();
+        assert!(status.is_status());
+        assert!(
+            status.msg_text == "OK" || status.msg_text == "Table is already up to date",
+            "{:?}",
+            status
+        );
+
+        // errors are reported as messages, one row per message
+        let messages = conn
+            .check_tables(["mysql.db", "mysql.no_such_table"])
+            .await?;
+        assert!(messages
+            .iter()
+            .any(|m| m.table == "mysql.db" && m.is_status() && m.msg_text == "OK"));
+        assert!(messages
+            .iter()
+            .any(|m| m.table == "mysql.no_such_table" && m.is_error()));
+
+        assert!(conn.optimize_tables(Vec::<String>::new()).await?.is_empty());
+
+        conn.disconnect().await
+    }
+
+    #[tokio::test]
+    async fn should_read_rows_of_insert_returning() -> super::Result<()> {
+        let mut conn = Conn::new(get_opts()).await?;
+
+        if !conn.supports_insert_returning() {
+            let result = conn
+                .exec_returning::<u64, _, _>("INSERT INTO t VALUES (1) RETURNING id", ())
+                .await;
+            match result {
+                Err(Error::Driver(DriverError::InsertReturningNotSupported)) => (),
+                other => panic!("expected InsertReturningNotSupported, got {:?}", other),
+            }
+            return conn.disconnect().await;
+        }
+
+        conn.query_drop(
+            "CREATE TEMPORARY TABLE mysql.tmp_returning \
+                (id INT PRIMARY KEY AUTO_INCREMENT, name TEXT)",
+        )
+        .await?;
+
+        let rows: Vec<(u64, String)> = conn
+            .exec_returning(
+                "INSERT INTO mysql.tmp_returning (name) VALUES (?), (?) RETURNING id, name",
+                ("foo", "bar"),
+            )
+            .await?;
+        assert_eq!(rows, [(1, "foo".into()), (2, "bar".into())]);
+
+        // it's an ordinary result set, so it's readable via the text protocol as well
+        let id: Option<u64> = conn
+            .query_first("INSERT INTO mysql.tmp_returning (name) VALUES ('baz') RETURNING id")
+            .await?;
+        assert_eq!(id, Some(3));
+
+        conn.disconnect().await
+    }
+
+    #[tokio::test]
+    async fn should_get_version_comment_and_edition() -> super::Result<()> {
+        let mut conn = Conn::new(get_opts()).await?;
+        assert!(conn.inner.version_comment.is_none());
+
+        let expected: String = conn.query_first("SELECT @@version_comment").await?.unwrap();
+        assert_eq!(conn.version_comment().await?, expected);
+        assert_eq!(conn.inner.version_comment.as_deref(), Some(&*expected));
+        assert_eq!(conn.version_comment().await?, expected);
+
+        let edition = conn.server_edition().await?;
+        if conn.inner.is_mariadb {
+            assert_eq!(edition, crate::ServerEdition::MariaDb);
+        } else {
+            assert_eq!(
+                edition,
+                crate::ServerEdition::from_version_comment(&expected)
+            );
+        }
+
+        conn.disconnect().await
+    }
+
+    #[tokio::test]
+    async fn should_escape_like_pattern() -> super::Result<()> {
+        const TERMS: &[&str] = &["100%", "a_b", "c!d", "e\\f", "it's", "%_!\\"];
+
+        let mut conn = Conn::new(get_opts()).await?;
+        for sql_mode in ["''", "'NO_BACKSLASH_ESCAPES'"] {
+            conn.query_drop(format!("SET SESSION sql_mode = {}", sql_mode))
+                .await?;
+
+            for escape_char in ['!', '\\'] {
+                for term in TERMS {
+                    // metacharacters of the term must not act as wildcards
+                    let wildcard_match = term.replace(['%', '_'], "x");
+                    let query = format!(
+                        "SELECT '{}' LIKE '%{}%' ESCAPE '{}', '{}' LIKE '{}' ESCAPE '{}'",
+                        conn.escape_string(&format!("<{}>", term))?,
+                        conn.escape_like(term, escape_char)?,
+                        conn.escape_string(&escape_char.to_string())?,
+                        conn.escape_string(&wildcard_match)?,
+                        conn.escape_like(term, escape_char)?,
+                        conn.escape_string(&escape_char.to_string())?,
+                    );
+                    let result: Option<(bool, bool)> = conn.query_first(query).await?;
+                    assert_eq!(
+                        result,
+                        Some((true, wildcard_match == *term)),
+                        "{} {:?} {}",
+                        sql_mode,
+                        escape_char,
+                        term
+                    );
+                }
+            }
+        }
+
+        conn.disconnect().await
+    }
+
+    #[tokio::test]
+    async fn should_track_charset_and_collation() -> super::Result<()> {
+        let opts = get_opts().add_capability(CapabilityFlags::CLIENT_SESSION_TRACK);
+        let mut conn = Conn::new(opts).await?;
+        assert_eq!(conn.charset(), conn.handshake_charset());
+        assert_eq!(conn.collation(), Some(conn.handshake_collation()));
+
+        let tracked: Option<String> = match conn
+            .query_first("SELECT @@session_track_system_variables")
+            .await
+        {
+            Ok(tracked) => tracked,
+            // session tracking isn't supported
+            Err(Error::Server(_)) => return conn.disconnect().await,
+            Err(err) => return Err(err),
+        };
+        let tracked = tracked.unwrap_or_default();
+
+        conn.query_drop("SET NAMES latin1").await?;
+        if tracked.contains("character_set_client") || tracked == "*" {
+            assert_eq!(conn.charset(), "latin1");
+            if !tracked.contains("collation_connection") && tracked != "*" {
+                assert_eq!(conn.collation(), None);
+            }
+        }
+
+        let tracked = if tracked.is_empty() {
+            "collation_connection".to_owned()
+        } else {
+            format!("{},collation_connection", tracked)
+        };
+        conn.query_drop(format!(
+            "SET SESSION session_track_system_variables = '{}'",
+            tracked
+        ))
+        .await?;
+        conn.query_drop("SET NAMES latin1 COLLATE latin1_bin")
+            .await?;
+        assert_eq!(conn.collation(), Some("latin1_bin"));
+        conn.query_drop("SET NAMES utf8mb4 COLLATE utf8mb4_bin")
+            .await?;
+        assert_eq!(conn.charset(), "utf8mb4");
+        assert_eq!(conn.collation(), Some("utf8mb4_bin"));
+
+        if conn.reset().await? {
+            assert_eq!(conn.charset(), conn.handshake_charset());
+            assert_eq!(conn.collation(), Some(conn.handshake_collation()));
+        }
+
+        conn.disconnect().await
+    }
+
+    #[tokio::test]
+    async fn should_set_charset() -> super::Result<()> {
+        const CHARSETS: &str =
+            "SELECT @@character_set_client, @@character_set_connection, @@character_set_results";
+
+        let mut conn = Conn::new(get_opts().charset(Some("LATIN1"))).await?;
+        let charsets: Option<(String, String, String)> = conn.query_first(CHARSETS).await?;
+        assert_eq!(
+            charsets,
+            Some(("latin1".into(), "latin1".into(), "latin1".into()))
+        );
+        assert_eq!(conn.charset(), "latin1");
+
+        // the charset survives a reset
+        if conn.reset().await? {
+            let charsets: Option<(String, String, String)> = conn.query_first(CHARSETS).await?;
+            assert_eq!(charsets.unwrap().0, "latin1");
+            assert_eq!(conn.charset(), "latin1");
+        }
+        conn.disconnect().await?;
+
+        // `binary_results` is applied on top of the charset
+        let opts = get_opts().charset(Some("latin1")).binary_results(true);
+        let mut conn = Conn::new(opts).await?;
+        let charsets: Option<(String, String, String)> = conn.query_first(CHARSETS).await?;
+        assert_eq!(
+            charsets,
+            Some(("latin1".into(), "latin1".into(), "binary".into()))
+        );
+        conn.disconnect().await?;
+
+        let err = Conn::new(get_opts().charset(Some("no_such_charset")))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::Server(_)));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn should_escape_string() -> super::Result<()> {
+        const TEXT: &str = "it's a \"quoted\" \\ text\nwith\r\0 — ключ 🦀";
+        const ATTACK: &[u8] = b"\xBF' OR 1=1";
+
+        let mut conn = Conn::new(get_opts()).await?;
+        assert_eq!(conn.charset(), conn.handshake_charset());
+
+        for sql_mode in ["''", "'NO_BACKSLASH_ESCAPES'"] {
+            conn.query_drop(format!("SET SESSION sql_mode = {}", sql_mode))
+                .await?;
+            assert_eq!(
+                conn.status()
+                    .contains(StatusFlags::SERVER_STATUS_NO_BACKSLASH_ESCAPES),
+                sql_mode != "''"
+            );
+
+            let query = format!("SELECT '{}'", conn.escape_string(TEXT)?);
+            let result: Option<String> = conn.query_first(query).await?;
+            assert_eq!(result.as_deref(), Some(TEXT));
+        }
+        conn.disconnect().await?;
+
+        // Character set changes are tracked via session state changes.
+        let mut conn =
+            Conn::new(get_opts().add_capability(CapabilityFlags::CLIENT_SESSION_TRACK)).await?;
+        conn.query_drop("SET NAMES gbk").await?;
+        if conn.charset() == "gbk" {
+            for sql_mode in ["''", "'NO_BACKSLASH_ESCAPES'"] {
+                conn.query_drop(format!("SET SESSION sql_mode = {}", sql_mode))
+                    .await?;
+
+                let query = format!("SELECT '{}'", conn.escape_string("it's \\ ascii")?);
+                let result: Option<String> = conn.query_first(query).await?;
+                assert_eq!(result.as_deref(), Some("it's \\ ascii"));
+
+                // UTF-8 text that isn't valid in `gbk` can't be escaped as text
+                if sql_mode == "''" {
+                    assert!(matches!(
+                        conn.escape_string("—"),
+                        Err(Error::Driver(DriverError::CantEscapeString { ref charset }))
+                            if charset == "gbk"
+                    ));
+                }
+
+                // The quote must not be consumed as a trailing byte of a multibyte character.
+                let mut query = b"SELECT '".to_vec();
+                query.extend(conn.escape_bytes(ATTACK));
+                query.push(b'\'');
+                let result: Vec<Vec<u8>> = conn.query(query).await?;
+                assert_eq!(result.len(), 1);
+                assert!(result[0].ends_with(b"' OR 1=1"));
+            }
+
+            conn.reset().await?;
+            assert_eq!(conn.charset(), conn.handshake_charset());
+        }
+        conn.disconnect().await?;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn should_return_binary_results() -> super::Result<()> {
+        const BYTES: &[u8] = b"caf\xE9 \xFF\x80";
+
+        let mut conn = Conn::new(get_opts().binary_results(true)).await?;
+        conn.query_drop(
+            "CREATE TEMPORARY TABLE mysql.tmp_binary_results (val VARCHAR(16) CHARACTER SET latin1)",
+        )
+        .await?;
+        conn.exec_drop("INSERT INTO mysql.tmp_binary_results VALUES (?)", (BYTES,))
+            .await?;
+
+        let charset: Option<String> = conn.query_first("SELECT @@character_set_results").await?;
+        assert_eq!(charset.as_deref(), Some("binary"));
+
+        let text: Option<Vec<u8>> = conn
+            .query_first("SELECT val FROM mysql.tmp_binary_results")
+            .await?;
+        let binary: Option<Vec<u8>> = conn
+            .exec_first("SELECT val FROM mysql.tmp_binary_results", ())
+            .await?;
+        assert_eq!(text.as_deref(), Some(BYTES));
+        assert_eq!(binary.as_deref(), Some(BYTES));
+
+        // the setting survives a reset
+        if conn.reset().await? {
+            let charset: Option<String> =
+                conn.query_first("SELECT @@character_set_results").await?;
+            assert_eq!(charset.as_deref(), Some("binary"));
+        }
+        conn.disconnect().await?;
+
+        // values are converted to the connection charset by default
+        let mut conn = Conn::new(get_opts()).await?;
+        let converted: Option<Vec<u8>> = conn
+            .query_first("SELECT CAST(_latin1 X'E9' AS CHAR CHARACTER SET latin1)")
+            .await?;
+        assert_ne!(converted.as_deref(), Some(&b"\xE9"[..]));
+        conn.disconnect().await?;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn should_send_connect_attrs() -> super::Result<()> {
+        const ATTRS: &str = "SELECT ATTR_NAME, ATTR_VALUE \
+            FROM performance_schema.session_connect_attrs \
+            WHERE PROCESSLIST_ID = CONNECTION_ID()";
+
+        let opts = get_opts().connect_attrs([("program_name", "mysql_async_test")]);
+        let mut conn = Conn::new(opts).await?;
+        let enabled: Option<bool> = conn.query_first("SELECT @@performance_schema").await?;
+        if enabled != Some(true)
+            || !conn
+                .capabilities()
+                .contains(CapabilityFlags::CLIENT_CONNECT_ATTRS)
+        {
+            // attributes are not visible without performance_schema
+            return conn.disconnect().await;
+        }
+
+        for change_user in [false, true] {
+            if change_user {
+                // attributes are sent again on COM_CHANGE_USER
+                conn.change_user(Default::default()).await?;
+            }
+            let attrs: std::collections::HashMap<String, String> =
+                conn.query(ATTRS).await?.into_iter().collect();
+            assert_eq!(attrs["_client_name"], "mysql_async");
+            assert_eq!(attrs["_client_version"], env!("CARGO_PKG_VERSION"));
+            assert_eq!(attrs["program_name"], "mysql_async_test");
+        }
+
+        conn.disconnect().await?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn should_set_resource_group() -> super::Result<()> {
+        const THREAD_GROUP: &str = "SELECT RESOURCE_GROUP FROM performance_schema.threads \
+            WHERE PROCESSLIST_ID = CONNECTION_ID()";
+
+        let mut conn = Conn::new(get_opts()).await?;
+        let supported = !conn.inner.is_mariadb && conn.inner.version >= (8, 0, 3);
+        if supported {
+            conn.query_drop(
+                "CREATE RESOURCE GROUP IF NOT EXISTS mysql_async_test TYPE = USER VCPU = 0",
+            )
+            .await?;
+        }
+        conn.disconnect().await?;
+
+        let opts = get_opts().resource_group(Some("mysql_async_test"));
+        if !supported {
+            let err = Conn::new(opts).await.unwrap_err();
+            assert!(matches!(
+                err,
+                Error::Driver(DriverError::ResourceGroupsNotSupported { .. })
+            ));
+            return Ok(());
+        }
+
+        let mut conn = match Conn::new(opts).await {
+            Ok(conn) => conn,
+            // e.g. thread priorities are not available in a container
+            Err(Error::Driver(DriverError::ResourceGroupsNotSupported { .. })) => return Ok(()),
+            Err(err) => return Err(err),
+        };
+        let group: Option<String> = conn.query_first(THREAD_GROUP).await?;
+        assert_eq!(group.as_deref(), Some("mysql_async_test"));
+
+        // the assignment survives a reset
+        conn.query_drop("SET RESOURCE GROUP USR_default").await?;
+        conn.reset().await?;
+        let group: Option<String> = conn.query_first(THREAD_GROUP).await?;
+        assert_eq!(group.as_deref(), Some("mysql_async_test"));
+        conn.disconnect().await?;
+
+        let opts = get_opts().resource_group(Some("mysql_async_no_such_group"));
+        let err = Conn::new(opts).await.unwrap_err();
+        assert!(matches!(err, Error::Server(_)));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn should_set_connection_memory_limit() -> super::Result<()> {
+        const LIMIT: u64 = 64 * 1024 * 1024;
+
+        let conn = Conn::new(get_opts()).await?;
+        let supported = !conn.inner.is_mariadb && conn.inner.version >= (8, 0, 28);
+        conn.disconnect().await?;
+
+        let opts = get_opts().connection_memory_limit(Some(LIMIT));
+        if !supported {
+            let err = Conn::new(opts).await.unwrap_err();
+            assert!(matches!(
+                err,
+                Error::Driver(DriverError::ConnectionMemoryLimitNotSupported { .. })
+            ));
+            return Ok(());
+        }
+
+        let mut conn = Conn::new(opts).await?;
+        let limit: Option<u64> = conn.query_first("SELECT @@connection_memory_limit").await?;
+        assert_eq!(limit, Some(LIMIT));
+
+        // the limit survives a reset
+        conn.query_drop("SET SESSION connection_memory_limit = DEFAULT")
+            .await?;
+        conn.reset().await?;
+        let limit: Option<u64> = conn.query_first("SELECT @@connection_memory_limit").await?;
+        assert_eq!(limit, Some(LIMIT));
+        conn.disconnect().await?;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn should_set_sql_mode_and_time_zone() -> super::Result<()> {
+        let opts = get_opts()
+            .sql_mode(Some("STRICT_ALL_TABLES"))
+            .time_zone(Some("+03:00"))
+            .init(vec!["SET @init_sql_mode = @@sql_mode"]);
+        let mut conn = Conn::new(opts).await?;
+
+        // variables are set before `init` commands
+        let vars: Option<(String, String, String)> = conn
+            .query_first("SELECT @@sql_mode, @@time_zone, @init_sql_mode")
+            .await?;
+        assert_eq!(
+            vars,
+            Some((
+                "STRICT_ALL_TABLES".into(),
+                "+03:00".into(),
+                "STRICT_ALL_TABLES".into()
+            ))
+        );
+
+        // and after a reset
+        conn.query_drop("SET SESSION sql_mode = DEFAULT, time_zone = DEFAULT")
+            .await?;
+        conn.reset().await?;
+        let vars: Option<(String, String)> =
+            conn.query_first("SELECT @@sql_mode, @@time_zone").await?;
+        assert_eq!(vars, Some(("STRICT_ALL_TABLES".into(), "+03:00".into())));
+        conn.disconnect().await?;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn should_connect() -> super::Result<()> {
+        let mut conn: Conn = Conn::new(get_opts()).await?;
+        conn.ping().await?;
+        let plugins: Vec<String> = conn
+            .query_map("SHOW PLUGINS", |mut row: crate::Row| {
+                row.take("Name").unwrap()
+            })
+            .await?;
+
+        // Should connect with any combination of supported plugin and empty-nonempty password.
+        let variants = vec![
+            ("caching_sha2_password", 2_u8, "non-empty"),
+            ("caching_sha2_password", 2_u8, ""),
+            ("mysql_native_password", 0_u8, "non-empty"),
+            ("mysql_native_password", 0_u8, ""),
+        ]
+        .into_iter()
+        .filter(|variant| plugins.iter().any(|p| p == variant.0));
+
+        for (plug, val, pass) in variants {
+            dbg!((plug, val, pass, conn.inner.version));
+
+            if plug == "mysql_native_password" && conn.inner.version >= (8, 4, 0) {
+                continue;
+            }
 
             let _ = conn.query_drop("DROP USER 'test_user'@'%'").await;
 
@@ -1562,6 +4028,96 @@ mod test {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn should_respect_ssl_mode() -> super::Result<()> {
+        async fn ssl_cipher(ssl_opts: SslOpts) -> super::Result<String> {
+            let mut conn = Conn::new(get_opts().ssl_opts(ssl_opts)).await?;
+            let (_, cipher): (String, String) = conn
+                .query_first("SHOW SESSION STATUS LIKE 'Ssl_cipher'")
+                .await?
+                .unwrap();
+            conn.disconnect().await?;
+            Ok(cipher)
+        }
+
+        let cipher = ssl_cipher(SslOpts::default().with_mode(SslMode::Disabled)).await?;
+        assert_eq!(cipher, "");
+
+        // server certificate is not verified, so it works with self-signed certificates
+        let cipher = ssl_cipher(SslOpts::default().with_mode(SslMode::Preferred)).await?;
+        if crate::test_misc::test_ssl() {
+            assert_ne!(cipher, "");
+            let cipher = ssl_cipher(SslOpts::default().with_mode(SslMode::Required)).await?;
+            assert_ne!(cipher, "");
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn should_respect_tls_version_range() -> super::Result<()> {
+        use crate::TlsVersion;
+
+        async fn ssl_version(ssl_opts: SslOpts) -> super::Result<String> {
+            let mut conn = Conn::new(get_opts().ssl_opts(ssl_opts)).await?;
+            let (_, version): (String, String) = conn
+                .query_first("SHOW SESSION STATUS LIKE 'Ssl_version'")
+                .await?
+                .unwrap();
+            conn.disconnect().await?;
+            Ok(version)
+        }
+
+        if !crate::test_misc::test_ssl() {
+            return Ok(());
+        }
+
+        let ssl_opts = SslOpts::default()
+            .with_danger_skip_domain_validation(true)
+            .with_danger_accept_invalid_certs(true);
+
+        let version = ssl_version(
+            ssl_opts
+                .clone()
+                .with_min_tls_version(TlsVersion::Tls1_2)
+                .with_max_tls_version(TlsVersion::Tls1_2),
+        )
+        .await?;
+        assert_eq!(version, "TLSv1.2");
+
+        // native-tls doesn't allow to require TLS 1.3
+        #[cfg(feature = "rustls-tls")]
+        {
+            let mut conn = Conn::new(get_opts()).await?;
+            let server_versions: Option<String> = conn.query_first("SELECT @@tls_version").await?;
+            conn.disconnect().await?;
+
+            let result =
+                ssl_version(ssl_opts.clone().with_min_tls_version(TlsVersion::Tls1_3)).await;
+            if server_versions.is_some_and(|x| x.contains("TLSv1.3")) {
+                assert_eq!(result?, "TLSv1.3");
+            } else {
+                result.unwrap_err();
+            }
+        }
+
+        let result = ssl_version(
+            ssl_opts
+                .with_min_tls_version(TlsVersion::Tls1_3)
+                .with_max_tls_version(TlsVersion::Tls1_2),
+        )
+        .await;
+        assert!(matches!(
+            result,
+            Err(Error::Driver(DriverError::UnsupportedTlsVersions {
+                min: Some(TlsVersion::Tls1_3),
+                max: Some(TlsVersion::Tls1_2),
+            }))
+        ));
+
+        Ok(())
+    }
+
     #[test]
     fn should_not_panic_if_dropped_without_tokio_runtime() {
         let fut = Conn::new(get_opts());
@@ -1638,6 +4194,56 @@ mod test {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn should_reset_using_given_kind() -> super::Result<()> {
+        for kind in [ResetKind::ResetConnection, ResetKind::ChangeUser] {
+            let mut conn = Conn::new(get_opts()).await?;
+
+            conn.query_drop("SET @foo = 'foo'").await?;
+            conn.query_drop("PREPARE sql_stmt FROM 'SELECT 1'").await?;
+            let stmt = conn.prep("SELECT 1").await?;
+            assert_eq!(conn.exec_first::<u8, _, _>(&stmt, ()).await?, Some(1));
+
+            let used = conn.reset_using(kind).await?;
+            if kind == ResetKind::ChangeUser {
+                assert_eq!(used, ResetKind::ChangeUser);
+            }
+
+            assert_eq!(
+                conn.query_first::<Value, _>("SELECT @foo").await?.unwrap(),
+                Value::NULL
+            );
+            assert!(conn.query_drop("EXECUTE sql_stmt").await.is_err());
+            match conn.exec_drop(&stmt, ()).await {
+                Err(Error::Server(err)) => assert_eq!(err.code, 1243), // ER_UNKNOWN_STMT_HANDLER
+                other => panic!("expected an unknown statement error, got {:?}", other),
+            }
+            assert_eq!(conn.prepared_statements_count(), 0);
+
+            // the connection is still usable (and statements are re-prepared)
+            assert_eq!(conn.exec_first::<u8, _, _>("SELECT 1", ()).await?, Some(1));
+
+            conn.disconnect().await?;
+        }
+
+        let pool_opts = crate::PoolOpts::default()
+            .with_constraints(crate::PoolConstraints::new(1, 1).unwrap())
+            .with_reset_kind(ResetKind::ChangeUser);
+        let pool = Pool::new(get_opts().pool_opts(pool_opts));
+        let mut conn = pool.get_conn().await?;
+        conn.query_drop("SET @foo = 'foo'").await?;
+        drop(conn);
+        let mut conn = pool.get_conn().await?;
+        assert_eq!(
+            conn.query_first::<Value, _>("SELECT @foo").await?.unwrap(),
+            Value::NULL
+        );
+        drop(conn);
+        pool.disconnect().await?;
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn should_change_user() -> super::Result<()> {
         /// Whether particular authentication plugin should be tested on the current database.
@@ -1794,37 +4400,183 @@ mod test {
                 assert_eq!(db, None);
                 assert!(user.starts_with("__mats"));
 
-                conn2.disconnect().await.unwrap();
-            }
-        }
+                conn2.disconnect().await.unwrap();
+            }
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn should_not_cache_statements_if_stmt_cache_size_is_zero() -> super::Result<()> {
+        let opts = OptsBuilder::from_opts(get_opts()).stmt_cache_size(0);
+
+        let mut conn = Conn::new(opts).await?;
+        conn.exec_drop("DO ?", (1_u8,)).await?;
+
+        let stmt = conn.prep("DO 2").await?;
+        conn.exec_drop(&stmt, ()).await?;
+        conn.exec_drop(&stmt, ()).await?;
+        conn.close(stmt).await?;
+
+        conn.exec_drop("DO 3", ()).await?;
+        conn.exec_batch("DO 4", vec![(), ()]).await?;
+        conn.exec_first::<u8, _, _>("DO 5", ()).await?;
+        let row: Option<(crate::Value, usize)> = conn
+            .query_first("SHOW SESSION STATUS LIKE 'Com_stmt_close';")
+            .await?;
+
+        assert_eq!(row.unwrap().1, 1);
+        assert_eq!(conn.inner.stmt_cache.len(), 0);
+
+        conn.disconnect().await?;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn should_handle_socket_fallback_policy() -> super::Result<()> {
+        use crate::SocketFallback;
+
+        const BAD_SOCKET: &str = "/nonexistent/mysql_async/mysqld.sock";
+
+        for fallback in [
+            SocketFallback::Warn,
+            SocketFallback::Error,
+            SocketFallback::Silent,
+        ] {
+            let opts = get_opts()
+                .socket(None::<String>)
+                .prefer_socket(false)
+                .socket_fallback(fallback);
+            let mut conn = Conn::new(opts).await?;
+            let id = conn.id();
+
+            // simulate `@@socket` pointing to an unavailable socket
+            conn.inner.socket = Some(BAD_SOCKET.into());
+            let result = conn.reconnect_via_socket_if_needed(true).await;
+
+            match fallback {
+                SocketFallback::Error => match result {
+                    Err(Error::Driver(DriverError::SocketUnavailable { socket, .. })) => {
+                        assert_eq!(socket, BAD_SOCKET);
+                    }
+                    other => panic!("expected SocketUnavailable, got {:?}", other),
+                },
+                SocketFallback::Warn | SocketFallback::Silent => {
+                    result?;
+                    // still on the original TCP connection
+                    assert_eq!(conn.id(), id);
+                    assert!(!conn.is_socket());
+                    conn.ping().await?;
+                }
+            }
+
+            conn.disconnect().await?;
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn should_connect_over_custom_stream() -> super::Result<()> {
+        let opts = Opts::from(get_opts().ssl_opts(None::<SslOpts>));
+
+        // in-process duplex pipe proxied to the test server
+        let (client, mut server) = tokio::io::duplex(64 * 1024);
+        let mut upstream =
+            tokio::net::TcpStream::connect((opts.ip_or_hostname(), opts.tcp_port())).await?;
+        let proxy = tokio::spawn(async move {
+            let copied = tokio::io::copy_bidirectional(&mut server, &mut upstream).await;
+            copied.map(drop)
+        });
+
+        let mut conn = Conn::from_stream(client, opts).await?;
+        assert!(conn.inner.stream.as_ref().unwrap().is_custom());
+        assert_eq!(conn.query_first::<u8, _>("SELECT 1").await?, Some(1));
+        assert_eq!(
+            conn.query_first::<u32, _>("SELECT CONNECTION_ID()").await?,
+            Some(conn.id())
+        );
+        conn.ping().await?;
+        conn.disconnect().await?;
+
+        tokio::time::timeout(std::time::Duration::from_secs(10), proxy)
+            .await
+            .expect("proxy should stop once the connection is closed")
+            .unwrap()?;
+
+        // TLS is the caller's responsibility
+        let (client, _server) = tokio::io::duplex(1024);
+        let err = Conn::from_stream(client, get_opts().ssl_opts(SslOpts::default()))
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            Error::Driver(DriverError::TlsOverCustomStream)
+        ));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn should_expose_scramble_if_requested() -> super::Result<()> {
+        let conn = Conn::new(get_opts()).await?;
+        assert_eq!(conn.scramble(), None);
+        conn.disconnect().await?;
+
+        let conn = Conn::new(get_opts().expose_scramble(true)).await?;
+        let scramble = conn.scramble().expect("scramble is not exposed").to_vec();
+        assert_eq!(scramble.len(), 20);
+        assert!(scramble.iter().any(|x| *x != 0));
+
+        // scramble is random per connection
+        let other = Conn::new(get_opts().expose_scramble(true)).await?;
+        assert_ne!(other.scramble(), Some(&*scramble));
 
+        other.disconnect().await?;
+        conn.disconnect().await?;
         Ok(())
     }
 
     #[tokio::test]
-    async fn should_not_cache_statements_if_stmt_cache_size_is_zero() -> super::Result<()> {
-        let opts = OptsBuilder::from_opts(get_opts()).stmt_cache_size(0);
+    async fn should_hold_max_prepared_statements_bound() -> super::Result<()> {
+        const ER_UNKNOWN_STMT_HANDLER: u16 = 1243;
 
+        let opts = get_opts().stmt_cache_size(0).max_prepared_statements(3);
         let mut conn = Conn::new(opts).await?;
-        conn.exec_drop("DO ?", (1_u8,)).await?;
 
-        let stmt = conn.prep("DO 2").await?;
-        conn.exec_drop(&stmt, ()).await?;
-        conn.exec_drop(&stmt, ()).await?;
-        conn.close(stmt).await?;
+        let mut stmts = Vec::new();
+        for i in 0..10 {
+            stmts.push(conn.prep(format!("SELECT {}", i)).await?);
+            assert_eq!(conn.prepared_statements_count(), std::cmp::min(i + 1, 3));
+        }
 
-        conn.exec_drop("DO 3", ()).await?;
-        conn.exec_batch("DO 4", vec![(), ()]).await?;
-        conn.exec_first::<u8, _, _>("DO 5", ()).await?;
-        let row: Option<(crate::Value, usize)> = conn
-            .query_first("SHOW SESSION STATUS LIKE 'Com_stmt_close';")
-            .await?;
+        // the oldest statements were closed on the server side
+        for stmt in &stmts[..7] {
+            let err = conn.exec_drop(stmt, ()).await.unwrap_err();
+            match err {
+                Error::Server(ServerError { code, .. }) => {
+                    assert_eq!(code, ER_UNKNOWN_STMT_HANDLER)
+                }
+                err => panic!("unexpected error {:?}", err),
+            }
+        }
+        for (i, stmt) in stmts.iter().enumerate().skip(7) {
+            assert_eq!(conn.exec_first(stmt, ()).await?, Some(i));
+        }
 
-        assert_eq!(row.unwrap().1, 1);
-        assert_eq!(conn.inner.stmt_cache.len(), 0);
+        // executed statements are considered recently used
+        conn.exec_drop(&stmts[7], ()).await?;
+        conn.prep("SELECT 10").await?;
+        assert_eq!(conn.exec_first(&stmts[7], ()).await?, Some(7_usize));
+        conn.exec_drop(&stmts[8], ()).await.unwrap_err();
 
-        conn.disconnect().await?;
+        // closed statements are not counted
+        conn.close(stmts.pop().unwrap()).await?;
+        assert_eq!(conn.prepared_statements_count(), 2);
 
+        conn.disconnect().await?;
         Ok(())
     }
 
@@ -1855,6 +4607,172 @@ mod test {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn should_call_on_connect() -> super::Result<()> {
+        use futures_util::FutureExt;
+        use std::sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        };
+
+        let calls = Arc::new(AtomicUsize::new(0));
+        let calls_clone = calls.clone();
+        let opts = get_opts()
+            .init(vec!["SET @app_user = 'init'"])
+            .setup(vec!["SET @setup_user = @app_user"])
+            .on_connect(move |conn: &mut Conn| {
+                let n = calls_clone.fetch_add(1, Ordering::SeqCst) + 1;
+                async move {
+                    // `init` commands are executed first
+                    let init: Option<String> = conn.query_first("SELECT @app_user").await?;
+                    assert_eq!(init.as_deref(), Some("init"));
+                    conn.exec_drop("SET @app_user = ?", (format!("user-{}", n),))
+                        .await
+                }
+                .boxed()
+            });
+
+        let pool = Pool::new(opts.clone());
+        let mut conn = pool.get_conn().await?;
+        let users: Option<(String, String)> =
+            conn.query_first("SELECT @app_user, @setup_user").await?;
+        assert_eq!(users, Some(("user-1".into(), "user-1".into())));
+        drop(conn);
+        pool.disconnect().await?;
+
+        let mut conn = Conn::new(opts).await?;
+        let user: Option<String> = conn.query_first("SELECT @app_user").await?;
+        assert_eq!(user.as_deref(), Some("user-2"));
+        conn.disconnect().await?;
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+        // a cleared function isn't called
+        let mut conn = Conn::new(opts.clone().clear_on_connect()).await?;
+        let user: Option<String> = conn.query_first("SELECT @app_user").await?;
+        assert_eq!(user.as_deref(), Some("init"));
+        conn.disconnect().await?;
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+        // an error fails the connection
+        let opts = get_opts()
+            .on_connect(|conn: &mut Conn| conn.query_drop("SELECT * FROM mysql.no_such_table"));
+        assert!(Conn::new(opts.clone()).await.is_err());
+
+        let pool = Pool::new(opts);
+        assert!(pool.get_conn().await.is_err());
+        assert_eq!(pool.stats().active, 0);
+        pool.disconnect().await?;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn should_rewrite_sql() -> super::Result<()> {
+        use std::borrow::Cow;
+
+        let opts = get_opts().sql_rewrite(|sql: &str| {
+            if sql.starts_with("SELECT") {
+                Cow::Owned(format!("{} /* rewritten */", sql))
+            } else {
+                Cow::Borrowed(sql)
+            }
+        });
+        assert!(Opts::from(opts.clone().clear_sql_rewrite())
+            .sql_rewrite()
+            .is_none());
+        let mut conn = Conn::new(opts).await?;
+
+        let info: Option<String> = conn
+            .query_first(
+                "SELECT INFO FROM information_schema.PROCESSLIST WHERE ID = CONNECTION_ID()",
+            )
+            .await?;
+        assert!(info.unwrap().ends_with("/* rewritten */"));
+
+        // statements are prepared (and cached) rewritten, parameters stay in place
+        let sum: Option<u32> = conn
+            .exec_first("SELECT :a + :b", params! { "a" => 1, "b" => 2 })
+            .await?;
+        assert_eq!(sum, Some(3));
+        assert!(conn
+            .get_cached_stmt(b"SELECT ? + ? /* rewritten */")
+            .is_some());
+        assert!(conn.get_cached_stmt(b"SELECT ? + ?").is_none());
+
+        conn.query_drop("DO 1").await?;
+        conn.disconnect().await?;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn should_bind_to_local_address() -> super::Result<()> {
+        use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr};
+
+        let opts = get_opts()
+            .prefer_socket(false)
+            .bind_address(Some((Ipv4Addr::UNSPECIFIED, 0)));
+        let mut conn = Conn::new(opts).await?;
+        conn.ping().await?;
+        conn.disconnect().await?;
+
+        // an IPv6 bind address can't be used to connect to an IPv4 host
+        let bind_address = SocketAddr::from((Ipv6Addr::UNSPECIFIED, 0));
+        let opts = get_opts()
+            .prefer_socket(false)
+            .ip_or_hostname("127.0.0.1")
+            .bind_address(Some(bind_address));
+        match Conn::new(opts).await {
+            Err(Error::Driver(DriverError::BindAddressFamilyMismatch {
+                bind_address: addr,
+                host,
+            })) => {
+                assert_eq!(addr, bind_address);
+                assert_eq!(host, "127.0.0.1");
+            }
+            other => panic!("expected BindAddressFamilyMismatch, got {:?}", other),
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn should_return_ok_packet_of_dropped_result() -> super::Result<()> {
+        let mut conn = Conn::new(get_opts()).await?;
+        conn.query_drop("CREATE TEMPORARY TABLE tmp (id SERIAL, val INT)")
+            .await?;
+
+        let ok = conn
+            .exec_drop_ok("INSERT INTO tmp (val) VALUES (?)", (1,))
+            .await?
+            .unwrap();
+        assert_eq!(ok.last_insert_id(), Some(1));
+        assert_eq!(ok.affected_rows(), 1);
+        assert_eq!(conn.last_insert_id(), Some(1));
+        assert_eq!(conn.affected_rows(), 1);
+
+        // the last statement of a batch wins
+        let ok = conn
+            .query_drop_ok(
+                "INSERT INTO tmp (val) VALUES (2); INSERT INTO tmp (val) VALUES (3), (4)",
+            )
+            .await?
+            .unwrap();
+        assert_eq!(ok.last_insert_id(), Some(3));
+        assert_eq!(ok.affected_rows(), 2);
+        assert_eq!(conn.last_insert_id(), Some(3));
+        assert_eq!(conn.affected_rows(), 2);
+
+        conn.query_drop("INSERT INTO tmp (val) VALUES (5); SELECT 1 / 0")
+            .await?;
+        assert_eq!(conn.last_insert_id(), None);
+        assert_eq!(conn.affected_rows(), 0);
+        assert_eq!(conn.get_warnings(), 1);
+
+        conn.disconnect().await?;
+        Ok(())
+    }
+
     #[tokio::test]
     async fn should_perform_queries() -> super::Result<()> {
         let mut conn = Conn::new(get_opts()).await?;
@@ -1982,6 +4900,168 @@ mod test {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn should_bulk_execute_batch() -> super::Result<()> {
+        const ROWS: usize = 1000;
+
+        let mut conn = Conn::new(get_opts()).await?;
+        conn.query_drop("CREATE TEMPORARY TABLE tmp_bulk (id INT, name TEXT, score DOUBLE)")
+            .await?;
+
+        let params = (0..ROWS).map(|i| {
+            let name = (i % 10 != 0).then(|| format!("name {}", i));
+            (i as u32, name, i as f64 / 2.0)
+        });
+        conn.exec_batch(
+            "INSERT INTO tmp_bulk (id, name, score) VALUES (?, ?, ?)",
+            params,
+        )
+        .await?;
+        if conn.supports_bulk_execute() {
+            // the whole batch is acknowledged by a single OK packet
+            assert_eq!(conn.affected_rows(), ROWS as u64);
+        } else {
+            assert_eq!(conn.affected_rows(), 1);
+        }
+
+        let rows: Vec<(u32, Option<String>, f64)> = conn
+            .query("SELECT id, name, score FROM tmp_bulk ORDER BY id")
+            .await?;
+        assert_eq!(rows.len(), ROWS);
+        for (i, (id, name, score)) in rows.into_iter().enumerate() {
+            assert_eq!(id, i as u32);
+            assert_eq!(name, (i % 10 != 0).then(|| format!("name {}", i)));
+            assert_eq!(score, i as f64 / 2.0);
+        }
+
+        // params that change their type from row to row are executed one by one
+        conn.exec_batch(
+            "INSERT INTO tmp_bulk (id) VALUES (?)",
+            vec![(Value::Int(-1),), (Value::Bytes(b"-2".to_vec()),)],
+        )
+        .await?;
+        let count: Option<u32> = conn
+            .query_first("SELECT COUNT(*) FROM tmp_bulk WHERE id < 0")
+            .await?;
+        assert_eq!(count, Some(2));
+
+        conn.disconnect().await?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn should_execute_sql_level_prepared_statements() -> super::Result<()> {
+        let mut conn = Conn::new(get_opts().sql_prepared_statements(true)).await?;
+
+        conn.query_drop("CREATE TEMPORARY TABLE tmp_sql_prep (id INT, name TEXT)")
+            .await?;
+        conn.exec_batch(
+            "INSERT INTO tmp_sql_prep (id, name) VALUES (?, ?)",
+            vec![(1, "foo"), (2, "it's \\ tricky"), (3, "bar")],
+        )
+        .await?;
+        assert_eq!(conn.affected_rows(), 1);
+
+        let rows: Vec<(u8, String)> = conn
+            .exec(
+                "SELECT id, name FROM tmp_sql_prep WHERE id > ? ORDER BY id",
+                (1,),
+            )
+            .await?;
+        assert_eq!(rows, vec![(2, "it's \\ tricky".into()), (3, "bar".into())]);
+
+        let name: Option<String> = conn
+            .exec_first(
+                "SELECT name FROM tmp_sql_prep WHERE id = :id OR name = :name",
+                params! { "id" => 3, "name" => "nope" },
+            )
+            .await?;
+        assert_eq!(name.as_deref(), Some("bar"));
+
+        // rows are sent using the text protocol
+        let row: Option<Row> = conn.exec_first("SELECT ?, ?", (42, Value::NULL)).await?;
+        let row = row.unwrap();
+        assert_eq!(row[0], Value::Bytes(b"42".to_vec()));
+        assert_eq!(row[1], Value::NULL);
+
+        // `Statement` is executed via SQL-level path as well
+        let stmt = conn.prep("SELECT ? + ?").await?;
+        assert_eq!(conn.exec_first(&stmt, (1, 2)).await?, Some(3_u8));
+
+        // SQL-level `EXECUTE` was used
+        let count: Option<(String, u64)> = conn
+            .query_first("SHOW SESSION STATUS LIKE 'Com_execute_sql'")
+            .await?;
+        assert!(count.unwrap().1 >= 6);
+
+        let err = conn
+            .exec_drop("SELECT ?", params! { "foo" => 1 })
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            Error::Driver(DriverError::NamedParamsForPositionalQuery)
+        ));
+
+        // params are validated the same way as for the binary protocol
+        let err = conn.exec_drop("SELECT ?, '?'", (1, 2)).await.unwrap_err();
+        assert!(matches!(
+            err,
+            Error::Driver(DriverError::StmtParamsMismatch {
+                required: 1,
+                supplied: 2
+            })
+        ));
+        let err = conn.exec_drop("SELECT :a, :b", (1,)).await.unwrap_err();
+        assert!(matches!(
+            err,
+            Error::Driver(DriverError::StmtParamsMismatch {
+                required: 2,
+                supplied: 1
+            })
+        ));
+        assert_eq!(
+            conn.exec_first("SELECT :a, :b", (1, 2)).await?,
+            Some((1_u8, 2_u8))
+        );
+
+        conn.disconnect().await?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn should_cache_and_deallocate_sql_level_prepared_statements() -> super::Result<()> {
+        async fn status(conn: &mut Conn, name: &str) -> super::Result<u64> {
+            let query = format!("SHOW SESSION STATUS LIKE '{}'", name);
+            let row: Option<(String, u64)> = conn.query_first(query).await?;
+            Ok(row.unwrap().1)
+        }
+
+        let opts = get_opts().sql_prepared_statements(true).stmt_cache_size(2);
+        let mut conn = Conn::new(opts).await?;
+
+        let prepared = status(&mut conn, "Com_prepare_sql").await?;
+        for _ in 0..3 {
+            conn.exec_drop("SELECT ?", (1,)).await?;
+        }
+        // the statement is prepared once
+        assert_eq!(status(&mut conn, "Com_prepare_sql").await?, prepared + 1);
+
+        let deallocated = status(&mut conn, "Com_dealloc_sql").await?;
+        conn.exec_drop("SELECT ? + 1", (1,)).await?;
+        conn.exec_drop("SELECT ? + 2", (1,)).await?;
+        // the LRU statement is deallocated on eviction
+        assert_eq!(status(&mut conn, "Com_dealloc_sql").await?, deallocated + 1);
+        assert_eq!(conn.stmt_cache_ref().sql_len(), 2);
+
+        // deallocated statement is prepared again
+        conn.exec_drop("SELECT ?", (1,)).await?;
+        assert_eq!(status(&mut conn, "Com_prepare_sql").await?, prepared + 4);
+
+        conn.disconnect().await?;
+        Ok(())
+    }
+
     #[tokio::test]
     async fn should_first_exec_statement() -> super::Result<()> {
         let mut conn = Conn::new(get_opts()).await?;
@@ -2469,6 +5549,264 @@ mod test {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn should_reject_unexpected_local_infile_requests() -> super::Result<()> {
+        use mysql_common::constants::Command;
+        use std::fs::write;
+        use tokio::io::{AsyncReadExt, DuplexStream};
+
+        use crate::LocalInfileError;
+
+        /// Proxies the connection to the test server, replacing `query` with `replacement`,
+        /// so that the server requests a file the client didn't ask for.
+        async fn spawn_malicious_proxy(
+            client: DuplexStream,
+            opts: &Opts,
+            query: &'static [u8],
+            replacement: Vec<u8>,
+        ) -> std::io::Result<()> {
+            let upstream =
+                tokio::net::TcpStream::connect((opts.ip_or_hostname(), opts.tcp_port())).await?;
+            let (mut upstream_read, mut upstream_write) = upstream.into_split();
+            let (mut client_read, mut client_write) = tokio::io::split(client);
+
+            tokio::spawn(
+                async move { tokio::io::copy(&mut upstream_read, &mut client_write).await },
+            );
+            tokio::spawn(async move {
+                let mut header = [0_u8; 4];
+                while client_read.read_exact(&mut header).await.is_ok() {
+                    let len = u32::from_le_bytes([header[0], header[1], header[2], 0]);
+                    let mut payload = vec![0_u8; len as usize];
+                    client_read.read_exact(&mut payload).await?;
+                    if payload.first() == Some(&(Command::COM_QUERY as u8))
+                        && payload[1..] == *query
+                    {
+                        payload.truncate(1);
+                        payload.extend_from_slice(&replacement);
+                        let len = (payload.len() as u32).to_le_bytes();
+                        header[..3].copy_from_slice(&len[..3]);
+                    }
+                    upstream_write.write_all(&header).await?;
+                    upstream_write.write_all(&payload).await?;
+                }
+                upstream_write.shutdown().await
+            });
+
+            Ok(())
+        }
+
+        let file_path = tempfile::Builder::new().tempfile_in("").unwrap();
+        let file_path = file_path.path();
+        let file_name = file_path.file_name().unwrap();
+
+        write(file_name, b"secret\n")?;
+
+        let attack = format!(
+            r#"LOAD DATA LOCAL INFILE "{}" INTO TABLE tmp"#,
+            file_name.to_str().unwrap(),
+        );
+
+        for strict in [false, true] {
+            let opts = get_opts()
+                .ssl_opts(None::<SslOpts>)
+                .local_infile_handler(Some(WhiteListFsHandler::new(&[file_name][..])))
+                .strict_local_infile(strict);
+
+            let (client, server) = tokio::io::duplex(64 * 1024);
+            let proxy_opts = Opts::from(opts.clone());
+            spawn_malicious_proxy(server, &proxy_opts, b"SELECT 1", attack.clone().into()).await?;
+
+            let mut conn = Conn::from_stream(client, opts).await?;
+            conn.query_drop("CREATE TEMPORARY TABLE tmp (a TEXT)")
+                .await?;
+
+            let result = conn.query_drop("SELECT 1").await;
+            if let Err(Error::Server(ref err)) = result {
+                if err.code == 1148 || err.code == 3948 {
+                    // LOCAL INFILE is disabled
+                    return Ok(());
+                }
+            }
+
+            let leaked: Vec<String> = conn.query("SELECT a FROM tmp").await?;
+            if strict {
+                match result {
+                    Err(Error::Driver(DriverError::LocalInfile(
+                        LocalInfileError::UnexpectedRequest(name),
+                    ))) => assert_eq!(name, file_name.to_str().unwrap()),
+                    other => panic!("unexpected result: {:?}", other),
+                }
+                assert!(leaked.is_empty());
+            } else {
+                // without strict mode the attack succeeds
+                result?;
+                assert_eq!(leaked, ["secret"]);
+            }
+
+            // the same statement issued by the client is allowed
+            conn.query_drop(&attack).await?;
+            let loaded: Vec<String> = conn.query("SELECT a FROM tmp").await?;
+            assert_eq!(loaded.len(), leaked.len() + 1);
+
+            conn.disconnect().await?;
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn should_load_data_from_typed_rows() -> super::Result<()> {
+        const ROWS: usize = 10_000;
+
+        let mut conn = Conn::new(get_opts()).await?;
+        conn.query_drop(
+            "CREATE TEMPORARY TABLE tmp_load_data (id INT PRIMARY KEY, name TEXT, score DOUBLE)",
+        )
+        .await?;
+
+        let rows = (0..ROWS).map(|i| {
+            let name = match i % 4 {
+                0 => None,
+                1 => Some(format!("tab\t{}", i)),
+                2 => Some(format!("line\nbreak\\{}", i)),
+                _ => Some(format!("plain {}", i)),
+            };
+            (i as u32, name, i as f64 / 2.0)
+        });
+
+        let result = match conn
+            .load_data("tmp_load_data", &["id", "name", "score"], rows)
+            .await
+        {
+            Ok(result) => result,
+            Err(super::Error::Server(ref err)) if err.code == 1148 || err.code == 3948 => {
+                // LOCAL INFILE is disabled
+                return Ok(());
+            }
+            Err(err) => return Err(err),
+        };
+
+        assert_eq!(result.affected_rows, ROWS as u64);
+        assert_eq!(result.warnings, 0);
+
+        let count: Option<u64> = conn
+            .query_first("SELECT COUNT(*) FROM tmp_load_data")
+            .await?;
+        assert_eq!(count, Some(ROWS as u64));
+
+        let rows: Vec<(u32, Option<String>, f64)> = conn
+            .query(
+                "SELECT id, name, score FROM tmp_load_data WHERE id IN (0, 1, 2, 9999) ORDER BY id",
+            )
+            .await?;
+        assert_eq!(
+            rows,
+            vec![
+                (0, None, 0.0),
+                (1, Some("tab\t1".into()), 0.5),
+                (2, Some("line\nbreak\\2".into()), 1.0),
+                (9999, Some("plain 9999".into()), 4999.5),
+            ]
+        );
+
+        Ok(())
+    }
+
+    /// Connects to a fake MariaDB server that announces the given extended capabilities.
+    ///
+    /// Returns [`Conn::supports_bulk_execute`] and extended capabilities announced by the client.
+    async fn connect_to_fake_mariadb(ext_capabilities: u32) -> super::Result<(bool, u32)> {
+        use tokio::io::{AsyncReadExt, DuplexStream};
+
+        async fn read_packet(stream: &mut DuplexStream) -> std::io::Result<Vec<u8>> {
+            let mut header = [0_u8; 4];
+            stream.read_exact(&mut header).await?;
+            let len = u32::from_le_bytes([header[0], header[1], header[2], 0]);
+            let mut payload = vec![0_u8; len as usize];
+            stream.read_exact(&mut payload).await?;
+            Ok(payload)
+        }
+
+        async fn write_packet(
+            stream: &mut DuplexStream,
+            seq_id: u8,
+            payload: &[u8],
+        ) -> std::io::Result<()> {
+            let len = (payload.len() as u32).to_le_bytes();
+            stream.write_all(&[len[0], len[1], len[2], seq_id]).await?;
+            stream.write_all(payload).await
+        }
+
+        const OK_PACKET: &[u8] = &[0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00];
+
+        // MariaDB doesn't set `CLIENT_MYSQL` (aka `CLIENT_LONG_PASSWORD`)
+        let capabilities = (CapabilityFlags::CLIENT_PROTOCOL_41
+            | CapabilityFlags::CLIENT_SECURE_CONNECTION
+            | CapabilityFlags::CLIENT_PLUGIN_AUTH
+            | CapabilityFlags::CLIENT_TRANSACTIONS)
+            .bits();
+        let mut handshake = vec![10];
+        handshake.extend_from_slice(b"5.5.5-10.11.6-MariaDB\0");
+        handshake.extend_from_slice(&1_u32.to_le_bytes());
+        handshake.extend_from_slice(&[1; 8]);
+        handshake.push(0);
+        handshake.extend_from_slice(&(capabilities as u16).to_le_bytes());
+        handshake.push(45);
+        handshake.extend_from_slice(&2_u16.to_le_bytes());
+        handshake.extend_from_slice(&((capabilities >> 16) as u16).to_le_bytes());
+        handshake.push(21);
+        handshake.extend_from_slice(&[0; 6]);
+        handshake.extend_from_slice(&ext_capabilities.to_le_bytes());
+        handshake.extend_from_slice(&[1; 12]);
+        handshake.push(0);
+        handshake.extend_from_slice(b"mysql_native_password\0");
+
+        let (client, mut server) = tokio::io::duplex(64 * 1024);
+        let server = tokio::task::spawn(async move {
+            write_packet(&mut server, 0, &handshake).await?;
+            let response = read_packet(&mut server).await?;
+            let announced =
+                u32::from_le_bytes([response[28], response[29], response[30], response[31]]);
+            write_packet(&mut server, 2, OK_PACKET).await?;
+            // every command succeeds until `COM_QUIT`
+            while let Ok(command) = read_packet(&mut server).await {
+                if command.first() == Some(&0x01) {
+                    break;
+                }
+                write_packet(&mut server, 1, OK_PACKET).await?;
+            }
+            Ok::<_, std::io::Error>(announced)
+        });
+
+        let opts = OptsBuilder::default()
+            .user(Some("root"))
+            .prefer_socket(false)
+            .max_allowed_packet(Some(16 * 1024 * 1024))
+            .wait_timeout(Some(28800));
+        let conn = Conn::from_stream(client, opts).await?;
+        let supports_bulk_execute = conn.supports_bulk_execute();
+        conn.disconnect().await?;
+
+        Ok((supports_bulk_execute, server.await.unwrap()?))
+    }
+
+    #[tokio::test]
+    async fn should_negotiate_mariadb_bulk_operations() -> super::Result<()> {
+        use super::MARIADB_CLIENT_STMT_BULK_OPERATIONS;
+
+        // the server doesn't announce the capability, so `exec_batch` executes one by one
+        let (supports_bulk_execute, announced) = connect_to_fake_mariadb(0).await?;
+        assert!(!supports_bulk_execute);
+        assert_eq!(announced, MARIADB_CLIENT_STMT_BULK_OPERATIONS);
+
+        let (supports_bulk_execute, _) =
+            connect_to_fake_mariadb(MARIADB_CLIENT_STMT_BULK_OPERATIONS).await?;
+        assert!(supports_bulk_execute);
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn should_handle_initial_error_packet() {
         let header = [
@@ -2508,6 +5846,72 @@ mod test {
         );
     }
 
+    #[tokio::test]
+    #[cfg(feature = "socks5")]
+    async fn should_connect_through_socks5_proxy() -> super::Result<()> {
+        use crate::ProxyConfig;
+        use tokio::io::AsyncReadExt;
+
+        // A fake proxy that accepts a single unauthenticated CONNECT.
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let proxy_addr = listener.local_addr()?;
+        let proxy = tokio::task::spawn(async move {
+            let (mut client, _) = listener.accept().await?;
+            let mut greeting = [0_u8; 2];
+            client.read_exact(&mut greeting).await?;
+            let mut methods = vec![0_u8; greeting[1] as usize];
+            client.read_exact(&mut methods).await?;
+            assert!(methods.contains(&0));
+            client.write_all(&[5, 0]).await?;
+
+            let mut request = [0_u8; 4];
+            client.read_exact(&mut request).await?;
+            assert_eq!(request[..3], [5, 1, 0]);
+            let host = match request[3] {
+                1 => {
+                    let mut ip = [0_u8; 4];
+                    client.read_exact(&mut ip).await?;
+                    std::net::Ipv4Addr::from(ip).to_string()
+                }
+                3 => {
+                    let mut host = vec![0_u8; client.read_u8().await? as usize];
+                    client.read_exact(&mut host).await?;
+                    String::from_utf8(host).unwrap()
+                }
+                4 => {
+                    let mut ip = [0_u8; 16];
+                    client.read_exact(&mut ip).await?;
+                    std::net::Ipv6Addr::from(ip).to_string()
+                }
+                x => panic!("unexpected address type {}", x),
+            };
+            let port = client.read_u16().await?;
+
+            let mut server = tokio::net::TcpStream::connect((host.as_str(), port)).await?;
+            client.write_all(&[5, 0, 0, 1, 0, 0, 0, 0, 0, 0]).await?;
+            let _ = tokio::io::copy_bidirectional(&mut client, &mut server).await;
+            Ok::<_, std::io::Error>((host, port))
+        });
+
+        let opts = Opts::from(get_opts());
+        let (host, port) = (opts.ip_or_hostname().to_owned(), opts.tcp_port());
+        let opts = OptsBuilder::from_opts(opts)
+            .socket(None::<String>)
+            .proxy(Some(ProxyConfig::socks5(
+                proxy_addr.ip().to_string(),
+                proxy_addr.port(),
+            )))
+            .build()?;
+        assert!(!opts.prefer_socket());
+
+        let mut conn = Conn::new(opts).await?;
+        assert_eq!(conn.query_first::<u8, _>("SELECT 1").await?, Some(1));
+        conn.disconnect().await?;
+
+        assert_eq!(proxy.await.unwrap()?, (host, port));
+        Ok(())
+    }
+
     #[cfg_attr(docsrs, doc(cfg(feature = "client_parsec")))]
     #[tokio::test]
     #[cfg(feature = "client_parsec")]
@@ -0,0 +1,86 @@
+// Copyright (c) 2025 mysql_async contributors
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+use futures_core::stream::{BoxStream, Stream};
+use futures_util::StreamExt;
+use tokio::sync::broadcast;
+
+use std::{
+    fmt,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+/// Number of events buffered for each subscriber (see [`crate::Pool::events`]).
+pub const POOL_EVENTS_CAPACITY: usize = 1024;
+
+/// An event of a connection pool (see [`crate::Pool::events`]).
+///
+/// `id` is the connection id (see [`crate::Conn::id`]).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum PoolEvent {
+    /// A new connection was established by the pool.
+    ConnectionCreated { id: u32 },
+    /// A connection left the pool (e.g. it's expired, superfluous, broken, detached
+    /// by [`crate::Conn::change_user`] or the pool is disconnecting).
+    ConnectionClosed { id: u32 },
+    /// A connection was taken from the pool.
+    CheckedOut { id: u32 },
+    /// A connection was returned to the idle queue of the pool.
+    Returned { id: u32 },
+    /// An idling connection failed the liveness check and was dropped.
+    ValidationFailed { id: u32 },
+    /// A [`crate::futures::GetConn`] was put into the wait queue.
+    ///
+    /// Note that a waiter is re-enqueued if it was woken up but there is still
+    /// no connection for it.
+    WaiterEnqueued,
+    /// A waiting [`crate::futures::GetConn`] was dropped before getting a connection
+    /// (e.g. it was timed out).
+    WaiterCancelled,
+    /// The subscriber didn't keep up, so `missed` oldest events were dropped.
+    Lagged { missed: u64 },
+}
+
+/// A stream of [`PoolEvent`]s (see [`crate::Pool::events`]).
+///
+/// Ends once the pool is dropped.
+pub struct PoolEvents {
+    inner: BoxStream<'static, PoolEvent>,
+}
+
+impl PoolEvents {
+    pub(super) fn new(receiver: broadcast::Receiver<PoolEvent>) -> Self {
+        let inner = futures_util::stream::unfold(receiver, |mut receiver| async move {
+            let event = match receiver.recv().await {
+                Ok(event) => event,
+                Err(broadcast::error::RecvError::Lagged(missed)) => PoolEvent::Lagged { missed },
+                Err(broadcast::error::RecvError::Closed) => return None,
+            };
+            Some((event, receiver))
+        });
+        Self {
+            inner: inner.boxed(),
+        }
+    }
+}
+
+impl fmt::Debug for PoolEvents {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PoolEvents").finish_non_exhaustive()
+    }
+}
+
+impl Stream for PoolEvents {
+    type Item = PoolEvent;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.inner.poll_next_unpin(cx)
+    }
+}
@@ -31,7 +31,7 @@ use std::sync::{atomic, Arc};
 #[must_use = "futures do nothing unless you `.await` or poll them"]
 pub struct DisconnectPool {
     pool_inner: Arc<Inner>,
-    drop: Option<UnboundedSender<Option<Conn>>>,
+    drop: Option<Arc<UnboundedSender<Option<Conn>>>>,
 }
 
 impl DisconnectPool {
@@ -10,10 +10,13 @@ use std::{
     fmt,
     future::Future,
     pin::Pin,
+    sync::atomic::Ordering,
     task::{Context, Poll},
+    time::Duration,
 };
 
 use futures_core::ready;
+use tokio::time::{Instant, Sleep};
 #[cfg(feature = "tracing")]
 use {
     std::sync::Arc,
@@ -22,7 +25,7 @@ use {
 
 use crate::{
     conn::{
-        pool::{Pool, QueueId},
+        pool::{events::PoolEvent, Pool, QueueId},
         Conn,
     },
     error::*,
@@ -63,6 +66,12 @@ pub struct GetConn {
     pub(crate) pool: Option<Pool>,
     pub(crate) inner: GetConnInner,
     reset_upon_returning_to_a_pool: bool,
+    /// `true` if an idling connection was found broken, so a new one replaces it.
+    reconnect: bool,
+    /// See [`crate::PoolOpts::with_acquire_timeout`].
+    acquire_timeout: Option<Duration>,
+    /// Started upon the first poll if `acquire_timeout` is set.
+    acquire_deadline: Option<Pin<Box<Sleep>>>,
     #[cfg(feature = "tracing")]
     span: Arc<Span>,
 }
@@ -74,6 +83,9 @@ impl GetConn {
             pool: Some(pool.clone()),
             inner: GetConnInner::New,
             reset_upon_returning_to_a_pool,
+            reconnect: false,
+            acquire_timeout: pool.opts.pool_opts().acquire_timeout(),
+            acquire_deadline: None,
             #[cfg(feature = "tracing")]
             span: Arc::new(debug_span!("mysql_async::get_conn")),
         }
@@ -90,6 +102,28 @@ impl GetConn {
             .take()
             .expect("GetConn::poll polled after returning Async::Ready")
     }
+
+    /// Returns the deadline of this checkout along with `acquire_timeout`, if it's set.
+    ///
+    /// The deadline is started upon the first call.
+    fn acquire_deadline(&mut self) -> Option<(Instant, Duration)> {
+        let timeout = self.acquire_timeout?;
+        let deadline = self
+            .acquire_deadline
+            .get_or_insert_with(|| Box::pin(tokio::time::sleep(timeout)))
+            .deadline();
+        Some((deadline, timeout))
+    }
+
+    /// Returns `true` if this checkout is still waiting for a connection
+    /// after `acquire_timeout` is elapsed.
+    fn poll_acquire_deadline(&mut self, cx: &mut Context<'_>) -> bool {
+        self.acquire_deadline();
+        match self.acquire_deadline {
+            Some(ref mut deadline) => deadline.as_mut().poll(cx).is_ready(),
+            None => false,
+        }
+    }
 }
 
 // this manual implementation of Future may seem stupid, but we sort
@@ -106,7 +140,23 @@ impl Future for GetConn {
             match self.inner {
                 GetConnInner::New => {
                     let queue_id = self.queue_id;
-                    let next = ready!(self.pool_mut().poll_new_conn(cx, queue_id))?;
+                    let reconnect = self.reconnect;
+                    let deadline = self.acquire_deadline();
+                    let pool = self.pool_mut();
+                    let next = match pool.poll_new_conn(cx, queue_id, reconnect, deadline) {
+                        Poll::Ready(next) => next?,
+                        Poll::Pending => {
+                            if self.poll_acquire_deadline(cx) {
+                                let timeout = self.acquire_timeout.unwrap_or_default();
+                                let pool = self.pool_take();
+                                pool.unqueue(queue_id);
+                                self.inner = GetConnInner::Done;
+                                let err = DriverError::PoolAcquireTimeout { timeout };
+                                return Poll::Ready(Err(err.into()));
+                            }
+                            return Poll::Pending;
+                        }
+                    };
                     match next {
                         GetConnInner::Connecting(conn_fut) => {
                             self.inner = GetConnInner::Connecting(conn_fut);
@@ -133,6 +183,13 @@ impl Future for GetConn {
 
                     return match result {
                         Ok(mut c) => {
+                            if let Some(tag) = pool.opts.pool_opts().conn_label() {
+                                let n = pool.inner.labeled.fetch_add(1, Ordering::Relaxed) + 1;
+                                c.inner.label = Some(format!("{}-{}", tag, n));
+                            }
+                            pool.inner.exchange.lock().unwrap().created += 1;
+                            pool.inner.emit(PoolEvent::ConnectionCreated { id: c.id() });
+                            pool.inner.emit(PoolEvent::CheckedOut { id: c.id() });
                             c.inner.pool = Some(pool);
                             c.inner.reset_upon_returning_to_a_pool =
                                 self.reset_upon_returning_to_a_pool;
@@ -151,6 +208,7 @@ impl Future for GetConn {
                             self.inner = GetConnInner::Done;
 
                             let pool = self.pool_take();
+                            pool.inner.emit(PoolEvent::CheckedOut { id: c.id() });
                             c.inner.pool = Some(pool);
                             c.inner.reset_upon_returning_to_a_pool =
                                 self.reset_upon_returning_to_a_pool;
@@ -159,9 +217,11 @@ impl Future for GetConn {
                         Err(_) => {
                             // Idling connection is broken. We'll drop it and try again.
                             self.inner = GetConnInner::New;
+                            self.reconnect = true;
 
+                            // `ConnectionClosed` is emitted by the check itself
                             let pool = self.pool_mut();
-                            pool.cancel_connection();
+                            pool.release_connection(true);
                             continue;
                         }
                     }
@@ -12,6 +12,9 @@ pub struct Metrics {
     pub connections_in_pool: AtomicUsize,
     /// Guage of GetConn requests that are currently active.
     pub active_wait_requests: AtomicUsize,
+    /// Guage of connections that are currently being established by the pool
+    /// (see [`crate::PoolOpts::with_max_concurrent_connects`]).
+    pub connects_in_progress: AtomicUsize,
     /// Counter of connections that failed to be created.
     pub create_failed: AtomicUsize,
     /// Counter of connections discarded due to pool constraints.
@@ -8,7 +8,7 @@
 
 use futures_util::FutureExt;
 use keyed_priority_queue::KeyedPriorityQueue;
-use tokio::sync::mpsc;
+use tokio::sync::{broadcast, mpsc, Semaphore, SemaphorePermit};
 
 use std::{
     borrow::Borrow,
@@ -24,12 +24,26 @@ use std::{
 use crate::{
     conn::{pool::futures::*, Conn},
     error::*,
-    opts::{Opts, PoolOpts},
+    opts::{Opts, PoolMinPolicy, PoolOpts},
     queryable::transaction::{Transaction, TxOpts},
 };
 
 pub use metrics::Metrics;
 
+use self::events::{PoolEvent, PoolEvents, POOL_EVENTS_CAPACITY};
+
+/// Timeout of a single attempt to fill a pool in the background
+/// (see [`PoolMinPolicy::BestEffort`]).
+const FILL_ATTEMPT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Initial delay between attempts to fill a pool in the background. It's doubled after
+/// every failed attempt up to [`FILL_RETRY_MAX_DELAY`].
+const FILL_RETRY_MIN_DELAY: Duration = Duration::from_millis(100);
+
+/// Maximum delay between attempts to fill a pool in the background.
+const FILL_RETRY_MAX_DELAY: Duration = Duration::from_secs(10);
+
+pub mod events;
 mod recycler;
 // this is a really unfortunate name for a module
 pub mod futures;
@@ -79,6 +93,10 @@ struct Exchange {
     waiting: Waitlist,
     available: VecDeque<IdlingConn>,
     exist: usize,
+    /// Number of connections established by the pool so far (see [`PoolStats::created`]).
+    created: usize,
+    /// Number of connections closed by the pool so far (see [`PoolStats::closed`]).
+    closed: usize,
     // only used to spawn the recycler the first time we're in async context
     recycler: Option<(mpsc::UnboundedReceiver<Option<Conn>>, PoolOpts)>,
 }
@@ -94,9 +112,10 @@ impl Exchange {
             tokio::spawn(Recycler::new(pool_opts.clone(), inner.clone(), dropped));
 
             // Spawn the ttl check interval if `inactive_connection_ttl` isn't `0` or
-            // connections have an absolute TTL.
+            // connections have an absolute TTL or a max lifetime.
             if pool_opts.inactive_connection_ttl() > Duration::ZERO
                 || pool_opts.abs_conn_ttl().is_some()
+                || pool_opts.max_connection_lifetime().is_some()
             {
                 tokio::spawn(TtlCheckInterval::new(pool_opts, inner.clone()));
             }
@@ -143,6 +162,10 @@ impl Waitlist {
     fn peek_id(&mut self) -> Option<QueueId> {
         self.queue.peek().map(|(qw, _)| qw.queue_id)
     }
+
+    fn len(&self) -> usize {
+        self.queue.len()
+    }
 }
 
 const QUEUE_END_ID: QueueId = QueueId(Reverse(u64::MAX));
@@ -188,8 +211,15 @@ impl Hash for QueuedWaker {
 #[derive(Debug)]
 pub struct Inner {
     metrics: Arc<Metrics>,
+    events: broadcast::Sender<PoolEvent>,
     close: atomic::AtomicBool,
     closed: atomic::AtomicBool,
+    maintenance_paused: atomic::AtomicBool,
+    /// Number of connections labeled so far (see [`PoolOpts::with_conn_label`]).
+    labeled: atomic::AtomicUsize,
+    /// Bounds the number of concurrent connection attempts
+    /// (see [`PoolOpts::with_max_concurrent_connects`]).
+    connect_permits: Option<Semaphore>,
     exchange: Mutex<Exchange>,
 }
 
@@ -205,7 +235,7 @@ pub struct Inner {
 pub struct Pool {
     opts: Opts,
     inner: Arc<Inner>,
-    drop: mpsc::UnboundedSender<Option<Conn>>,
+    drop: Arc<mpsc::UnboundedSender<Option<Conn>>>,
 }
 
 impl Pool {
@@ -213,7 +243,8 @@ impl Pool {
     ///
     /// # Panic
     ///
-    /// It'll panic if `Opts::try_from(opts)` returns error.
+    /// It'll panic if `Opts::try_from(opts)` returns error (e.g. if `opts` is a malformed
+    /// connection URL). Use `Opts::try_from` to handle the error.
     pub fn new<O>(opts: O) -> Pool
     where
         Opts: TryFrom<O>,
@@ -227,15 +258,24 @@ impl Pool {
             inner: Arc::new(Inner {
                 close: false.into(),
                 closed: false.into(),
+                maintenance_paused: false.into(),
+                labeled: 0.into(),
+                // larger values are rejected by `OptsBuilder::build`, but `Opts::from` doesn't validate
+                connect_permits: pool_opts
+                    .max_concurrent_connects()
+                    .map(|max| Semaphore::new(max.min(Semaphore::MAX_PERMITS))),
                 metrics: Arc::new(Metrics::default()),
+                events: broadcast::channel(POOL_EVENTS_CAPACITY).0,
                 exchange: Mutex::new(Exchange {
                     available: VecDeque::with_capacity(pool_opts.constraints().max()),
                     waiting: Waitlist::default(),
                     exist: 0,
+                    created: 0,
+                    closed: 0,
                     recycler: Some((rx, pool_opts)),
                 }),
             }),
-            drop: tx,
+            drop: Arc::new(tx),
         }
     }
 
@@ -244,6 +284,183 @@ impl Pool {
         self.inner.metrics.clone()
     }
 
+    /// Returns a snapshot of the state of this pool, e.g. for capacity planning.
+    ///
+    /// Unlike [`Pool::metrics`], all the numbers are taken at once under the pool's lock,
+    /// so they are consistent with each other. No connection is checked out.
+    ///
+    /// ```
+    /// # use mysql_async::{test_misc::get_opts, Pool, Result};
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// let pool = Pool::new(get_opts());
+    /// let conn = pool.get_conn().await?;
+    /// let stats = pool.stats();
+    /// assert_eq!(stats.active, 1);
+    /// assert_eq!(stats.in_use, 1);
+    /// assert_eq!(stats.created, 1);
+    /// # drop(conn);
+    /// # pool.disconnect().await?;
+    /// # Ok(()) }
+    /// ```
+    pub fn stats(&self) -> PoolStats {
+        let exchange = self.inner.exchange.lock().unwrap();
+        PoolStats {
+            active: exchange.exist,
+            idle: exchange.available.len(),
+            in_use: exchange.exist.saturating_sub(exchange.available.len()),
+            pending_acquires: exchange.waiting.len(),
+            idle_bound: self.opts.pool_opts().active_bound(),
+            created: exchange.created,
+            closed: exchange.closed,
+        }
+    }
+
+    /// Returns a stream of events of this pool, e.g. for observability purposes.
+    ///
+    /// Every call creates a new subscriber that receives events emitted after the call.
+    /// Each subscriber has a bounded buffer of [`POOL_EVENTS_CAPACITY`] events, so the channel
+    /// is lossy: the pool never waits for subscribers, and a subscriber that doesn't keep up
+    /// loses the oldest events and receives [`PoolEvent::Lagged`] instead. Events are not
+    /// buffered if there are no subscribers.
+    ///
+    /// The stream ends once the pool is dropped.
+    ///
+    /// ```
+    /// # use mysql_async::{test_misc::get_opts, Pool, PoolEvent, Result};
+    /// # use futures_util::StreamExt;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// let pool = Pool::new(get_opts());
+    /// let mut events = pool.events();
+    /// let conn = pool.get_conn().await?;
+    /// assert_eq!(
+    ///     events.next().await,
+    ///     Some(PoolEvent::ConnectionCreated { id: conn.id() })
+    /// );
+    /// # drop(conn);
+    /// # pool.disconnect().await?;
+    /// # Ok(()) }
+    /// ```
+    pub fn events(&self) -> PoolEvents {
+        PoolEvents::new(self.inner.events.subscribe())
+    }
+
+    /// Pauses background maintenance of the pool (see [`Pool::resume_maintenance`]).
+    ///
+    /// While maintenance is paused, the background task keeps running but skips its checks,
+    /// so idle connections are not reaped: connections that exceed `inactive_connection_ttl`
+    /// or `abs_conn_ttl` stay in the pool until maintenance is resumed (they may still be
+    /// handed out by [`Pool::get_conn`]). Connections are neither dropped nor disconnected
+    /// by this call.
+    pub fn pause_maintenance(&self) {
+        self.inner
+            .maintenance_paused
+            .store(true, atomic::Ordering::Release);
+    }
+
+    /// Resumes background maintenance of the pool paused by [`Pool::pause_maintenance`].
+    ///
+    /// Idle connections are checked on the next tick of `ttl_check_interval`.
+    pub fn resume_maintenance(&self) {
+        self.inner
+            .maintenance_paused
+            .store(false, atomic::Ordering::Release);
+    }
+
+    /// Returns `true` if background maintenance is paused (see [`Pool::pause_maintenance`]).
+    pub fn is_maintenance_paused(&self) -> bool {
+        self.inner
+            .maintenance_paused
+            .load(atomic::Ordering::Acquire)
+    }
+
+    /// Creates a new pool of connections and fills it up to the `min` bound of its
+    /// [`PoolConstraints`] according to [`PoolOpts::min_policy`].
+    ///
+    /// Unlike [`Pool::new`], that never establishes connections up front, this function fails
+    /// with [`DriverError::PoolMinNotReached`] under the [`PoolMinPolicy::Strict`] policy
+    /// if the server doesn't accept enough connections in time.
+    ///
+    /// ```
+    /// # use mysql_async::{test_misc::get_opts, *};
+    /// # use std::time::Duration;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// let pool_opts = PoolOpts::new()
+    ///     .with_constraints(PoolConstraints::new(2, 10).unwrap())
+    ///     .with_min_policy(PoolMinPolicy::Strict(Duration::from_secs(10)));
+    /// let pool = Pool::connect(get_opts().pool_opts(pool_opts)).await?;
+    /// assert_eq!(pool.stats().idle, 2);
+    /// # pool.disconnect().await?;
+    /// # Ok(()) }
+    /// ```
+    ///
+    /// [`PoolConstraints`]: crate::PoolConstraints
+    pub async fn connect<T: Into<Opts>>(opts: T) -> Result<Pool> {
+        let pool = Pool::new(opts.into());
+        let min = pool.opts.pool_opts().constraints().min();
+        if min == 0 {
+            return Ok(pool);
+        }
+
+        match pool.opts.pool_opts().min_policy() {
+            PoolMinPolicy::Lazy => (),
+            PoolMinPolicy::BestEffort => {
+                pool.spawn_fill();
+            }
+            PoolMinPolicy::Strict(timeout) => {
+                let result = pool.warmup(timeout).await;
+                if result.ready < min {
+                    pool.disconnect().await?;
+                    return Err(DriverError::PoolMinNotReached {
+                        min,
+                        ready: result.ready,
+                        timeout,
+                    }
+                    .into());
+                }
+            }
+        }
+
+        Ok(pool)
+    }
+
+    /// Fills this pool up to the `min` bound in the background, retrying failed attempts
+    /// (see [`PoolMinPolicy::BestEffort`]).
+    ///
+    /// The task only keeps weak references to the pool in between attempts, so it stops
+    /// once every `Pool` handle is dropped.
+    fn spawn_fill(&self) -> tokio::task::JoinHandle<()> {
+        let opts = self.opts.clone();
+        let inner = Arc::downgrade(&self.inner);
+        let drop = Arc::downgrade(&self.drop);
+        tokio::spawn(async move {
+            let min = opts.pool_opts().constraints().min();
+            let mut delay = FILL_RETRY_MIN_DELAY;
+            loop {
+                let pool = match (inner.upgrade(), drop.upgrade()) {
+                    (Some(inner), Some(drop)) => Pool {
+                        opts: opts.clone(),
+                        inner,
+                        drop,
+                    },
+                    _ => break,
+                };
+                if pool.inner.close.load(atomic::Ordering::Acquire) {
+                    break;
+                }
+                let result = pool.warmup(FILL_ATTEMPT_TIMEOUT).await;
+                if result.ready >= min {
+                    break;
+                }
+                std::mem::drop(pool);
+                tokio::time::sleep(delay).await;
+                delay = std::cmp::min(delay * 2, FILL_RETRY_MAX_DELAY);
+            }
+        })
+    }
+
     /// Creates a new pool of connections.
     pub fn from_url<T: AsRef<str>>(url: T) -> Result<Pool> {
         let opts = Opts::from_str(url.as_ref())?;
@@ -262,6 +479,136 @@ impl Pool {
         Transaction::new(conn, options).await
     }
 
+    /// Runs `f` with a dedicated connection that bypasses the pool.
+    ///
+    /// A fresh connection is opened using the pool's options. It is closed after `f` completes
+    /// and is never placed into the idle queue, so it is a good fit for long-running or
+    /// session-state-heavy operations that shouldn't occupy (or pollute) a pooled connection.
+    ///
+    /// Whether the dedicated connection counts against the `max` bound of the pool constraints
+    /// is controlled by [`PoolOpts::with_count_dedicated_conns`] (defaults to `false`).
+    /// If it's counted and the pool is exhausted, then this function waits for
+    /// a pooled connection and closes it to free a slot.
+    ///
+    /// ```
+    /// # use mysql_async::{prelude::*, test_misc::get_opts, Pool, Result};
+    /// # use futures_util::FutureExt;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// let pool = Pool::new(get_opts());
+    /// let id = pool
+    ///     .with_dedicated_conn(|conn| async move { Ok(conn.id()) }.boxed())
+    ///     .await?;
+    /// # assert!(id > 0);
+    /// # pool.disconnect().await?;
+    /// # Ok(()) }
+    /// ```
+    ///
+    /// [`PoolOpts::with_count_dedicated_conns`]: crate::PoolOpts::with_count_dedicated_conns
+    pub async fn with_dedicated_conn<F, T>(&self, f: F) -> Result<T>
+    where
+        F: for<'a> FnOnce(&'a mut Conn) -> crate::BoxFuture<'a, T>,
+    {
+        let _slot = if self.opts.pool_opts().count_dedicated_conns() {
+            Some(self.reserve_slot().await?)
+        } else if self.inner.close.load(atomic::Ordering::Acquire) {
+            return Err(DriverError::PoolDisconnected.into());
+        } else {
+            None
+        };
+
+        let mut conn = Conn::new(self.opts.clone()).await?;
+        let result = f(&mut conn).await;
+        let disconnected = conn.disconnect().await;
+        let output = result?;
+        disconnected?;
+        Ok(output)
+    }
+
+    /// Reserves a slot within the `max` bound of the pool constraints.
+    ///
+    /// If the pool is exhausted, then a pooled connection is taken and closed.
+    async fn reserve_slot(&self) -> Result<ReservedSlot> {
+        {
+            let mut exchange = self.inner.exchange.lock().unwrap();
+            if self.inner.close.load(atomic::Ordering::Acquire) {
+                return Err(DriverError::PoolDisconnected.into());
+            }
+            if exchange.exist < self.opts.pool_opts().constraints().max() {
+                exchange.exist += 1;
+                self.inner
+                    .metrics
+                    .connection_count
+                    .store(exchange.exist, atomic::Ordering::Relaxed);
+                return Ok(ReservedSlot(self.clone()));
+            }
+        }
+
+        let mut conn = self.get_conn().await?;
+        // The connection now holds a slot that is handed over to the `ReservedSlot`.
+        let slot = ReservedSlot(conn.inner.pool.take().expect("pooled connection"));
+        self.inner.exchange.lock().unwrap().closed += 1;
+        self.inner
+            .emit(PoolEvent::ConnectionClosed { id: conn.id() });
+        conn.disconnect().await?;
+        Ok(slot)
+    }
+
+    /// Pre-fills the pool with connections up to the `min` bound of its [`PoolConstraints`].
+    ///
+    /// Takes `min` connections from the pool at once – idle connections are reused and the rest
+    /// are established concurrently. They are returned to the pool once all the attempts
+    /// are finished, so the pool holds at least `min` connections unless some attempts failed.
+    ///
+    /// Never fails as a whole – errors are collected into the returned [`WarmupResult`] along
+    /// with the number of connections that are ready. If `timeout` is given, then connections
+    /// that aren't ready within it are abandoned and [`WarmupResult::timed_out`] is set,
+    /// so that a slow or unreachable server won't block the caller indefinitely.
+    ///
+    /// This future is cancellation-safe: if it's dropped, then pending connection attempts
+    /// are abandoned and ready connections are returned to the pool.
+    ///
+    /// ```
+    /// # use mysql_async::{test_misc::get_opts, Pool, PoolConstraints, PoolOpts, Result};
+    /// # use std::time::Duration;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// let constraints = PoolConstraints::new(2, 10).unwrap();
+    /// let pool = Pool::new(get_opts().pool_opts(PoolOpts::new().with_constraints(constraints)));
+    /// let result = pool.warmup(Duration::from_secs(10)).await;
+    /// assert_eq!(result.ready, 2);
+    /// assert!(result.errors.is_empty());
+    /// # pool.disconnect().await?;
+    /// # Ok(()) }
+    /// ```
+    pub async fn warmup<T: Into<Option<Duration>>>(&self, timeout: T) -> WarmupResult {
+        let deadline = timeout
+            .into()
+            .map(|timeout| tokio::time::Instant::now() + timeout);
+        let min = self.opts.pool_opts().constraints().min();
+
+        let attempts = (0..min).map(|_| async move {
+            match deadline {
+                Some(deadline) => tokio::time::timeout_at(deadline, self.get_conn()).await,
+                None => Ok(self.get_conn().await),
+            }
+        });
+
+        let mut result = WarmupResult::default();
+        // connections are held until the end, so that each attempt gets a separate one
+        let mut conns = Vec::with_capacity(min);
+        for attempt in futures_util::future::join_all(attempts).await {
+            match attempt {
+                Ok(Ok(conn)) => conns.push(conn),
+                Ok(Err(err)) => result.errors.push(err),
+                Err(_elapsed) => result.timed_out = true,
+            }
+        }
+        result.ready = conns.len();
+
+        result
+    }
+
     /// Async function that disconnects this pool from the server and resolves to `()`.
     ///
     /// **Note:** This Future won't resolve until all active connections, taken from it,
@@ -299,8 +646,34 @@ impl Pool {
     /// Decreases the exist counter since a broken or dropped connection should not count towards
     /// the total.
     pub(super) fn cancel_connection(&self) {
+        self.release_connection(false);
+    }
+
+    /// Like [`Pool::cancel_connection`], but for an established connection that leaves the pool
+    /// (see [`PoolStats::closed`]).
+    pub(super) fn close_connection(&self, id: u32) {
+        self.inner.emit(PoolEvent::ConnectionClosed { id });
+        self.release_connection(true);
+    }
+
+    /// Accounts for a pooled connection that was replaced by a new one
+    /// (see [`crate::Opts::reconnect_on_wait_timeout`]).
+    pub(super) fn replace_connection(&self, old_id: u32, new_id: u32) {
+        {
+            let mut exchange = self.inner.exchange.lock().unwrap();
+            exchange.created += 1;
+            exchange.closed += 1;
+        }
+        self.inner.emit(PoolEvent::ConnectionClosed { id: old_id });
+        self.inner.emit(PoolEvent::ConnectionCreated { id: new_id });
+    }
+
+    fn release_connection(&self, closed: bool) {
         let mut exchange = self.inner.exchange.lock().unwrap();
         exchange.exist -= 1;
+        if closed {
+            exchange.closed += 1;
+        }
         self.inner
             .metrics
             .create_failed
@@ -316,10 +689,15 @@ impl Pool {
     }
 
     /// Poll the pool for an available connection.
+    ///
+    /// `reconnect` is `true` if the new connection replaces a broken one
+    /// (see [`PoolOpts::with_reinit_on_reconnect`]).
     fn poll_new_conn(
         &mut self,
         cx: &mut Context<'_>,
         queue_id: QueueId,
+        reconnect: bool,
+        acquire_deadline: Option<(tokio::time::Instant, Duration)>,
     ) -> Poll<Result<GetConnInner>> {
         let mut exchange = self.inner.exchange.lock().unwrap();
 
@@ -346,6 +724,7 @@ impl Pool {
                     .metrics
                     .active_wait_requests
                     .fetch_add(1, atomic::Ordering::Relaxed);
+                self.inner.emit(PoolEvent::WaiterEnqueued);
             }
             return Poll::Pending;
         }
@@ -362,10 +741,20 @@ impl Pool {
                     .saturating_record(since.elapsed().as_micros() as u64);
                 #[cfg(feature = "hdrhistogram")]
                 let metrics = self.metrics();
+                let inner = self.inner.clone();
+                let id = conn.id();
                 conn.inner.active_since = Instant::now();
                 return Poll::Ready(Ok(GetConnInner::Checking(
                     async move {
-                        conn.stream_mut()?.check().await?;
+                        let checked = match conn.stream_mut() {
+                            Ok(stream) => stream.check().await.map_err(Error::from),
+                            Err(err) => Err(err),
+                        };
+                        if let Err(err) = checked {
+                            inner.emit(PoolEvent::ValidationFailed { id });
+                            inner.emit(PoolEvent::ConnectionClosed { id });
+                            return Err(err);
+                        }
                         #[cfg(feature = "hdrhistogram")]
                         metrics
                             .check_duration
@@ -400,15 +789,17 @@ impl Pool {
                 .store(exchange.exist, atomic::Ordering::Relaxed);
 
             let opts = self.opts.clone();
-            #[cfg(feature = "hdrhistogram")]
-            let metrics = self.metrics();
+            let init = !reconnect || opts.pool_opts().reinit_on_reconnect();
+            let inner = self.inner.clone();
 
             return Poll::Ready(Ok(GetConnInner::Connecting(
                 async move {
-                    let conn = Conn::new(opts).await;
+                    let _in_progress = ConnectInProgress::start(&inner, acquire_deadline).await?;
+                    let conn = Conn::new_with_init(opts, init).await;
                     #[cfg(feature = "hdrhistogram")]
                     if let Ok(conn) = &conn {
-                        metrics
+                        inner
+                            .metrics
                             .connect_duration
                             .lock()
                             .unwrap()
@@ -428,6 +819,7 @@ impl Pool {
                 .metrics
                 .active_wait_requests
                 .fetch_add(1, atomic::Ordering::Relaxed);
+            self.inner.emit(PoolEvent::WaiterEnqueued);
         }
         Poll::Pending
     }
@@ -439,18 +831,128 @@ impl Pool {
                 .metrics
                 .active_wait_requests
                 .fetch_sub(1, atomic::Ordering::Relaxed);
+            self.inner.emit(PoolEvent::WaiterCancelled);
         }
     }
 }
 
+/// An ongoing connection attempt of a pool (see [`Metrics::connects_in_progress`]).
+struct ConnectInProgress<'a> {
+    metrics: &'a Metrics,
+    _permit: Option<SemaphorePermit<'a>>,
+}
+
+impl<'a> ConnectInProgress<'a> {
+    /// Waits for a permit if the number of concurrent connection attempts is bounded.
+    ///
+    /// The wait is a part of the checkout, so it's bounded by `acquire_deadline`
+    /// (see [`PoolOpts::with_acquire_timeout`]).
+    async fn start(
+        inner: &'a Inner,
+        acquire_deadline: Option<(tokio::time::Instant, Duration)>,
+    ) -> Result<ConnectInProgress<'a>> {
+        let permit = match (&inner.connect_permits, acquire_deadline) {
+            (Some(permits), Some((deadline, timeout))) => {
+                match tokio::time::timeout_at(deadline, permits.acquire()).await {
+                    // the semaphore is never closed
+                    Ok(permit) => permit.ok(),
+                    Err(_) => return Err(DriverError::PoolAcquireTimeout { timeout }.into()),
+                }
+            }
+            // the semaphore is never closed
+            (Some(permits), None) => permits.acquire().await.ok(),
+            (None, _) => None,
+        };
+        inner
+            .metrics
+            .connects_in_progress
+            .fetch_add(1, atomic::Ordering::Relaxed);
+        Ok(ConnectInProgress {
+            metrics: &inner.metrics,
+            _permit: permit,
+        })
+    }
+}
+
+impl Drop for ConnectInProgress<'_> {
+    fn drop(&mut self) {
+        self.metrics
+            .connects_in_progress
+            .fetch_sub(1, atomic::Ordering::Relaxed);
+    }
+}
+
+impl Inner {
+    /// Emits the event to subscribers, if any (see [`Pool::events`]).
+    fn emit(&self, event: PoolEvent) {
+        // fails if there are no subscribers
+        let _ = self.events.send(event);
+    }
+}
+
+/// Outcome of [`Pool::warmup`].
+#[derive(Debug, Default)]
+#[non_exhaustive]
+pub struct WarmupResult {
+    /// Number of connections that are ready (both newly established and already idle ones).
+    pub ready: usize,
+    /// Errors of failed connection attempts.
+    pub errors: Vec<Error>,
+    /// `true` if some connection attempts were abandoned due to the timeout.
+    pub timed_out: bool,
+}
+
+/// A snapshot of the state of a pool (see [`Pool::stats`]).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[non_exhaustive]
+pub struct PoolStats {
+    /// Number of connections that count towards the `max` bound of the pool constraints.
+    ///
+    /// It's the sum of `idle` and `in_use`.
+    pub active: usize,
+    /// Number of connections idling in the pool.
+    pub idle: usize,
+    /// Number of connections that aren't idling in the pool, i.e. checked out ones, as well as
+    /// ones that are being established or are on their way back to the pool.
+    pub in_use: usize,
+    /// Number of [`Pool::get_conn`] calls waiting for a connection.
+    pub pending_acquires: usize,
+    /// Number of idle connections the pool keeps – connections returned to a pool
+    /// with this many idle connections are closed.
+    ///
+    /// It's the `max` bound of the pool constraints if `inactive_connection_ttl` is non-zero
+    /// and the `min` bound otherwise (see [`PoolOpts::with_inactive_connection_ttl`]).
+    pub idle_bound: usize,
+    /// Number of connections established by the pool so far.
+    pub created: usize,
+    /// Number of connections closed by the pool so far, including broken ones
+    /// and ones taken out of the pool.
+    pub closed: usize,
+}
+
+/// A slot within the `max` bound of the pool constraints held by a dedicated connection.
+///
+/// The slot is released on drop.
+#[derive(Debug)]
+struct ReservedSlot(Pool);
+
+impl Drop for ReservedSlot {
+    fn drop(&mut self) {
+        // a pooled connection that handed over its slot is accounted as closed
+        // by `Pool::reserve_slot`
+        self.0.release_connection(false);
+    }
+}
+
 impl Drop for Conn {
     fn drop(&mut self) {
         self.inner.infile_handler = None;
+        self.inner.query_cache = None;
 
         if std::thread::panicking() {
             // Try to decrease the number of existing connections.
             if let Some(pool) = self.inner.pool.take() {
-                pool.cancel_connection();
+                pool.close_connection(self.inner.id);
             }
 
             return;
@@ -477,7 +979,10 @@ mod test {
         cmp::Reverse,
         future::Future,
         pin::pin,
-        sync::{Arc, OnceLock},
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc, OnceLock,
+        },
         task::{Context, Poll, RawWaker, RawWakerVTable, Waker},
         time::Duration,
     };
@@ -486,8 +991,8 @@ mod test {
         conn::pool::{Pool, QueueId, Waitlist, QUEUE_END_ID},
         opts::PoolOpts,
         prelude::*,
-        test_misc::get_opts,
-        PoolConstraints, Row, TxOpts, Value,
+        test_misc::{get_opts, max_while},
+        DriverError, Error, PoolConstraints, Row, TxOpts, Value,
     };
 
     macro_rules! conn_ex_field {
@@ -586,6 +1091,343 @@ mod test {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn should_run_closure_with_dedicated_conn() -> super::Result<()> {
+        let constraints = PoolConstraints::new(1, 1).unwrap();
+        let pool_opts = PoolOpts::default().with_constraints(constraints);
+        let pool = Pool::new(get_opts().pool_opts(pool_opts.clone()));
+
+        // not counted against `pool_max`, so it works while the only pooled connection is taken
+        let mut pooled = pool.get_conn().await?;
+        let dedicated_id = pool
+            .with_dedicated_conn(|conn| {
+                async move {
+                    assert!(conn.inner.pool.is_none());
+                    conn.query_drop("SET @session_state = 42").await?;
+                    Ok(conn.id())
+                }
+                .boxed()
+            })
+            .await?;
+        assert_eq!(ex_field!(pool, exist), 1);
+        assert_eq!(ex_field!(pool, available).len(), 0);
+
+        // the dedicated connection is closed
+        sleep(Duration::from_millis(100)).await;
+        let count: usize = pooled
+            .exec_first(
+                "SELECT COUNT(*) FROM information_schema.processlist WHERE id = ?",
+                (dedicated_id,),
+            )
+            .await?
+            .unwrap();
+        assert_eq!(count, 0);
+        drop(pooled);
+        pool.disconnect().await?;
+
+        // counted against `pool_max`
+        let pool = Pool::new(get_opts().pool_opts(pool_opts.with_count_dedicated_conns(true)));
+        let pooled_id = pool.get_conn().await?.id();
+        sleep(Duration::from_millis(100)).await;
+        assert_eq!(ex_field!(pool, available).len(), 1);
+
+        let (dedicated_id, exist) = pool
+            .with_dedicated_conn(|conn| {
+                let exist = ex_field!(pool, exist);
+                async move { Ok((conn.id(), exist)) }.boxed()
+            })
+            .await?;
+        assert_ne!(dedicated_id, pooled_id);
+        assert_eq!(exist, 1);
+        assert_eq!(ex_field!(pool, exist), 0);
+        assert_eq!(ex_field!(pool, available).len(), 0);
+
+        let mut conn = pool.get_conn().await?;
+        assert_ne!(conn.id(), dedicated_id);
+        assert_ne!(conn.id(), pooled_id);
+        conn.ping().await?;
+        drop(conn);
+
+        pool.disconnect().await
+    }
+
+    #[tokio::test]
+    async fn should_warmup_pool() -> super::Result<()> {
+        let constraints = PoolConstraints::new(3, 5).unwrap();
+        let pool = Pool::new(get_opts().pool_opts(PoolOpts::new().with_constraints(constraints)));
+
+        let result = pool.warmup(None).await;
+        assert_eq!(result.ready, 3);
+        assert!(result.errors.is_empty());
+        assert!(!result.timed_out);
+        assert_eq!(ex_field!(pool, exist), 3);
+
+        // idle connections are reused once the recycler has returned them to the pool
+        timeout(Duration::from_secs(10), async {
+            while pool.stats().idle < 3 {
+                sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("connections weren't returned to the pool");
+        let result = pool.warmup(Duration::from_secs(10)).await;
+        assert_eq!(result.ready, 3);
+        assert_eq!(ex_field!(pool, exist), 3);
+
+        pool.disconnect().await
+    }
+
+    #[tokio::test]
+    async fn should_report_partial_warmup() -> super::Result<()> {
+        use tokio::net::{TcpListener, TcpStream};
+
+        let opts = crate::Opts::from(get_opts());
+        let server = (opts.ip_or_hostname().to_owned(), opts.tcp_port());
+
+        // proxies first two connections to the server, drops the third one
+        // and never answers others
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let port = listener.local_addr()?.port();
+        tokio::spawn(async move {
+            let mut stalled = Vec::new();
+            for i in 0.. {
+                let (mut client, _) = listener.accept().await?;
+                match i {
+                    0 | 1 => {
+                        let mut upstream = TcpStream::connect(server.clone()).await?;
+                        tokio::spawn(async move {
+                            tokio::io::copy_bidirectional(&mut client, &mut upstream).await
+                        });
+                    }
+                    2 => drop(client),
+                    _ => stalled.push(client),
+                }
+            }
+            std::io::Result::Ok(())
+        });
+
+        let constraints = PoolConstraints::new(5, 5).unwrap();
+        let opts = get_opts()
+            .ip_or_hostname("127.0.0.1")
+            .tcp_port(port)
+            .prefer_socket(false)
+            .pool_opts(PoolOpts::new().with_constraints(constraints));
+        let pool = Pool::new(opts);
+
+        let result = pool.warmup(Duration::from_secs(2)).await;
+        assert_eq!(result.ready, 2);
+        assert_eq!(result.errors.len(), 1);
+        assert!(result.timed_out);
+
+        // abandoned attempts don't hold slots
+        assert_eq!(ex_field!(pool, exist), 2);
+
+        pool.disconnect().await
+    }
+
+    /// Proxies the first `accepted` connections to the test server and drops other ones.
+    ///
+    /// Also returns the number of incoming connections so far.
+    async fn limited_server(
+        accepted: usize,
+    ) -> super::Result<(crate::OptsBuilder, Arc<AtomicUsize>)> {
+        use tokio::net::{TcpListener, TcpStream};
+
+        let opts = crate::Opts::from(get_opts());
+        let server = (opts.ip_or_hostname().to_owned(), opts.tcp_port());
+
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let port = listener.local_addr()?.port();
+        let incoming = Arc::new(AtomicUsize::new(0));
+        let counter = incoming.clone();
+        tokio::spawn(async move {
+            for _ in 0.. {
+                let (mut client, _) = listener.accept().await?;
+                if counter.fetch_add(1, Ordering::SeqCst) < accepted {
+                    let mut upstream = TcpStream::connect(server.clone()).await?;
+                    tokio::spawn(async move {
+                        tokio::io::copy_bidirectional(&mut client, &mut upstream).await
+                    });
+                }
+            }
+            std::io::Result::Ok(())
+        });
+
+        let opts = get_opts()
+            .ip_or_hostname("127.0.0.1")
+            .tcp_port(port)
+            .prefer_socket(false);
+        Ok((opts, incoming))
+    }
+
+    #[tokio::test]
+    async fn should_apply_pool_min_policy() -> super::Result<()> {
+        use crate::PoolMinPolicy;
+
+        let pool_opts = |policy| {
+            PoolOpts::new()
+                .with_constraints(PoolConstraints::new(3, 5).unwrap())
+                .with_min_policy(policy)
+        };
+
+        // lazy: nothing is established up front
+        let (opts, _) = limited_server(2).await?;
+        let pool = Pool::connect(opts.pool_opts(pool_opts(PoolMinPolicy::Lazy))).await?;
+        assert_eq!(pool.stats().active, 0);
+        pool.disconnect().await?;
+
+        // strict: fails if `min` connections can't be established
+        let (opts, _) = limited_server(2).await?;
+        let strict_timeout = Duration::from_secs(5);
+        let pool_opts_strict = pool_opts(PoolMinPolicy::Strict(strict_timeout));
+        match Pool::connect(opts.pool_opts(pool_opts_strict.clone())).await {
+            Err(Error::Driver(DriverError::PoolMinNotReached {
+                min: 3,
+                ready: 2,
+                timeout,
+            })) => assert_eq!(timeout, strict_timeout),
+            other => panic!("expected PoolMinNotReached, got {:?}", other),
+        }
+        let pool = Pool::connect(get_opts().pool_opts(pool_opts_strict)).await?;
+        assert_eq!(pool.stats().idle, 3);
+        pool.disconnect().await?;
+
+        // best effort: keeps established connections and retries in the background
+        let (opts, incoming) = limited_server(2).await?;
+        let pool = Pool::connect(opts.pool_opts(pool_opts(PoolMinPolicy::BestEffort))).await?;
+        let connect_attempts = incoming.load(Ordering::SeqCst);
+        timeout(Duration::from_secs(10), async {
+            // wait for two background retries past the initial attempts
+            while pool.stats().idle < 2 || incoming.load(Ordering::SeqCst) < connect_attempts + 2 {
+                tokio::time::sleep(Duration::from_millis(50)).await;
+            }
+        })
+        .await
+        .expect("background fill didn't retry");
+        // retries are rejected by the server, so nothing is created past the accepted two
+        assert_eq!(pool.stats().active, 2);
+        assert_eq!(pool.stats().created, 2);
+        pool.disconnect().await?;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn should_stop_background_fill_when_pool_is_dropped() -> super::Result<()> {
+        let (opts, _) = limited_server(0).await?;
+        let pool = Pool::new(
+            opts.pool_opts(PoolOpts::new().with_constraints(PoolConstraints::new(3, 5).unwrap())),
+        );
+        let fill = pool.spawn_fill();
+        let inner = Arc::downgrade(&pool.inner);
+        drop(pool);
+
+        timeout(Duration::from_secs(30), fill)
+            .await
+            .expect("background fill outlived the pool")
+            .unwrap();
+        timeout(Duration::from_secs(10), async {
+            while inner.upgrade().is_some() {
+                tokio::time::sleep(Duration::from_millis(50)).await;
+            }
+        })
+        .await
+        .expect("pool wasn't released");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn should_emit_pool_events() -> super::Result<()> {
+        use futures_util::StreamExt;
+
+        use crate::{PoolEvent, PoolEvents};
+
+        async fn next_event(events: &mut PoolEvents) -> PoolEvent {
+            timeout(Duration::from_secs(10), events.next())
+                .await
+                .expect("no pool event")
+                .expect("event stream ended")
+        }
+
+        let pool = pool_with_one_connection();
+        let mut events = pool.events();
+
+        let conn = pool.get_conn().await?;
+        let id = conn.id();
+        assert_eq!(
+            next_event(&mut events).await,
+            PoolEvent::ConnectionCreated { id }
+        );
+        assert_eq!(next_event(&mut events).await, PoolEvent::CheckedOut { id });
+
+        drop(conn);
+        assert_eq!(next_event(&mut events).await, PoolEvent::Returned { id });
+
+        let conn = pool.get_conn().await?;
+        assert_eq!(next_event(&mut events).await, PoolEvent::CheckedOut { id });
+
+        // the only connection is taken, so this waiter times out
+        assert!(timeout(Duration::from_millis(100), pool.get_conn())
+            .await
+            .is_err());
+        assert_eq!(next_event(&mut events).await, PoolEvent::WaiterEnqueued);
+        assert_eq!(next_event(&mut events).await, PoolEvent::WaiterCancelled);
+
+        // multiple subscribers receive the same events
+        let mut other_events = pool.events();
+        drop(conn);
+        assert_eq!(next_event(&mut events).await, PoolEvent::Returned { id });
+        assert_eq!(
+            next_event(&mut other_events).await,
+            PoolEvent::Returned { id }
+        );
+
+        // a broken connection is closed by the recycler
+        let mut conn = pool.get_conn().await?;
+        assert_eq!(next_event(&mut events).await, PoolEvent::CheckedOut { id });
+        conn.inner.disconnected = true;
+        drop(conn);
+        assert_eq!(
+            next_event(&mut events).await,
+            PoolEvent::ConnectionClosed { id }
+        );
+
+        // a connection detached by `change_user` leaves the pool
+        let mut conn = pool.get_conn().await?;
+        let id = conn.id();
+        assert_eq!(
+            next_event(&mut events).await,
+            PoolEvent::ConnectionCreated { id }
+        );
+        assert_eq!(next_event(&mut events).await, PoolEvent::CheckedOut { id });
+        let opts = crate::ChangeUserOpts::default().with_db_name(Some("information_schema".into()));
+        conn.change_user(opts).await?;
+        assert_eq!(
+            next_event(&mut events).await,
+            PoolEvent::ConnectionClosed { id }
+        );
+        conn.disconnect().await?;
+
+        let conn = pool.get_conn().await?;
+        let id = conn.id();
+        assert_eq!(
+            next_event(&mut events).await,
+            PoolEvent::ConnectionCreated { id }
+        );
+        assert_eq!(next_event(&mut events).await, PoolEvent::CheckedOut { id });
+        drop(conn);
+        assert_eq!(next_event(&mut events).await, PoolEvent::Returned { id });
+
+        pool.disconnect().await?;
+        assert_eq!(
+            next_event(&mut events).await,
+            PoolEvent::ConnectionClosed { id }
+        );
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn should_connect() -> super::Result<()> {
         let pool = Pool::new(crate::Opts::from(get_opts()));
@@ -654,6 +1496,50 @@ mod test {
         master.disconnect().await
     }
 
+    #[tokio::test]
+    async fn should_reinit_replacement_connection_if_requested() -> super::Result<()> {
+        let mut master = crate::Conn::new(get_opts()).await?;
+
+        for reinit in [true, false] {
+            // connection reset would also clear the user variable
+            let pool_opts = PoolOpts::new()
+                .with_constraints(PoolConstraints::new(1, 1).unwrap())
+                .with_reset_connection(false)
+                .with_reinit_on_reconnect(reinit);
+            let opts = get_opts()
+                .init(vec!["SET @initialized = 1"])
+                .charset(Some("latin1"))
+                .time_zone(Some("+03:00"))
+                .pool_opts(pool_opts);
+            let pool = Pool::new(opts);
+
+            let mut conn = pool.get_conn().await?;
+            let initialized: Option<u8> = conn.query_first("SELECT @initialized").await?.unwrap();
+            assert_eq!(initialized, Some(1));
+            let id = conn.id();
+            drop(conn);
+
+            master.query_drop(format!("KILL {}", id)).await?;
+            sleep(Duration::from_millis(500)).await;
+
+            // the broken idling connection is transparently replaced
+            let mut conn = pool.get_conn().await?;
+            assert_ne!(conn.id(), id);
+            let initialized: Option<u8> = conn.query_first("SELECT @initialized").await?.unwrap();
+            assert_eq!(initialized, if reinit { Some(1) } else { None });
+            // session settings are applied regardless
+            let charset: Option<String> = conn.query_first("SELECT @@character_set_client").await?;
+            assert_eq!(charset.as_deref(), Some("latin1"));
+            let time_zone: Option<String> = conn.query_first("SELECT @@time_zone").await?;
+            assert_eq!(time_zone.as_deref(), Some("+03:00"));
+
+            drop(conn);
+            pool.disconnect().await?;
+        }
+
+        master.disconnect().await
+    }
+
     #[tokio::test]
     async fn should_reuse_connections() -> super::Result<()> {
         let pool = pool_with_one_connection();
@@ -724,6 +1610,49 @@ mod test {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn should_rollback_raw_transaction() -> super::Result<()> {
+        // connection reset would also rollback the transaction
+        let pool_opts = PoolOpts::new()
+            .with_constraints(PoolConstraints::new(1, 1).unwrap())
+            .with_reset_connection(false);
+        let pool = Pool::new(get_opts().pool_opts(pool_opts));
+
+        let mut conn = pool.get_conn().await?;
+        conn.query_drop("CREATE TABLE IF NOT EXISTS mysql.raw_tx_tmp(id int)")
+            .await?;
+        conn.query_drop("DELETE FROM mysql.raw_tx_tmp").await?;
+        assert!(!conn.in_transaction());
+
+        conn.query_drop("BEGIN").await?;
+        assert!(conn.in_transaction());
+        conn.query_drop("INSERT INTO mysql.raw_tx_tmp (id) VALUES (1)")
+            .await?;
+        // errors don't end the transaction
+        assert!(conn
+            .query_drop("SELECT * FROM mysql.no_such_table")
+            .await
+            .is_err());
+        assert!(conn.in_transaction());
+        drop(conn);
+
+        let mut conn = pool.get_conn().await?;
+        assert!(!conn.in_transaction());
+        let count: Option<u8> = conn
+            .query_first("SELECT COUNT(*) FROM mysql.raw_tx_tmp")
+            .await?;
+        assert_eq!(count, Some(0));
+
+        conn.query_drop("BEGIN").await?;
+        conn.query_drop("COMMIT").await?;
+        assert!(!conn.in_transaction());
+
+        conn.query_drop("DROP TABLE mysql.raw_tx_tmp").await?;
+        drop(conn);
+        pool.disconnect().await?;
+        Ok(())
+    }
+
     #[tokio::test]
     async fn should_check_inactive_connection_ttl() -> super::Result<()> {
         const POOL_MIN: usize = 5;
@@ -765,6 +1694,75 @@ mod test {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn should_label_connections() -> super::Result<()> {
+        let pool_opts = PoolOpts::default().with_conn_label("worker");
+        let pool = Pool::new(get_opts().pool_opts(pool_opts));
+
+        let mut first = pool.get_conn().await?;
+        let second = pool.get_conn().await?;
+        let info = first.conn_info();
+        assert_eq!(info.id, first.id());
+        assert_eq!(info.label.as_deref(), Some("worker-1"));
+        assert!(info.pooled);
+        assert_eq!(second.conn_info().label.as_deref(), Some("worker-2"));
+
+        // the caller's label replaces the one set by the pool and persists in the pool
+        first.set_label("reports");
+        assert_eq!(first.conn_info().label.as_deref(), Some("reports"));
+        let id = first.id();
+        drop(first);
+        drop(second);
+        sleep(Duration::from_millis(100)).await;
+
+        let conns = try_join_all((0..2).map(|_| pool.get_conn())).await?;
+        let reused = conns.iter().find(|conn| conn.id() == id).unwrap();
+        assert_eq!(reused.label(), Some("reports"));
+        drop(conns);
+
+        pool.disconnect().await
+    }
+
+    #[tokio::test]
+    async fn should_not_reap_connections_while_maintenance_is_paused() -> super::Result<()> {
+        const POOL_MAX: usize = 3;
+
+        const INACTIVE_CONNECTION_TTL: Duration = Duration::from_millis(500);
+        const TTL_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+        let constraints = PoolConstraints::new(0, POOL_MAX).unwrap();
+        let pool_opts = PoolOpts::default()
+            .with_constraints(constraints)
+            .with_inactive_connection_ttl(INACTIVE_CONNECTION_TTL)
+            .with_ttl_check_interval(TTL_CHECK_INTERVAL);
+
+        let pool = Pool::new(get_opts().pool_opts(pool_opts));
+        let conns = (0..POOL_MAX).map(|_| pool.get_conn()).collect::<Vec<_>>();
+        let conns = try_join_all(conns).await?;
+
+        pool.pause_maintenance();
+        assert!(pool.is_maintenance_paused());
+        drop(conns);
+
+        // wait for a few ttl checks
+        sleep(TTL_CHECK_INTERVAL * 2 + Duration::from_millis(500)).await;
+
+        // connections are still there, although they've exceeded inactive_connection_ttl
+        assert_eq!(ex_field!(pool, available).len(), POOL_MAX);
+        assert_eq!(ex_field!(pool, exist), POOL_MAX);
+
+        pool.resume_maintenance();
+        assert!(!pool.is_maintenance_paused());
+
+        // wait for the next ttl check
+        sleep(TTL_CHECK_INTERVAL + Duration::from_millis(500)).await;
+
+        assert_eq!(ex_field!(pool, available).len(), 0);
+
+        pool.disconnect().await?;
+        Ok(())
+    }
+
     #[tokio::test]
     async fn aa_should_hold_bounds2() -> super::Result<()> {
         use std::cmp::min;
@@ -1139,6 +2137,178 @@ mod test {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn should_retire_conn_after_max_lifetime() -> super::Result<()> {
+        let pool_opts = PoolOpts::default()
+            .with_constraints(PoolConstraints::new(1, 1).unwrap())
+            .with_inactive_connection_ttl(Duration::from_secs(99))
+            .with_ttl_check_interval(Duration::from_secs(99))
+            .with_max_connection_lifetime(Some(Duration::from_secs(1)));
+
+        let pool = Pool::new(get_opts().pool_opts(pool_opts));
+
+        let conn = pool.get_conn().await?;
+        let old_id = conn.id();
+        drop(conn);
+
+        // Still within its lifetime, so the same connection is reused.
+        let conn = pool.get_conn().await?;
+        assert_eq!(conn.id(), old_id);
+
+        sleep(Duration::from_millis(1500)).await;
+
+        // Expired while checked out, so it isn't returned to the pool.
+        drop(conn);
+        let conn = pool.get_conn().await?;
+        assert_ne!(conn.id(), old_id);
+        let new_id = conn.id();
+        drop(conn);
+
+        sleep(Duration::from_millis(1500)).await;
+
+        // Expired while idling, so it isn't handed out.
+        let conn = pool.get_conn().await?;
+        assert_ne!(conn.id(), new_id);
+        drop(conn);
+
+        pool.disconnect().await?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn should_time_out_saturated_checkout() -> super::Result<()> {
+        let pool_opts = PoolOpts::default()
+            .with_constraints(PoolConstraints::new(1, 1).unwrap())
+            .with_acquire_timeout(Some(Duration::from_millis(200)));
+
+        let pool = Pool::new(get_opts().pool_opts(pool_opts));
+
+        let conn = pool.get_conn().await?;
+        match pool.get_conn().await {
+            Err(Error::Driver(DriverError::PoolAcquireTimeout { timeout })) => {
+                assert_eq!(timeout, Duration::from_millis(200));
+            }
+            other => panic!(
+                "expected PoolAcquireTimeout, got {:?}",
+                other.map(|c| c.id())
+            ),
+        }
+        assert_eq!(ex_field!(pool, waiting).queue.len(), 0);
+
+        drop(conn);
+        let conn = pool.get_conn().await?;
+        drop(conn);
+
+        pool.disconnect().await?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn should_limit_concurrent_connects() -> super::Result<()> {
+        const LIMIT: usize = 2;
+
+        let pool_opts = PoolOpts::default()
+            .with_constraints(PoolConstraints::new(0, 10).unwrap())
+            .with_max_concurrent_connects(Some(LIMIT));
+
+        let pool = Pool::new(get_opts().pool_opts(pool_opts));
+        let metrics = pool.metrics();
+
+        let (conns, max_seen) = max_while(try_join_all((0..10).map(|_| pool.get_conn())), || {
+            metrics.connects_in_progress.load(Ordering::Relaxed)
+        })
+        .await;
+        let conns = conns?;
+
+        assert!(max_seen <= LIMIT, "{} connects in parallel", max_seen);
+        assert_eq!(ex_field!(pool, exist), 10);
+        assert_eq!(metrics.connects_in_progress.load(Ordering::Relaxed), 0);
+
+        drop(conns);
+        pool.disconnect().await?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn should_time_out_waiting_for_connect_permit() -> super::Result<()> {
+        // never accepts, so connection attempts hang waiting for the handshake
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+        let pool_opts = PoolOpts::default()
+            .with_max_concurrent_connects(Some(1))
+            .with_acquire_timeout(Some(Duration::from_millis(200)));
+        let opts = get_opts()
+            .ip_or_hostname("127.0.0.1")
+            .tcp_port(listener.local_addr()?.port())
+            .prefer_socket(false)
+            .pool_opts(pool_opts);
+
+        let pool = Pool::new(opts);
+        let metrics = pool.metrics();
+
+        // takes the only permit
+        let mut hanging = pin!(pool.get_conn());
+        assert!(poll!(&mut hanging).is_pending());
+        assert_eq!(metrics.connects_in_progress.load(Ordering::Relaxed), 1);
+
+        match timeout(Duration::from_secs(10), pool.get_conn()).await {
+            Ok(Err(Error::Driver(DriverError::PoolAcquireTimeout { timeout }))) => {
+                assert_eq!(timeout, Duration::from_millis(200));
+            }
+            other => panic!(
+                "expected PoolAcquireTimeout, got {:?}",
+                other.map(|x| x.map(|c| c.id()))
+            ),
+        }
+        assert_eq!(metrics.connects_in_progress.load(Ordering::Relaxed), 1);
+        assert_eq!(ex_field!(pool, exist), 1);
+
+        drop(hanging);
+        assert_eq!(ex_field!(pool, exist), 0);
+        pool.disconnect().await?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn should_report_pool_stats() -> super::Result<()> {
+        let pool_opts = PoolOpts::default().with_constraints(PoolConstraints::new(1, 2).unwrap());
+        let pool = Pool::new(get_opts().pool_opts(pool_opts));
+        assert_eq!(pool.stats().active, 0);
+
+        let conn1 = pool.get_conn().await?;
+        let stats = pool.stats();
+        assert_eq!((stats.active, stats.idle, stats.in_use), (1, 0, 1));
+        assert_eq!((stats.created, stats.closed, stats.idle_bound), (1, 0, 1));
+
+        let conn2 = pool.get_conn().await?;
+        assert_eq!(pool.stats().in_use, 2);
+
+        {
+            let mut get_conn = pin!(pool.get_conn());
+            assert!(poll!(&mut get_conn).is_pending());
+            assert_eq!(pool.stats().pending_acquires, 1);
+        }
+        assert_eq!(pool.stats().pending_acquires, 0);
+
+        drop(conn1);
+        while pool.stats().idle == 0 {
+            sleep(Duration::from_millis(10)).await;
+        }
+        let stats = pool.stats();
+        assert_eq!((stats.active, stats.idle, stats.in_use), (2, 1, 1));
+
+        // the pool already keeps `idle_bound` idle connections
+        drop(conn2);
+        while pool.stats().closed == 0 {
+            sleep(Duration::from_millis(10)).await;
+        }
+        let stats = pool.stats();
+        assert_eq!((stats.active, stats.idle, stats.in_use), (1, 1, 0));
+        assert_eq!((stats.created, stats.closed), (2, 1));
+
+        pool.disconnect().await?;
+        Ok(())
+    }
+
     #[tokio::test]
     async fn save_last_waker() {
         // Test that if passed multiple wakers, we call the last one.
@@ -7,7 +7,7 @@
 // modified, or distributed except according to those terms.
 
 use futures_core::stream::Stream;
-use futures_util::{stream::futures_unordered::FuturesUnordered, FutureExt};
+use futures_util::{stream::futures_unordered::FuturesUnordered, FutureExt, TryFutureExt};
 use tokio::sync::mpsc;
 
 use std::{
@@ -17,8 +17,8 @@ use std::{
     task::{Context, Poll},
 };
 
-use super::{IdlingConn, Inner};
-use crate::{queryable::transaction::TxStatus, BoxFuture, Conn, PoolOpts};
+use super::{events::PoolEvent, IdlingConn, Inner};
+use crate::{BoxFuture, Conn, PoolOpts};
 use tokio::sync::mpsc::UnboundedReceiver;
 
 #[derive(Debug)]
@@ -72,6 +72,9 @@ impl Future for Recycler {
                         .metrics
                         .discarded_superfluous_connection
                         .fetch_add(1, Ordering::Relaxed);
+                    $self
+                        .inner
+                        .emit(PoolEvent::ConnectionClosed { id: $conn.id() });
                     $self.discard.push($conn.close_conn().boxed());
                 } else {
                     $self
@@ -87,6 +90,7 @@ impl Future for Recycler {
                         .lock()
                         .unwrap()
                         .saturating_record($conn.inner.active_since.elapsed().as_micros() as u64);
+                    $self.inner.emit(PoolEvent::Returned { id: $conn.id() });
                     exchange.available.push_back($conn.into());
                     $self
                         .inner
@@ -109,20 +113,33 @@ impl Future for Recycler {
                         .metrics
                         .discarded_unestablished_connection
                         .fetch_add(1, Ordering::Relaxed);
+                    $self
+                        .inner
+                        .emit(PoolEvent::ConnectionClosed { id: $conn.id() });
                     $self.discard.push(futures_util::future::ok(()).boxed());
-                } else if $conn.inner.tx_status != TxStatus::None || $conn.has_pending_result() {
+                } else if $conn.in_transaction() || $conn.has_pending_result() {
                     $self
                         .inner
                         .metrics
                         .dirty_connection_return
                         .fetch_add(1, Ordering::Relaxed);
-                    $self.cleaning.push($conn.cleanup_for_pool().boxed());
+                    let (inner, id) = ($self.inner.clone(), $conn.id());
+                    $self.cleaning.push(
+                        $conn
+                            .cleanup_for_pool()
+                            // the connection is discarded on error
+                            .inspect_err(move |_| inner.emit(PoolEvent::ConnectionClosed { id }))
+                            .boxed(),
+                    );
                 } else if $conn.expired() || close {
                     $self
                         .inner
                         .metrics
                         .discarded_expired_connection
                         .fetch_add(1, Ordering::Relaxed);
+                    $self
+                        .inner
+                        .emit(PoolEvent::ConnectionClosed { id: $conn.id() });
                     $self.discard.push($conn.close_conn().boxed());
                 } else if $conn.inner.reset_upon_returning_to_a_pool {
                     $self
@@ -130,7 +147,14 @@ impl Future for Recycler {
                         .metrics
                         .resetting_connection
                         .fetch_add(1, Ordering::Relaxed);
-                    $self.reset.push($conn.reset_for_pool().boxed());
+                    let (inner, id) = ($self.inner.clone(), $conn.id());
+                    $self.reset.push(
+                        $conn
+                            .reset_for_pool()
+                            // the connection is discarded on error
+                            .inspect_err(move |_| inner.emit(PoolEvent::ConnectionClosed { id }))
+                            .boxed(),
+                    );
                 } else {
                     conn_return!($self, $conn, false);
                 }
@@ -242,6 +266,7 @@ impl Future for Recycler {
             // we need to open up slots for new connctions to be established!
             let mut exchange = self.inner.exchange.lock().unwrap();
             exchange.exist -= self.discarded;
+            exchange.closed += self.discarded;
             self.inner
                 .metrics
                 .connection_count
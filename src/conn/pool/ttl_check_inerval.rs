@@ -15,7 +15,7 @@ use std::{
     sync::{atomic::Ordering, Arc},
 };
 
-use super::Inner;
+use super::{events::PoolEvent, Inner};
 use crate::PoolOpts;
 use futures_core::task::{Context, Poll};
 use std::pin::Pin;
@@ -76,10 +76,14 @@ impl TtlCheckInterval {
 
         for idling_conn in to_be_dropped {
             assert!(idling_conn.conn.inner.pool.is_none());
+            self.inner.emit(PoolEvent::ConnectionClosed {
+                id: idling_conn.conn.id(),
+            });
             let inner = self.inner.clone();
             tokio::spawn(idling_conn.conn.disconnect().then(move |_| {
                 let mut exchange = inner.exchange.lock().unwrap();
                 exchange.exist -= 1;
+                exchange.closed += 1;
                 inner
                     .metrics
                     .connection_count
@@ -99,7 +103,9 @@ impl Future for TtlCheckInterval {
             let close = self.inner.close.load(Ordering::Acquire);
 
             if !close {
-                self.check_ttl();
+                if !self.inner.maintenance_paused.load(Ordering::Acquire) {
+                    self.check_ttl();
+                }
             } else {
                 return Poll::Ready(());
             }
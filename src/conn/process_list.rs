@@ -0,0 +1,62 @@
+// Copyright (c) 2025 mysql_async contributors
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+use mysql_common::{prelude::FromValue, row::Row};
+
+use std::time::Duration;
+
+/// A thread running within the server.
+///
+/// Returned by [`crate::Conn::process_list`]. Fields correspond to columns
+/// of `SHOW FULL PROCESSLIST`.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ProcessInfo {
+    /// Connection identifier (`Id`). This is the value to pass to `KILL`
+    /// (see also [`crate::Conn::id`]).
+    pub id: u64,
+    /// The user who issued the statement (`User`).
+    pub user: String,
+    /// Host name (and usually the port) of the client (`Host`).
+    pub host: String,
+    /// The default database, if any (`db`).
+    pub db: Option<String>,
+    /// The type of command the thread is executing (`Command`), e.g. `Query` or `Sleep`.
+    pub command: String,
+    /// The time the thread has been in its current state (`Time`).
+    pub time: Duration,
+    /// An action, event, or state that indicates what the thread is doing (`State`).
+    pub state: Option<String>,
+    /// The full text of the statement the thread is executing (`Info`), if any.
+    pub info: Option<String>,
+}
+
+impl ProcessInfo {
+    /// Parses a row of `SHOW FULL PROCESSLIST`.
+    pub(crate) fn from_row(row: &Row) -> Self {
+        Self {
+            id: field(row, "Id").unwrap_or_default(),
+            user: field(row, "User").unwrap_or_default(),
+            host: field(row, "Host").unwrap_or_default(),
+            db: field(row, "db"),
+            command: field(row, "Command").unwrap_or_default(),
+            // `Time` might be negative for replication threads.
+            time: field::<i64>(row, "Time")
+                .map(|x| Duration::from_secs(x.max(0) as u64))
+                .unwrap_or_default(),
+            state: field(row, "State"),
+            info: field(row, "Info"),
+        }
+    }
+}
+
+/// Reads a non-`NULL` column value.
+fn field<T: FromValue>(row: &Row, name: &str) -> Option<T> {
+    row.get_opt::<Option<T>, _>(name)
+        .and_then(Result::ok)
+        .flatten()
+}
@@ -0,0 +1,246 @@
+// Copyright (c) 2025 mysql_async contributors
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+use mysql_common::{params::Params, row::Row, value::Value};
+
+/// A client-side cache of query results (see [`crate::Conn::set_query_cache`]).
+///
+/// The driver only consults the cache (see [`crate::Conn::query_cached`]
+/// and [`crate::Conn::exec_cached`]) – eviction, expiration and invalidation are up to the
+/// implementation. Note that the server knows nothing about the cache, so it's
+/// the caller's responsibility to make sure that stale results are acceptable
+/// (e.g. cache only small lookup tables that rarely change).
+pub trait QueryCache: Send + Sync {
+    /// Returns cached rows for the given key, if any.
+    fn get(&self, key: &QueryCacheKey) -> Option<Vec<Row>>;
+
+    /// Stores rows of the first result set of the query identified by the given key.
+    fn put(&self, key: QueryCacheKey, rows: Vec<Row>);
+}
+
+/// Identifies a query within a [`QueryCache`].
+///
+/// The key consists of the normalized query text and of the parameters (with their types), if any.
+/// Normalization strips comments (except for executable comments and optimizer hints),
+/// collapses whitespace outside of quoted strings and identifiers and removes trailing
+/// semicolons, so `SELECT  1; -- one` and `SELECT 1` have the same key. Text queries and
+/// statements executed with parameters have different keys (even if the parameters are empty),
+/// because values of their rows are of different types.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct QueryCacheKey {
+    sql: String,
+    params: Option<Vec<String>>,
+}
+
+impl QueryCacheKey {
+    pub(crate) fn new(sql: &str, params: Option<&Params>) -> Self {
+        let params = params.map(|params| match params {
+            Params::Empty => Vec::new(),
+            Params::Positional(values) => values.iter().map(param_key).collect(),
+            Params::Named(values) => {
+                let mut values = values
+                    .iter()
+                    .map(|(name, value)| {
+                        format!(":{}={}", String::from_utf8_lossy(name), param_key(value))
+                    })
+                    .collect::<Vec<_>>();
+                values.sort_unstable();
+                values
+            }
+        });
+
+        Self {
+            sql: normalize(sql),
+            params,
+        }
+    }
+
+    /// Returns the normalized query text.
+    pub fn sql(&self) -> &str {
+        &self.sql
+    }
+}
+
+/// Renders a parameter value for a [`QueryCacheKey`].
+///
+/// The value is prefixed with its type, because e.g. `Int(1)`, `UInt(1)` and `Double(1.0)`
+/// are rendered the same way, but may produce rows of different types.
+fn param_key(value: &Value) -> String {
+    let kind = match value {
+        Value::NULL => "null",
+        Value::Bytes(_) => "bytes",
+        Value::Int(_) => "int",
+        Value::UInt(_) => "uint",
+        Value::Float(_) => "float",
+        Value::Double(_) => "double",
+        Value::Date(..) => "date",
+        Value::Time(..) => "time",
+    };
+    format!("{}:{}", kind, value.as_sql(false))
+}
+
+/// Strips comments, collapses whitespace outside of quotes and removes trailing semicolons.
+///
+/// Executable comments (`/*! ... */`) and optimizer hints (`/*+ ... */`) aren't comments
+/// as far as the server is concerned, so they are kept as is.
+fn normalize(sql: &str) -> String {
+    let chars = sql.chars().collect::<Vec<_>>();
+    let mut output = String::with_capacity(sql.len());
+    let mut pending_space = false;
+    let mut unterminated_quote = false;
+
+    let mut i = 0;
+    while i < chars.len() {
+        let ch = chars[i];
+        let starts_comment = match ch {
+            '#' => true,
+            // `--` only starts a comment if followed by a whitespace or a control character
+            '-' => {
+                chars.get(i + 1) == Some(&'-')
+                    && chars
+                        .get(i + 2)
+                        .map_or(true, |x| x.is_whitespace() || x.is_control())
+            }
+            _ => false,
+        };
+
+        if starts_comment {
+            while i < chars.len() && chars[i] != '\n' {
+                i += 1;
+            }
+            pending_space = true;
+            continue;
+        }
+
+        if ch == '/' && chars.get(i + 1) == Some(&'*') {
+            let end = (i + 2..chars.len())
+                .find(|&j| chars[j] == '*' && chars.get(j + 1) == Some(&'/'))
+                .map_or(chars.len(), |j| j + 2);
+            if matches!(chars.get(i + 2), Some('!' | '+')) {
+                push_pending_space(&mut output, &mut pending_space);
+                output.extend(&chars[i..end]);
+            } else {
+                pending_space = true;
+            }
+            i = end;
+            continue;
+        }
+
+        if ch.is_whitespace() {
+            pending_space = true;
+            i += 1;
+            continue;
+        }
+
+        push_pending_space(&mut output, &mut pending_space);
+        output.push(ch);
+        i += 1;
+
+        if matches!(ch, '\'' | '"' | '`') {
+            unterminated_quote = true;
+            while i < chars.len() {
+                let x = chars[i];
+                output.push(x);
+                i += 1;
+                if x == '\\' && ch != '`' {
+                    if let Some(&escaped) = chars.get(i) {
+                        output.push(escaped);
+                        i += 1;
+                    }
+                } else if x == ch {
+                    unterminated_quote = false;
+                    break;
+                }
+            }
+        }
+    }
+
+    if !unterminated_quote {
+        while output.ends_with(';') || output.ends_with(' ') {
+            output.pop();
+        }
+    }
+
+    output
+}
+
+/// Pushes a single space separating tokens, if there was whitespace (or a comment) before.
+fn push_pending_space(output: &mut String, pending_space: &mut bool) {
+    if *pending_space && !output.is_empty() {
+        output.push(' ');
+    }
+    *pending_space = false;
+}
+
+#[cfg(test)]
+mod test {
+    use mysql_common::{params::Params, value::Value};
+
+    use super::QueryCacheKey;
+    use crate::params;
+
+    #[test]
+    fn should_normalize_query_cache_key() {
+        let key = QueryCacheKey::new("SELECT 1", None);
+        assert_eq!(key.sql(), "SELECT 1");
+        assert_eq!(QueryCacheKey::new("\n SELECT\t  1 ; ;", None), key);
+        assert_ne!(QueryCacheKey::new("SELECT 1", Some(&Params::Empty)), key);
+
+        let key = QueryCacheKey::new("SELECT ' a  b ', `c  d`, \"it\\\"s  \"  FROM t", None);
+        assert_eq!(key.sql(), "SELECT ' a  b ', `c  d`, \"it\\\"s  \" FROM t");
+    }
+
+    #[test]
+    fn should_strip_comments_from_query_cache_key() {
+        // a comment hides the rest of the line, not the rest of the query
+        let key = QueryCacheKey::new("SELECT a FROM t -- x\nWHERE id = 1", None);
+        assert_eq!(key.sql(), "SELECT a FROM t WHERE id = 1");
+        let key = QueryCacheKey::new("SELECT a FROM t -- x WHERE id = 1", None);
+        assert_eq!(key.sql(), "SELECT a FROM t");
+        let key = QueryCacheKey::new("SELECT a FROM t # x\nWHERE id = 1", None);
+        assert_eq!(key.sql(), "SELECT a FROM t WHERE id = 1");
+
+        let key = QueryCacheKey::new("SELECT/* one */1 /* two */ ;", None);
+        assert_eq!(key.sql(), "SELECT 1");
+        // `--` without a following whitespace is two minuses
+        assert_eq!(QueryCacheKey::new("SELECT 1--1", None).sql(), "SELECT 1--1");
+        // comment markers within quotes are kept
+        let key = QueryCacheKey::new("SELECT '-- a', `#b`, \"/* c */\"", None);
+        assert_eq!(key.sql(), "SELECT '-- a', `#b`, \"/* c */\"");
+        // executable comments and optimizer hints aren't comments
+        let key = QueryCacheKey::new("SELECT /*+ NO_ICP(t) */ a  /*!80000 , b */ FROM t", None);
+        assert_eq!(
+            key.sql(),
+            "SELECT /*+ NO_ICP(t) */ a /*!80000 , b */ FROM t"
+        );
+    }
+
+    #[test]
+    fn should_include_params_into_query_cache_key() {
+        let positional = |x: u32| QueryCacheKey::new("SELECT ?", Some(&Params::from((x,))));
+        assert_eq!(positional(1), positional(1));
+        assert_ne!(positional(1), positional(2));
+
+        // values of different types are different parameters
+        let typed = |x: Value| QueryCacheKey::new("SELECT ?", Some(&Params::from(vec![x])));
+        assert_ne!(typed(Value::Int(1)), typed(Value::UInt(1)));
+        assert_ne!(typed(Value::Int(1)), typed(Value::Double(1.0)));
+        assert_ne!(typed(Value::Float(1.0)), typed(Value::Double(1.0)));
+        assert_eq!(typed(Value::Int(1)), typed(Value::Int(1)));
+
+        let named = |params: Params| QueryCacheKey::new("SELECT :a, :b", Some(&params));
+        assert_eq!(
+            named(params! { "a" => 1, "b" => "x" }),
+            named(params! { "b" => "x", "a" => 1 })
+        );
+        assert_ne!(
+            named(params! { "a" => 1, "b" => "x" }),
+            named(params! { "a" => "x", "b" => 1 })
+        );
+    }
+}
@@ -0,0 +1,82 @@
+// Copyright (c) 2025 mysql_async contributors
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+use mysql_common::{prelude::FromValue, row::Row};
+
+use std::time::Duration;
+
+/// Replication status of a replica server.
+///
+/// Returned by [`crate::Conn::replica_status`]. Field names follow the MySql 8.0.22+ naming
+/// (`Source`/`Replica`); values are read from the legacy `Master`/`Slave` columns
+/// on older servers and on MariaDb.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ReplicaStatus {
+    /// Replication lag (`Seconds_Behind_Source`).
+    ///
+    /// `None` if the SQL thread is not running or if the lag is unknown
+    /// (e.g. the IO thread is not connected to the source).
+    pub seconds_behind_source: Option<Duration>,
+    /// `true` if the IO (receiver) thread is running and connected to the source
+    /// (`Replica_IO_Running` is `Yes`).
+    pub io_running: bool,
+    /// `true` if the SQL (applier) thread is running (`Replica_SQL_Running` is `Yes`).
+    pub sql_running: bool,
+    /// Host name of the source (`Source_Host`).
+    pub source_host: Option<String>,
+    /// Port of the source (`Source_Port`).
+    pub source_port: Option<u16>,
+    /// Source binary log file the IO thread is currently reading from (`Source_Log_File`).
+    pub source_log_file: Option<String>,
+    /// Position within [`ReplicaStatus::source_log_file`] the IO thread has read up to
+    /// (`Read_Source_Log_Pos`).
+    pub read_source_log_pos: Option<u64>,
+    /// Source binary log file containing the most recent executed event
+    /// (`Relay_Source_Log_File`).
+    pub relay_source_log_file: Option<String>,
+    /// Position within [`ReplicaStatus::relay_source_log_file`] the SQL thread has executed
+    /// up to (`Exec_Source_Log_Pos`).
+    pub exec_source_log_pos: Option<u64>,
+    /// Set of GTIDs received by this replica (`Retrieved_Gtid_Set`, MySql only).
+    pub retrieved_gtid_set: Option<String>,
+    /// Set of GTIDs executed by this replica (`Executed_Gtid_Set`, MySql only).
+    pub executed_gtid_set: Option<String>,
+    /// The last error reported by the IO or SQL thread (`Last_Error`), if any.
+    pub last_error: Option<String>,
+}
+
+impl ReplicaStatus {
+    /// Parses a row of `SHOW REPLICA STATUS` or `SHOW SLAVE STATUS`.
+    pub(crate) fn from_row(row: &Row) -> Self {
+        let running = |names: &[&str]| field::<String>(row, names).as_deref() == Some("Yes");
+        Self {
+            seconds_behind_source: field(row, &["Seconds_Behind_Source", "Seconds_Behind_Master"])
+                .map(Duration::from_secs),
+            io_running: running(&["Replica_IO_Running", "Slave_IO_Running"]),
+            sql_running: running(&["Replica_SQL_Running", "Slave_SQL_Running"]),
+            source_host: field(row, &["Source_Host", "Master_Host"]),
+            source_port: field(row, &["Source_Port", "Master_Port"]),
+            source_log_file: field(row, &["Source_Log_File", "Master_Log_File"]),
+            read_source_log_pos: field(row, &["Read_Source_Log_Pos", "Read_Master_Log_Pos"]),
+            relay_source_log_file: field(row, &["Relay_Source_Log_File", "Relay_Master_Log_File"]),
+            exec_source_log_pos: field(row, &["Exec_Source_Log_Pos", "Exec_Master_Log_Pos"]),
+            retrieved_gtid_set: field(row, &["Retrieved_Gtid_Set"]),
+            executed_gtid_set: field(row, &["Executed_Gtid_Set"]),
+            last_error: field::<String>(row, &["Last_Error"]).filter(|x| !x.is_empty()),
+        }
+    }
+}
+
+/// Reads the first existing non-`NULL` column among `names`.
+fn field<T: FromValue>(row: &Row, names: &[&str]) -> Option<T> {
+    names
+        .iter()
+        .find_map(|name| row.get_opt::<Option<T>, _>(*name))
+        .and_then(Result::ok)
+        .flatten()
+}
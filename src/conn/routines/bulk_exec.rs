@@ -0,0 +1,205 @@
+use futures_core::future::BoxFuture;
+use futures_util::FutureExt;
+use mysql_common::{
+    constants::{ColumnType, DEFAULT_MAX_ALLOWED_PACKET},
+    proto::MySerialize,
+    value::Value,
+};
+#[cfg(feature = "tracing")]
+use tracing::info_span;
+
+use crate::{conn::MAX_STATEMENT_PARAMS, BinaryProtocol, Conn, DriverError, Statement};
+
+use super::Routine;
+
+/// MariaDB's `COM_STMT_BULK_EXECUTE` command.
+const COM_STMT_BULK_EXECUTE: u8 = 0xFA;
+
+/// Parameter types are sent before parameter values.
+const STMT_BULK_FLAG_SEND_TYPES_TO_SERVER: u16 = 128;
+
+/// Parameter value follows the indicator.
+const STMT_INDICATOR_NONE: u8 = 0;
+
+/// Parameter value is `NULL`.
+const STMT_INDICATOR_NULL: u8 = 1;
+
+/// A routine that executes a statement for a batch of rows using MariaDB's
+/// `COM_STMT_BULK_EXECUTE`.
+///
+/// Rows are split into as few commands as `max_allowed_packet` allows. The server responds
+/// to each command with a single OK packet that accounts for all of its rows.
+#[derive(Debug, Clone)]
+pub struct BulkExecRoutine<'a> {
+    stmt: &'a Statement,
+    rows: &'a [Vec<Value>],
+    types: Vec<(ColumnType, u8)>,
+}
+
+impl<'a> BulkExecRoutine<'a> {
+    /// Returns `None` if `rows` can't be sent in bulk, i.e. if a parameter changes its type
+    /// from row to row (types are sent once per command).
+    pub fn new(stmt: &'a Statement, rows: &'a [Vec<Value>]) -> Option<Self> {
+        let types = bulk_param_types(rows)?;
+        Some(Self { stmt, rows, types })
+    }
+
+    /// Serializes the command header followed by parameter types.
+    fn write_header(&self, buf: &mut Vec<u8>) {
+        buf.push(COM_STMT_BULK_EXECUTE);
+        buf.extend_from_slice(&self.stmt.id().to_le_bytes());
+        buf.extend_from_slice(&STMT_BULK_FLAG_SEND_TYPES_TO_SERVER.to_le_bytes());
+        for (column_type, flags) in &self.types {
+            buf.push(*column_type as u8);
+            buf.push(*flags);
+        }
+    }
+}
+
+impl Routine<()> for BulkExecRoutine<'_> {
+    fn call<'a>(&'a mut self, conn: &'a mut Conn) -> BoxFuture<'a, crate::Result<()>> {
+        #[cfg(feature = "tracing")]
+        let span = info_span!(
+            "mysql_async::bulk_exec",
+            mysql_async.connection.id = conn.id(),
+            mysql_async.statement.id = self.stmt.id(),
+        );
+
+        let fut = async move {
+            for row in self.rows {
+                if row.len() > MAX_STATEMENT_PARAMS {
+                    Err(DriverError::StmtParamsNumberExceedsLimit {
+                        supplied: row.len(),
+                    })?
+                }
+
+                if self.stmt.num_params() as usize != row.len() {
+                    Err(DriverError::StmtParamsMismatch {
+                        required: self.stmt.num_params(),
+                        supplied: row.len(),
+                    })?
+                }
+
+                conn.check_params_len(row)?;
+            }
+
+            let max_len = conn
+                .server_max_allowed_packet()
+                .or(conn.opts().max_allowed_packet())
+                .unwrap_or(DEFAULT_MAX_ALLOWED_PACKET);
+
+            let mut rows = self.rows;
+            while !rows.is_empty() {
+                conn.start_command();
+
+                let mut body = crate::buffer_pool().get();
+                self.write_header(body.as_mut());
+                let mut taken = 0;
+                for row in rows {
+                    let row_len = row.iter().map(|value| 1 + value_len(value)).sum::<usize>();
+                    if taken > 0 && body.len() + row_len > max_len {
+                        break;
+                    }
+                    for value in row {
+                        if matches!(value, Value::NULL) {
+                            body.as_mut().push(STMT_INDICATOR_NULL);
+                        } else {
+                            body.as_mut().push(STMT_INDICATOR_NONE);
+                            value.serialize(body.as_mut());
+                        }
+                    }
+                    taken += 1;
+                }
+                rows = &rows[taken..];
+
+                conn.write_command_raw(body).await?;
+                conn.read_result_set::<BinaryProtocol>(true).await?;
+                conn.drop_result().await?;
+            }
+
+            Ok(())
+        };
+
+        #[cfg(feature = "tracing")]
+        let fut = instrument_result!(fut, span);
+
+        fut.boxed()
+    }
+}
+
+/// Returns types of parameters of the given rows (the same way `COM_STMT_EXECUTE` does),
+/// or `None` if a parameter changes its type from row to row.
+///
+/// Types are taken from the first non-`NULL` value of a parameter.
+fn bulk_param_types(rows: &[Vec<Value>]) -> Option<Vec<(ColumnType, u8)>> {
+    let mut types = vec![(ColumnType::MYSQL_TYPE_NULL, 0); rows.first()?.len()];
+    for row in rows {
+        if row.len() != types.len() {
+            return None;
+        }
+        for (value, param_type) in row.iter().zip(types.iter_mut()) {
+            let value_type = match value {
+                Value::NULL => continue,
+                Value::Bytes(_) => (ColumnType::MYSQL_TYPE_VAR_STRING, 0),
+                Value::Int(_) => (ColumnType::MYSQL_TYPE_LONGLONG, 0),
+                Value::UInt(_) => (ColumnType::MYSQL_TYPE_LONGLONG, 128),
+                Value::Float(_) => (ColumnType::MYSQL_TYPE_FLOAT, 0),
+                Value::Double(_) => (ColumnType::MYSQL_TYPE_DOUBLE, 0),
+                Value::Date(..) => (ColumnType::MYSQL_TYPE_DATETIME, 0),
+                Value::Time(..) => (ColumnType::MYSQL_TYPE_TIME, 0),
+            };
+            if param_type.0 == ColumnType::MYSQL_TYPE_NULL {
+                *param_type = value_type;
+            } else if *param_type != value_type {
+                return None;
+            }
+        }
+    }
+    Some(types)
+}
+
+/// Returns the upper bound of the length of the given value in the binary protocol.
+fn value_len(value: &Value) -> usize {
+    match value {
+        Value::NULL => 0,
+        Value::Bytes(bytes) => 9 + bytes.len(),
+        Value::Int(_) | Value::UInt(_) | Value::Double(_) => 8,
+        Value::Float(_) => 4,
+        Value::Date(..) => 12,
+        Value::Time(..) => 13,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use mysql_common::{constants::ColumnType, value::Value};
+
+    use super::bulk_param_types;
+
+    #[test]
+    fn should_infer_bulk_param_types() {
+        let rows = vec![
+            vec![Value::NULL, Value::Int(1), Value::Bytes(b"foo".to_vec())],
+            vec![Value::UInt(2), Value::NULL, Value::Bytes(b"bar".to_vec())],
+        ];
+        assert_eq!(
+            bulk_param_types(&rows),
+            Some(vec![
+                (ColumnType::MYSQL_TYPE_LONGLONG, 128),
+                (ColumnType::MYSQL_TYPE_LONGLONG, 0),
+                (ColumnType::MYSQL_TYPE_VAR_STRING, 0),
+            ])
+        );
+
+        let rows = vec![vec![Value::NULL], vec![Value::NULL]];
+        assert_eq!(
+            bulk_param_types(&rows),
+            Some(vec![(ColumnType::MYSQL_TYPE_NULL, 0)])
+        );
+
+        let rows = vec![vec![Value::Int(1)], vec![Value::Bytes(b"2".to_vec())]];
+        assert_eq!(bulk_param_types(&rows), None);
+
+        assert_eq!(bulk_param_types(&[]), None);
+    }
+}
@@ -65,9 +65,12 @@ impl Routine<()> for ExecRoutine<'_> {
                             })?
                         }
 
+                        conn.check_params_len(params)?;
+
                         let (body, as_long_data) =
                             ComStmtExecuteRequestBuilder::new(self.stmt.id()).build(params);
 
+                        conn.start_command();
                         if as_long_data {
                             conn.send_long_data(self.stmt.id(), params.iter()).await?;
                         }
@@ -100,6 +103,7 @@ impl Routine<()> for ExecRoutine<'_> {
 
                         let (body, _) =
                             ComStmtExecuteRequestBuilder::new(self.stmt.id()).build(&[]);
+                        conn.start_command();
                         conn.write_command(&body).await?;
                         conn.read_result_set::<BinaryProtocol>(true).await?;
                         break;
@@ -4,15 +4,49 @@ use std::sync::Arc;
 
 use futures_util::StreamExt;
 use mysql_common::{
-    constants::MAX_PAYLOAD_LEN,
+    constants::{StatusFlags, MAX_PAYLOAD_LEN},
     io::{ParseBuf, ReadMysqlExt},
     packets::{ComStmtSendLongData, LocalInfilePacket},
     value::Value,
 };
 
-use crate::{error::LocalInfileError, queryable::Protocol, Conn, Error};
+use crate::{
+    conn::local_infile::local_infile_names, error::LocalInfileError, queryable::Protocol, Conn,
+    DriverError, Error,
+};
 
 impl Conn {
+    /// Checks the query against `max_query_len` (see [`crate::Opts::max_query_len`]).
+    pub(super) fn check_query_len(&self, query: &[u8]) -> crate::Result<()> {
+        match self.opts().max_query_len() {
+            Some(max) if query.len() > max => Err(DriverError::QueryTooLong {
+                len: query.len(),
+                max,
+            }
+            .into()),
+            _ => Ok(()),
+        }
+    }
+
+    /// Checks parameter values against `max_param_len` (see [`crate::Opts::max_param_len`]).
+    pub(super) fn check_params_len(&self, params: &[Value]) -> crate::Result<()> {
+        if let Some(max) = self.opts().max_param_len() {
+            for (index, value) in params.iter().enumerate() {
+                if let Value::Bytes(bytes) = value {
+                    if bytes.len() > max {
+                        return Err(DriverError::ParamTooLong {
+                            index,
+                            len: bytes.len(),
+                            max,
+                        }
+                        .into());
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
     /// Helper, that sends all `Value::Bytes` in the given list of paramenters as long data.
     pub(super) async fn send_long_data<'a, I>(
         &mut self,
@@ -48,6 +82,10 @@ impl Conn {
     where
         P: Protocol,
     {
+        if is_first_result_set {
+            self.inner.truncated = false;
+        }
+
         let packet = match self.read_packet().await {
             Ok(packet) => packet,
             Err(err @ Error::Server(_)) if is_first_result_set => {
@@ -78,6 +116,34 @@ impl Conn {
         Ok(())
     }
 
+    /// Remembers files named by `LOAD DATA LOCAL INFILE` statements of the given query
+    /// (see [`crate::Opts::strict_local_infile`]).
+    pub(super) fn expect_local_infiles(&mut self, query: &[u8]) {
+        let no_backslash_escapes = self
+            .status()
+            .contains(StatusFlags::SERVER_STATUS_NO_BACKSLASH_ESCAPES);
+        self.inner.expected_infiles = local_infile_names(query, no_backslash_escapes);
+    }
+
+    /// Checks that the requested file is named by the last query,
+    /// if [`crate::Opts::strict_local_infile`] is enabled.
+    fn authorize_local_infile(&mut self, file_name: &[u8]) -> Result<(), LocalInfileError> {
+        if !self.opts().strict_local_infile() {
+            return Ok(());
+        }
+
+        let expected = &mut self.inner.expected_infiles;
+        match expected.iter().position(|x| x == file_name) {
+            Some(i) => {
+                expected.remove(i);
+                Ok(())
+            }
+            None => Err(LocalInfileError::UnexpectedRequest(
+                String::from_utf8_lossy(file_name).into_owned(),
+            )),
+        }
+    }
+
     /// Will handle local infile packet.
     pub(super) async fn handle_local_infile<P>(&mut self, packet: &[u8]) -> crate::Result<()>
     where
@@ -85,30 +151,33 @@ impl Conn {
     {
         let local_infile = ParseBuf(packet).parse::<LocalInfilePacket>(())?;
 
-        let mut infile_data = if let Some(handler) = self.inner.infile_handler.take() {
-            handler.await?
-        } else if let Some(handler) = self.opts().local_infile_handler() {
-            handler.handle(local_infile.file_name_ref()).await?
-        } else {
-            return Err(LocalInfileError::NoHandler.into());
-        };
+        let mut result = self.authorize_local_infile(local_infile.file_name_ref());
+        if result.is_ok() {
+            let mut infile_data = if let Some(handler) = self.inner.infile_handler.take() {
+                handler.await?
+            } else if let Some(handler) = self.opts().local_infile_handler() {
+                handler.handle(local_infile.file_name_ref()).await?
+            } else {
+                return Err(LocalInfileError::NoHandler.into());
+            };
 
-        let mut result = Ok(());
-        while let Some(bytes) = infile_data.next().await {
-            match bytes {
-                Ok(bytes) => {
-                    // We'll skip empty chunks to stay compliant with the protocol.
-                    if !bytes.is_empty() {
-                        self.write_bytes(&bytes).await?;
+            while let Some(bytes) = infile_data.next().await {
+                match bytes {
+                    Ok(bytes) => {
+                        // We'll skip empty chunks to stay compliant with the protocol.
+                        if !bytes.is_empty() {
+                            self.write_bytes(&bytes).await?;
+                        }
+                    }
+                    Err(err) => {
+                        // Abort the stream in case of an error.
+                        result = Err(LocalInfileError::from(err));
+                        break;
                     }
-                }
-                Err(err) => {
-                    // Abort the stream in case of an error.
-                    result = Err(LocalInfileError::from(err));
-                    break;
                 }
             }
         }
+        // An empty packet ends the data (no data is sent if the request was rejected).
         self.write_bytes(&[]).await?;
 
         self.read_packet().await?;
@@ -2,8 +2,11 @@ use futures_core::future::BoxFuture;
 
 use crate::Conn;
 
-pub use self::{change_user::*, exec::*, next_set::*, ping::*, prepare::*, query::*, reset::*};
+pub use self::{
+    bulk_exec::*, change_user::*, exec::*, next_set::*, ping::*, prepare::*, query::*, reset::*,
+};
 
+mod bulk_exec;
 mod change_user;
 mod exec;
 mod next_set;
@@ -6,7 +6,7 @@ use mysql_common::constants::Command;
 #[cfg(feature = "tracing")]
 use tracing::{field, info_span, Level, Span};
 
-use crate::{queryable::stmt::StmtInner, Conn};
+use crate::{queryable::stmt::StmtInner, Conn, DriverError, Error};
 
 use super::Routine;
 
@@ -43,26 +43,116 @@ impl Routine<Arc<StmtInner>> for PrepareRoutine {
         }
 
         let fut = async move {
+            conn.check_query_len(&self.query)?;
             conn.write_command_data(Command::COM_STMT_PREPARE, &self.query)
                 .await?;
 
-            let packet = conn.read_packet().await?;
-            let mut inner_stmt = StmtInner::from_payload(&packet, conn.id(), self.query.clone())?;
+            let inner_stmt = read_prepare_response(conn, self.query.clone()).await?;
 
             #[cfg(feature = "tracing")]
             Span::current().record("mysql_async.statement.id", inner_stmt.id());
 
-            if inner_stmt.num_params() > 0 {
-                let params = conn.read_column_defs(inner_stmt.num_params()).await?;
-                inner_stmt = inner_stmt.with_params(params);
-            }
+            Ok(inner_stmt)
+        };
+
+        #[cfg(feature = "tracing")]
+        let fut = instrument_result!(fut, span);
+
+        fut.boxed()
+    }
+}
 
-            if inner_stmt.num_columns() > 0 {
-                let columns = conn.read_column_defs(inner_stmt.num_columns()).await?;
-                inner_stmt = inner_stmt.with_columns(columns);
+/// Maximum number of pipelined `COM_STMT_PREPARE` requests sent before reading responses.
+const MAX_PIPELINED_PREPARES: usize = 64;
+
+/// Maximum total size of pipelined `COM_STMT_PREPARE` requests sent before reading responses.
+///
+/// Requests in flight must fit into socket buffers, otherwise the server could block writing
+/// responses nobody reads while the client blocks writing requests nobody reads.
+const MAX_PIPELINED_BYTES: usize = 16 * 1024;
+
+/// A routine that performs a batch of `COM_STMT_PREPARE` (see [`Conn::prepare_many`]).
+///
+/// Resolves to a result per query (in order). Errors that don't break the connection
+/// (e.g. a syntax error) are reported per query, other errors fail the routine.
+#[derive(Debug, Clone)]
+pub struct PrepareManyRoutine {
+    queries: Vec<Arc<[u8]>>,
+    pipeline: bool,
+}
+
+impl PrepareManyRoutine {
+    /// Requests are pipelined in bounded chunks, if `pipeline` is `true` (not supported
+    /// for compressed streams), otherwise a request is sent once the previous response is read.
+    pub fn new(queries: Vec<Arc<[u8]>>, pipeline: bool) -> Self {
+        Self { queries, pipeline }
+    }
+}
+
+impl Routine<Vec<crate::Result<Arc<StmtInner>>>> for PrepareManyRoutine {
+    fn call<'a>(
+        &'a mut self,
+        conn: &'a mut Conn,
+    ) -> BoxFuture<'a, crate::Result<Vec<crate::Result<Arc<StmtInner>>>>> {
+        #[cfg(feature = "tracing")]
+        let span = info_span!(
+            "mysql_async::prepare_many",
+            mysql_async.connection.id = conn.id(),
+            mysql_async.statements = self.queries.len(),
+        );
+
+        let fut = async move {
+            let mut results = Vec::with_capacity(self.queries.len());
+
+            if self.pipeline {
+                let mut queries = self.queries.iter().peekable();
+                while queries.peek().is_some() {
+                    let mut chunk = Vec::new();
+                    let mut chunk_len = 0;
+                    while let Some(query) = queries.next_if(|query| {
+                        chunk.is_empty()
+                            || (chunk.len() < MAX_PIPELINED_PREPARES
+                                && chunk_len + query.len() <= MAX_PIPELINED_BYTES)
+                    }) {
+                        let check = conn.check_query_len(query);
+                        if check.is_ok() {
+                            conn.write_command_data(Command::COM_STMT_PREPARE, query)
+                                .await?;
+                            chunk_len += query.len();
+                        }
+                        chunk.push((query, check));
+                    }
+
+                    let mut first_response = true;
+                    for (query, check) in chunk {
+                        let result = match check {
+                            Ok(()) => {
+                                if !first_response {
+                                    conn.expect_pipelined_response();
+                                }
+                                first_response = false;
+                                read_prepare_response(conn, query.clone()).await
+                            }
+                            Err(err) => Err(err),
+                        };
+                        results.push(statement_result(result)?);
+                    }
+                }
+            } else {
+                for query in self.queries.iter() {
+                    let result = match conn.check_query_len(query) {
+                        Ok(()) => {
+                            conn.write_command_data(Command::COM_STMT_PREPARE, query)
+                                .await?;
+                            read_prepare_response(conn, query.clone()).await
+                        }
+                        Err(err) => Err(err),
+                    };
+                    results.push(statement_result(result)?);
+                }
             }
 
-            Ok(Arc::new(inner_stmt))
+            Ok(results)
         };
 
         #[cfg(feature = "tracing")]
@@ -71,3 +161,33 @@ impl Routine<Arc<StmtInner>> for PrepareRoutine {
         fut.boxed()
     }
 }
+
+/// Reads a response to `COM_STMT_PREPARE`.
+async fn read_prepare_response(conn: &mut Conn, query: Arc<[u8]>) -> crate::Result<Arc<StmtInner>> {
+    let packet = conn.read_packet().await?;
+    let mut inner_stmt = StmtInner::from_payload(&packet, conn.id(), query)?;
+
+    if inner_stmt.num_params() > 0 {
+        let params = conn.read_column_defs(inner_stmt.num_params()).await?;
+        inner_stmt = inner_stmt.with_params(params);
+    }
+
+    if inner_stmt.num_columns() > 0 {
+        let columns = conn.read_column_defs(inner_stmt.num_columns()).await?;
+        inner_stmt = inner_stmt.with_columns(columns);
+    }
+
+    Ok(Arc::new(inner_stmt))
+}
+
+/// Separates errors of a statement (the inner result) from fatal errors (the outer result).
+fn statement_result(
+    result: crate::Result<Arc<StmtInner>>,
+) -> crate::Result<crate::Result<Arc<StmtInner>>> {
+    match result {
+        Ok(inner_stmt) => Ok(Ok(inner_stmt)),
+        Err(err @ Error::Server(_))
+        | Err(err @ Error::Driver(DriverError::QueryTooLong { .. })) => Ok(Err(err)),
+        Err(err) => Err(err),
+    }
+}
@@ -47,8 +47,13 @@ impl<L: TracingLevel> Routine<()> for QueryRoutine<'_, L> {
         }
 
         let fut = async move {
+            conn.check_query_len(self.data)?;
+            conn.start_command();
             conn.write_command_data(Command::COM_QUERY, self.data)
                 .await?;
+            if conn.opts().strict_local_infile() {
+                conn.expect_local_infiles(self.data);
+            }
             conn.read_result_set::<TextProtocol>(true).await?;
             Ok(())
         };
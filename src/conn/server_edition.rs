@@ -0,0 +1,79 @@
+// Copyright (c) 2025 mysql_async contributors
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+/// Server edition (distribution) derived from `@@version_comment`.
+///
+/// See [`crate::Conn::server_edition`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ServerEdition {
+    /// MySql Community Server.
+    Community,
+    /// MySql Enterprise Server.
+    Enterprise,
+    /// Percona Server for MySql.
+    Percona,
+    /// MariaDb Server.
+    MariaDb,
+    /// Other or unknown distribution (e.g. a build from source or a cloud offering
+    /// that doesn't advertise its origin).
+    Other,
+}
+
+impl ServerEdition {
+    /// Derives the server edition from the value of `@@version_comment`.
+    pub fn from_version_comment(version_comment: &str) -> Self {
+        let version_comment = version_comment.to_ascii_lowercase();
+        if version_comment.contains("mariadb") {
+            ServerEdition::MariaDb
+        } else if version_comment.contains("percona") {
+            ServerEdition::Percona
+        } else if version_comment.contains("enterprise") {
+            ServerEdition::Enterprise
+        } else if version_comment.contains("community") {
+            ServerEdition::Community
+        } else {
+            ServerEdition::Other
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::ServerEdition;
+
+    #[test]
+    fn should_parse_version_comment() {
+        let cases = [
+            ("MySQL Community Server - GPL", ServerEdition::Community),
+            ("MySQL Community Server (GPL)", ServerEdition::Community),
+            ("MySQL Enterprise Server - Commercial", ServerEdition::Enterprise),
+            (
+                "Percona Server (GPL), Release 36, Revision 7e403c5",
+                ServerEdition::Percona,
+            ),
+            (
+                "Percona XtraDB Cluster (GPL), Release rel36, Revision 3fa2a8d, WSREP version 26.1.4.3",
+                ServerEdition::Percona,
+            ),
+            ("mariadb.org binary distribution", ServerEdition::MariaDb),
+            ("MariaDB Server", ServerEdition::MariaDb),
+            ("Source distribution", ServerEdition::Other),
+            ("(Ubuntu)", ServerEdition::Other),
+            ("", ServerEdition::Other),
+        ];
+
+        for (version_comment, edition) in cases {
+            assert_eq!(
+                ServerEdition::from_version_comment(version_comment),
+                edition,
+                "{}",
+                version_comment
+            );
+        }
+    }
+}
@@ -38,11 +38,27 @@ pub struct Entry {
     pub query: QueryString,
 }
 
+/// Statement prepared using SQL-level `PREPARE` (see [`crate::Opts::sql_prepared_statements`]).
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct SqlEntry {
+    /// Name of the statement on the server side.
+    pub name: Arc<str>,
+    /// Number of `?` placeholders.
+    pub num_params: u16,
+}
+
 #[derive(Debug)]
 pub struct StmtCache {
     cap: usize,
     cache: LruCache<u32, Entry>,
     query_map: HashMap<QueryString, u32, BuildHasherDefault<XxHash64>>,
+    /// Ids of all statements that are open on the server side (cached or not),
+    /// ordered by last use.
+    open: LruCache<u32, ()>,
+    /// Statements prepared using SQL-level `PREPARE`.
+    sql: LruCache<QueryString, SqlEntry>,
+    /// Sequence number of the next SQL-level prepared statement.
+    next_sql_id: u64,
 }
 
 impl StmtCache {
@@ -51,6 +67,9 @@ impl StmtCache {
             cap,
             cache: LruCache::unbounded(),
             query_map: Default::default(),
+            open: LruCache::unbounded(),
+            sql: LruCache::unbounded(),
+            next_sql_id: 0,
         }
     }
 
@@ -91,12 +110,63 @@ impl StmtCache {
     pub fn clear(&mut self) {
         self.query_map.clear();
         self.cache.clear();
+        self.open.clear();
+        self.sql.clear();
     }
 
     pub fn remove(&mut self, id: u32) {
         if let Some(entry) = self.cache.pop(&id) {
             self.query_map.remove::<[u8]>(entry.query.borrow());
         }
+        self.open.pop(&id);
+    }
+
+    /// Tracks a statement that was just prepared on the server side.
+    pub fn opened(&mut self, id: u32) {
+        self.open.put(id, ());
+    }
+
+    /// Marks the given open statement as recently used.
+    pub fn touch(&mut self, id: u32) {
+        self.open.get(&id);
+    }
+
+    /// Number of statements open on the server side.
+    pub fn open_count(&self) -> usize {
+        self.open.len()
+    }
+
+    /// Returns the least recently used open statement.
+    pub fn lru_open(&self) -> Option<u32> {
+        self.open.peek_lru().map(|(id, _)| *id)
+    }
+
+    /// Returns SQL-level prepared statement, if cached.
+    pub fn sql_by_query(&mut self, query: &[u8]) -> Option<SqlEntry> {
+        self.sql.get(query).cloned()
+    }
+
+    /// Returns a name for a new SQL-level prepared statement, that isn't used by any cached one.
+    pub fn next_sql_name(&mut self, prefix: &str) -> Arc<str> {
+        self.next_sql_id += 1;
+        format!("{}_{}", prefix, self.next_sql_id).into()
+    }
+
+    /// Caches SQL-level prepared statement.
+    ///
+    /// Returns LRU statement on cache capacity overflow (it must be deallocated by the caller).
+    pub fn put_sql(&mut self, query: Arc<[u8]>, entry: SqlEntry) -> Option<SqlEntry> {
+        self.sql.put(QueryString(query), entry);
+        if self.sql.len() > self.cap {
+            return self.sql.pop_lru().map(|(_, entry)| entry);
+        }
+        None
+    }
+
+    /// Number of cached SQL-level prepared statements.
+    #[cfg(test)]
+    pub fn sql_len(&self) -> usize {
+        self.sql.len()
     }
 
     #[cfg(test)]
@@ -132,6 +202,24 @@ impl super::Conn {
         }
     }
 
+    /// Closes least recently used statements, so that the number of statements open
+    /// on the server side doesn't exceed [`crate::Opts::max_prepared_statements`].
+    pub(crate) async fn enforce_max_prepared_statements(&mut self) -> crate::Result<()> {
+        let max = match self.inner.opts.max_prepared_statements() {
+            Some(max) => max.max(1),
+            None => return Ok(()),
+        };
+
+        while self.inner.stmt_cache.open_count() > max {
+            match self.inner.stmt_cache.lru_open() {
+                Some(id) => self.close_statement(id).await?,
+                None => break,
+            }
+        }
+
+        Ok(())
+    }
+
     /// Returns statement, if cached.
     ///
     /// `raw_query` is the query with `?` placeholders (not with `:<name>` placeholders).
@@ -0,0 +1,138 @@
+// Copyright (c) 2025 mysql_async contributors
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+/// Query used by [`crate::Conn::table_columns`].
+pub(crate) const TABLE_COLUMNS_QUERY: &str = "SELECT COLUMN_NAME, ORDINAL_POSITION, DATA_TYPE, \
+    COLUMN_TYPE, IS_NULLABLE, COLUMN_DEFAULT, COLUMN_KEY, EXTRA, CHARACTER_SET_NAME, COLLATION_NAME \
+    FROM information_schema.COLUMNS WHERE TABLE_SCHEMA = ? AND TABLE_NAME = ? \
+    ORDER BY ORDINAL_POSITION";
+
+/// Row of [`TABLE_COLUMNS_QUERY`].
+pub(crate) type TableColumnsRow = (
+    String,
+    u32,
+    String,
+    String,
+    String,
+    Option<String>,
+    String,
+    String,
+    Option<String>,
+    Option<String>,
+);
+
+/// Definition of a table column.
+///
+/// Returned by [`crate::Conn::table_columns`]. Fields correspond to columns
+/// of `information_schema.COLUMNS`.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ColumnDef {
+    /// Column name (`COLUMN_NAME`).
+    pub name: String,
+    /// 1-based position of the column within the table (`ORDINAL_POSITION`).
+    pub position: u32,
+    /// Data type without any additional information (`DATA_TYPE`), e.g. `varchar`.
+    pub data_type: String,
+    /// Full column type (`COLUMN_TYPE`), e.g. `varchar(64)` or `int unsigned`.
+    pub column_type: String,
+    /// `true` if the column is nullable (`IS_NULLABLE`).
+    pub nullable: bool,
+    /// Default value of the column (`COLUMN_DEFAULT`), or `None` if the default is `NULL`
+    /// or there is no default.
+    ///
+    /// String literals are unquoted (MariaDB reports them quoted), expressions
+    /// are reported as is (e.g. `CURRENT_TIMESTAMP`).
+    pub default: Option<String>,
+    /// Index the column belongs to (`COLUMN_KEY`): `PRI`, `UNI`, `MUL` or an empty string.
+    pub key: String,
+    /// Additional information (`EXTRA`), e.g. `auto_increment`.
+    pub extra: String,
+    /// Character set of a string column (`CHARACTER_SET_NAME`).
+    pub character_set: Option<String>,
+    /// Collation of a string column (`COLLATION_NAME`).
+    pub collation: Option<String>,
+}
+
+impl ColumnDef {
+    /// Returns `true` if the column is a part of the primary key.
+    pub fn is_primary_key(&self) -> bool {
+        self.key == "PRI"
+    }
+
+    /// Returns `true` if the column has the `AUTO_INCREMENT` attribute.
+    pub fn is_auto_increment(&self) -> bool {
+        self.extra.to_ascii_lowercase().contains("auto_increment")
+    }
+
+    pub(crate) fn from_row(row: TableColumnsRow, is_mariadb: bool) -> Self {
+        let (
+            name,
+            position,
+            data_type,
+            column_type,
+            nullable,
+            default,
+            key,
+            extra,
+            character_set,
+            collation,
+        ) = row;
+
+        let default = if is_mariadb {
+            default.and_then(|x| unquote_mariadb_default(&x))
+        } else {
+            default
+        };
+
+        Self {
+            name,
+            position,
+            data_type,
+            column_type,
+            nullable: nullable.eq_ignore_ascii_case("YES"),
+            default,
+            key,
+            extra,
+            character_set,
+            collation,
+        }
+    }
+}
+
+/// MariaDB (since 10.2.7) reports `NULL` default as `NULL` and quotes string literals.
+fn unquote_mariadb_default(default: &str) -> Option<String> {
+    if default == "NULL" {
+        return None;
+    }
+
+    match default
+        .strip_prefix('\'')
+        .and_then(|x| x.strip_suffix('\''))
+    {
+        Some(literal) => Some(literal.replace("''", "'")),
+        None => Some(default.to_owned()),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::unquote_mariadb_default;
+
+    #[test]
+    fn should_unquote_mariadb_default() {
+        assert_eq!(unquote_mariadb_default("NULL"), None);
+        assert_eq!(unquote_mariadb_default("'NULL'").as_deref(), Some("NULL"));
+        assert_eq!(unquote_mariadb_default("'it''s'").as_deref(), Some("it's"));
+        assert_eq!(unquote_mariadb_default("''").as_deref(), Some(""));
+        assert_eq!(unquote_mariadb_default("42").as_deref(), Some("42"));
+        assert_eq!(
+            unquote_mariadb_default("current_timestamp()").as_deref(),
+            Some("current_timestamp()")
+        );
+    }
+}
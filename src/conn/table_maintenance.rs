@@ -0,0 +1,102 @@
+// Copyright (c) 2025 mysql_async contributors
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+/// Row of the result of a table maintenance statement.
+///
+/// Returned by [`crate::Conn::check_tables`], [`crate::Conn::optimize_tables`]
+/// and [`crate::Conn::analyze_tables`]. Fields correspond to columns of the result of
+/// `CHECK TABLE`, `OPTIMIZE TABLE` and `ANALYZE TABLE`. There might be multiple rows
+/// per table (one per message).
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct TableMaintenanceMessage {
+    /// Qualified name of the table (`Table`), e.g. `db.tbl`.
+    pub table: String,
+    /// The operation (`Op`): `check`, `optimize` or `analyze`.
+    pub op: String,
+    /// Type of the message (`Msg_type`): `status`, `error`, `info`, `note` or `warning`.
+    pub msg_type: String,
+    /// The message (`Msg_text`), e.g. `OK` or `Table is already up to date`.
+    pub msg_text: String,
+}
+
+impl TableMaintenanceMessage {
+    /// Returns `true` if this is an error message.
+    ///
+    /// Note that a failed operation is reported via the result rather than via a server error
+    /// (e.g. a missing table yields an `Error` message followed by `error` status).
+    pub fn is_error(&self) -> bool {
+        self.msg_type.eq_ignore_ascii_case("error")
+    }
+
+    /// Returns `true` if this is a status message.
+    pub fn is_status(&self) -> bool {
+        self.msg_type.eq_ignore_ascii_case("status")
+    }
+}
+
+/// Row of a table maintenance statement result (`Table`, `Op`, `Msg_type`, `Msg_text`).
+pub(crate) type TableMaintenanceRow = (String, String, String, String);
+
+impl From<TableMaintenanceRow> for TableMaintenanceMessage {
+    fn from((table, op, msg_type, msg_text): TableMaintenanceRow) -> Self {
+        Self {
+            table,
+            op,
+            msg_type,
+            msg_text,
+        }
+    }
+}
+
+/// Builds a table maintenance statement, e.g. `CHECK TABLE `db`.`tbl``.
+///
+/// A name might be qualified with a schema (`db.tbl`). Both parts are quoted.
+pub(crate) fn table_maintenance_query<I, T>(statement: &str, tables: I) -> Option<String>
+where
+    I: IntoIterator<Item = T>,
+    T: AsRef<str>,
+{
+    let tables = tables
+        .into_iter()
+        .map(|table| {
+            table
+                .as_ref()
+                .splitn(2, '.')
+                .map(|part| format!("`{}`", part.replace('`', "``")))
+                .collect::<Vec<_>>()
+                .join(".")
+        })
+        .collect::<Vec<_>>();
+
+    if tables.is_empty() {
+        None
+    } else {
+        Some(format!("{} TABLE {}", statement, tables.join(", ")))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::table_maintenance_query;
+
+    #[test]
+    fn should_build_table_maintenance_query() {
+        assert_eq!(
+            table_maintenance_query("CHECK", ["foo", "db.bar"]).as_deref(),
+            Some("CHECK TABLE `foo`, `db`.`bar`")
+        );
+        assert_eq!(
+            table_maintenance_query("ANALYZE", ["a`b.c.d"]).as_deref(),
+            Some("ANALYZE TABLE `a``b`.`c.d`")
+        );
+        assert_eq!(
+            table_maintenance_query("OPTIMIZE", Vec::<String>::new()),
+            None
+        );
+    }
+}
@@ -51,6 +51,7 @@ impl Error {
     /// Returns true if the error means that connection is broken.
     pub fn is_fatal(&self) -> bool {
         match self {
+            Error::Driver(DriverError::PartialResult { error, .. }) => error.is_fatal(),
             Error::Driver(_) | Error::Io(_) | Error::Other(_) | Error::Url(_) => true,
             Error::Server(_) => false,
         }
@@ -91,6 +92,35 @@ pub enum UrlError {
     #[error("Invalid pool constraints: pool_min ({}) > pool_max ({}).", min, max)]
     InvalidPoolConstraints { min: usize, max: usize },
 
+    #[error("Empty host name.")]
+    EmptyHost,
+
+    #[error("Invalid TLS versions: min ({:?}) > max ({:?}).", min, max)]
+    InvalidTlsVersions {
+        min: crate::TlsVersion,
+        max: crate::TlsVersion,
+    },
+
+    #[error(
+        "Connection URL parameter `{}={}' would disable SSL required by the `mysqls' scheme",
+        param,
+        value
+    )]
+    SslDowngrade { param: String, value: String },
+
+    #[error(
+        "Conflicting connection URL parameters `require_ssl={}' and `ssl_mode={}'",
+        require_ssl,
+        ssl_mode
+    )]
+    ConflictingSslParams { require_ssl: bool, ssl_mode: String },
+
+    #[error("SOCKS5 proxy can't be combined with socket connections (`prefer_socket`, `socket`).")]
+    ProxyWithSocket,
+
+    #[error("SOCKS5 proxy can't be combined with `bind_address`.")]
+    ProxyWithBindAddress,
+
     #[error("URL parse error: {}", _0)]
     Parse(#[source] ParseError),
 
@@ -128,12 +158,33 @@ pub enum DriverError {
     #[error("Transactions couldn't be nested.")]
     NestedTransaction,
 
+    #[error("Transaction is still active after a rollback.")]
+    TransactionNotRolledBack,
+
     #[error("Packet out of order.")]
     PacketOutOfOrder,
 
     #[error("Pool was disconnected.")]
     PoolDisconnected,
 
+    #[error("Unable to get a connection from the pool within {:?}.", timeout)]
+    PoolAcquireTimeout { timeout: std::time::Duration },
+
+    #[error(
+        "Unable to establish {} connections of the pool within {:?} ({} are ready).",
+        min,
+        timeout,
+        ready
+    )]
+    PoolMinNotReached {
+        min: usize,
+        ready: usize,
+        timeout: std::time::Duration,
+    },
+
+    #[error("Result doesn't fit into the result memory budget of {} bytes.", limit)]
+    ResultMemoryBudgetExceeded { limit: usize },
+
     #[error("`SET TRANSACTION READ (ONLY|WRITE)' is not supported in your MySQL version.")]
     ReadOnlyTransNotSupported,
 
@@ -151,9 +202,40 @@ pub enum DriverError {
     )]
     StmtParamsNumberExceedsLimit { supplied: usize },
 
+    #[error(
+        "Query of {} bytes exceeds the limit of {} bytes (see `OptsBuilder::max_query_len`).",
+        len,
+        max
+    )]
+    QueryTooLong { len: usize, max: usize },
+
+    #[error(
+        "Parameter {} of {} bytes exceeds the limit of {} bytes (see `OptsBuilder::max_param_len`).",
+        index,
+        len,
+        max
+    )]
+    ParamTooLong {
+        index: usize,
+        len: usize,
+        max: usize,
+    },
+
+    #[error(
+        "String can't be escaped as UTF-8 text in the `{}' character set of the connection.",
+        charset
+    )]
+    CantEscapeString { charset: String },
+
     #[error("Unexpected packet.")]
     UnexpectedPacket { payload: Vec<u8> },
 
+    #[error(
+        "Unexpected NULL in column `{}' converted into a non-optional type.",
+        column
+    )]
+    UnexpectedNull { column: String },
+
     #[error("Unknown authentication plugin `{}'.", name)]
     UnknownAuthPlugin { name: String },
 
@@ -175,11 +257,111 @@ pub enum DriverError {
     #[error("No private key found in the file specified")]
     NoKeyFound,
 
+    #[error("Client identity is set both as PKCS#12 archive and as PEM certificate and key")]
+    ConflictingClientIdentity,
+
+    #[error("PEM client identity requires both the certificate and the private key path")]
+    IncompleteClientIdentity,
+
     #[error("Client asked for SSL but server does not have this capability")]
     NoClientSslFlagFromServer,
 
+    #[error("TLS over a caller-provided stream must be established by the caller")]
+    TlsOverCustomStream,
+
+    #[error(
+        "TLS backend supports no version within the configured range (min: {:?}, max: {:?}).",
+        min,
+        max
+    )]
+    UnsupportedTlsVersions {
+        min: Option<crate::TlsVersion>,
+        max: Option<crate::TlsVersion>,
+    },
+
+    #[error("Unable to establish a TCP connection within {:?}.", timeout)]
+    ConnectTimeout { timeout: std::time::Duration },
+
+    #[error(
+        "Unable to connect to `{}' from {}: the host has no address of the same IP family.",
+        host,
+        bind_address
+    )]
+    BindAddressFamilyMismatch {
+        bind_address: std::net::SocketAddr,
+        host: String,
+    },
+
+    #[error("Unable to read a packet from the server within {:?}.", timeout)]
+    ReadTimeout { timeout: std::time::Duration },
+
+    #[error("Unable to write a packet to the server within {:?}.", timeout)]
+    WriteTimeout { timeout: std::time::Duration },
+
+    #[error("Unable to connect via the preferred socket `{}': {}", socket, error)]
+    SocketUnavailable {
+        socket: String,
+        #[source]
+        error: Box<Error>,
+    },
+
+    #[error("Unable to prepare statement {} of the batch: {}", index, error)]
+    PrepareManyFailed {
+        index: usize,
+        #[source]
+        error: Box<Error>,
+    },
+
+    #[error(
+        "Batch of {} distinct statements doesn't fit into {} cached statements",
+        statements,
+        capacity
+    )]
+    PrepareManyExceedsCache { statements: usize, capacity: usize },
+
+    #[error("Result set was interrupted after {} row(s): {}", rows.len(), error)]
+    PartialResult {
+        rows: Vec<Row>,
+        #[source]
+        error: Box<Error>,
+    },
+
+    #[error(
+        "Row {} of a multi-row insert has {} values, but {} expected.",
+        index,
+        supplied,
+        required
+    )]
+    InsertValuesArityMismatch {
+        index: usize,
+        required: usize,
+        supplied: usize,
+    },
+
     #[error("mysql_clear_password must be enabled on the client side")]
     CleartextPluginDisabled,
+
+    #[error(
+        "Multiple statements in a single query are disabled (see `OptsBuilder::multi_statements`): {}",
+        server_error
+    )]
+    MultiStatementsDisabled { server_error: ServerError },
+
+    #[error(
+        "Resource groups are not supported by the server (see `OptsBuilder::resource_group`){}",
+        server_error.as_ref().map(|e| format!(": {}", e)).unwrap_or_default()
+    )]
+    ResourceGroupsNotSupported { server_error: Option<ServerError> },
+
+    #[error(
+        "`connection_memory_limit' is not supported by the server (requires MySql 8.0.28+){}",
+        server_error.as_ref().map(|e| format!(": {}", e)).unwrap_or_default()
+    )]
+    ConnectionMemoryLimitNotSupported { server_error: Option<ServerError> },
+
+    #[error("`INSERT ... RETURNING' is not supported by the server (requires MariaDB 10.5+)")]
+    InsertReturningNotSupported,
+
     #[error("Invalid parsec ext-salt packet received from server")]
     InvalidParsecSalt,
 }
@@ -192,6 +374,11 @@ pub enum LocalInfileError {
     ReadError(#[from] io::Error),
     #[error("Can't handle local infile request. Handler is not specified.")]
     NoHandler,
+    #[error(
+        "The server requested a file not named by the preceding `LOAD DATA LOCAL INFILE`: {}",
+        _0
+    )]
+    UnexpectedRequest(String),
     #[error(transparent)]
     OtherError(Box<dyn std::error::Error + Send + Sync + 'static>),
 }
@@ -10,18 +10,24 @@ pub use self::{read_packet::ReadPacket, write_packet::WritePacket};
 
 use bytes::BytesMut;
 use futures_core::{ready, stream};
+use futures_util::{
+    future::{select, Either},
+    stream::{FuturesUnordered, StreamExt},
+};
 use mysql_common::proto::codec::PacketCodec as PacketCodecInner;
 #[cfg(unix)]
 use tokio::io::AsyncWriteExt;
 use tokio::{
     io::{AsyncRead, AsyncWrite, ErrorKind::Interrupted, ReadBuf},
-    net::TcpStream,
+    net::{lookup_host, TcpSocket, TcpStream},
 };
 use tokio_util::codec::{Decoder, Encoder, Framed, FramedParts};
 
 #[cfg(unix)]
 use std::path::Path;
 use std::{
+    borrow::Cow,
+    collections::HashMap,
     fmt,
     future::Future,
     io::{
@@ -32,6 +38,7 @@ use std::{
     net::SocketAddr,
     ops::{Deref, DerefMut},
     pin::Pin,
+    sync::{Arc, Mutex},
     task::{Context, Poll},
     time::Duration,
 };
@@ -39,7 +46,8 @@ use std::{
 use crate::{
     buffer_pool::PooledBuf,
     error::IoError,
-    opts::{HostPortOrUrl, DEFAULT_PORT},
+    opts::{HostPortOrUrl, IpPreference, DEFAULT_PORT},
+    ProxyConfig,
 };
 
 #[cfg(unix)]
@@ -62,6 +70,8 @@ macro_rules! with_interrupted {
 
 mod read_packet;
 mod socket;
+mod socks5;
+mod srv;
 mod write_packet;
 
 #[derive(Debug)]
@@ -115,6 +125,20 @@ impl Encoder<PooledBuf> for PacketCodec {
     }
 }
 
+/// A caller-provided transport (see [`crate::Conn::from_stream`]).
+pub(crate) trait CustomIo: AsyncRead + AsyncWrite + Send + Sync {}
+
+impl<T: AsyncRead + AsyncWrite + Send + Sync> CustomIo for T {}
+
+/// A caller-provided transport (see [`crate::Conn::from_stream`]).
+pub(crate) struct CustomStream(Pin<Box<dyn CustomIo>>);
+
+impl fmt::Debug for CustomStream {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("CustomStream").field(&"<stream>").finish()
+    }
+}
+
 #[derive(Debug)]
 pub(crate) enum Endpoint {
     Plain(Option<TcpStream>),
@@ -124,6 +148,7 @@ pub(crate) enum Endpoint {
     Secure(tokio_rustls::client::TlsStream<tokio::net::TcpStream>),
     #[cfg(unix)]
     Socket(Socket),
+    Custom(CustomStream),
 }
 
 /// This future will check that TcpStream is live.
@@ -158,6 +183,10 @@ impl Endpoint {
         matches!(self, Self::Socket(_))
     }
 
+    fn is_custom(&self) -> bool {
+        matches!(self, Self::Custom(_))
+    }
+
     /// Checks, that connection is alive.
     async fn check(&mut self) -> std::result::Result<(), IoError> {
         //return Ok(());
@@ -182,6 +211,8 @@ impl Endpoint {
                 let _ = socket.write(&[]).await?;
                 Ok(())
             }
+            // there is no generic way to check a caller-provided stream
+            Endpoint::Custom(_) => Ok(()),
             Endpoint::Plain(None) => unreachable!(),
         }
     }
@@ -206,6 +237,7 @@ impl Endpoint {
             }
             #[cfg(unix)]
             Endpoint::Socket(_) => (/* inapplicable */),
+            Endpoint::Custom(_) => (/* inapplicable */),
         }
         Ok(())
     }
@@ -224,6 +256,12 @@ impl From<Socket> for Endpoint {
     }
 }
 
+impl From<CustomStream> for Endpoint {
+    fn from(stream: CustomStream) -> Self {
+        Endpoint::Custom(stream)
+    }
+}
+
 #[cfg(feature = "native-tls-tls")]
 impl From<tokio_native_tls::TlsStream<TcpStream>> for Endpoint {
     fn from(stream: tokio_native_tls::TlsStream<TcpStream>) -> Self {
@@ -252,6 +290,7 @@ impl AsyncRead for Endpoint {
             Self::Secure(stream) => Pin::new(stream).poll_read(cx, buf),
             #[cfg(unix)]
             Self::Socket(stream) => Pin::new(stream).poll_read(cx, buf),
+            Self::Custom(stream) => stream.0.as_mut().poll_read(cx, buf),
         })
     }
 }
@@ -273,6 +312,7 @@ impl AsyncWrite for Endpoint {
             Self::Secure(stream) => Pin::new(stream).poll_write(cx, buf),
             #[cfg(unix)]
             Self::Socket(stream) => Pin::new(stream).poll_write(cx, buf),
+            Self::Custom(stream) => stream.0.as_mut().poll_write(cx, buf),
         })
     }
 
@@ -291,6 +331,7 @@ impl AsyncWrite for Endpoint {
             Self::Secure(stream) => Pin::new(stream).poll_flush(cx),
             #[cfg(unix)]
             Self::Socket(stream) => Pin::new(stream).poll_flush(cx),
+            Self::Custom(stream) => stream.0.as_mut().poll_flush(cx),
         })
     }
 
@@ -309,6 +350,7 @@ impl AsyncWrite for Endpoint {
             Self::Secure(stream) => Pin::new(stream).poll_shutdown(cx),
             #[cfg(unix)]
             Self::Socket(stream) => Pin::new(stream).poll_shutdown(cx),
+            Self::Custom(stream) => stream.0.as_mut().poll_shutdown(cx),
         })
     }
 }
@@ -329,8 +371,197 @@ impl fmt::Debug for Stream {
     }
 }
 
+/// Addresses resolved by connections that share the same [`crate::Opts`]
+/// (see [`crate::Opts::resolve_on_reconnect`]).
+#[derive(Clone, Default)]
+pub(crate) struct AddrCache {
+    addrs: Arc<Mutex<HashMap<(String, u16), Vec<SocketAddr>>>>,
+}
+
+impl AddrCache {
+    /// Resolves `addr` using `resolve` unless it's already resolved and `re_resolve` is `false`.
+    pub(crate) async fn resolve<'a, F, Fut>(
+        &self,
+        addr: &'a HostPortOrUrl,
+        re_resolve: bool,
+        resolve: F,
+    ) -> io::Result<Vec<SocketAddr>>
+    where
+        F: FnOnce(&'a HostPortOrUrl) -> Fut,
+        Fut: Future<Output = io::Result<Vec<SocketAddr>>>,
+    {
+        if re_resolve {
+            return resolve(addr).await;
+        }
+
+        let key = (addr.get_ip_or_hostname().to_owned(), addr.get_tcp_port());
+        if let Some(addrs) = self.addrs.lock().unwrap().get(&key) {
+            return Ok(addrs.clone());
+        }
+        let addrs = resolve(addr).await?;
+        self.addrs.lock().unwrap().insert(key, addrs.clone());
+        Ok(addrs)
+    }
+
+    /// Forgets addresses of `addr`, so that the next connection resolves it again.
+    pub(crate) fn forget(&self, addr: &HostPortOrUrl) {
+        let key = (addr.get_ip_or_hostname().to_owned(), addr.get_tcp_port());
+        self.addrs.lock().unwrap().remove(&key);
+    }
+}
+
+impl PartialEq for AddrCache {
+    fn eq(&self, _other: &Self) -> bool {
+        // it's a cache rather than an option
+        true
+    }
+}
+
+impl Eq for AddrCache {}
+
+impl fmt::Debug for AddrCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AddrCache").finish_non_exhaustive()
+    }
+}
+
+/// Resolves the given hosts, in order. Hosts that fail to resolve are skipped
+/// unless all of them fail.
+async fn lookup_hosts(hosts: &[(String, u16)]) -> io::Result<Vec<SocketAddr>> {
+    let mut addrs = Vec::new();
+    let mut last_err = None;
+    for (host, port) in hosts {
+        match lookup_host((host.as_str(), *port)).await {
+            Ok(resolved) => addrs.extend(resolved),
+            Err(err) => last_err = Some(err),
+        }
+    }
+    match last_err {
+        Some(err) if addrs.is_empty() => Err(err),
+        _ => Ok(addrs),
+    }
+}
+
+/// Orders resolved addresses according to the given IP family preference.
+///
+/// If `interleave` is `true`, then addresses of different families are interleaved
+/// (as recommended by RFC 8305), starting with the preferred family (or with the family
+/// of the first address, if there is no preference).
+pub(crate) fn order_addrs(
+    mut addrs: Vec<SocketAddr>,
+    preference: IpPreference,
+    interleave: bool,
+) -> Vec<SocketAddr> {
+    let first_is_v4 = match preference {
+        IpPreference::Any => match addrs.first() {
+            Some(addr) => addr.is_ipv4(),
+            None => return addrs,
+        },
+        IpPreference::Ipv4 => true,
+        IpPreference::Ipv6 => false,
+    };
+
+    // stable sort keeps the resolver order within a family
+    addrs.sort_by_key(|addr| addr.is_ipv4() != first_is_v4);
+
+    if interleave {
+        let split = addrs
+            .iter()
+            .position(|addr| addr.is_ipv4() != first_is_v4)
+            .unwrap_or(addrs.len());
+        let (preferred, other) = addrs.split_at(split);
+        let mut interleaved = Vec::with_capacity(addrs.len());
+        let mut preferred = preferred.iter();
+        let mut other = other.iter();
+        loop {
+            match (preferred.next(), other.next()) {
+                (None, None) => break,
+                (a, b) => interleaved.extend(a.into_iter().chain(b)),
+            }
+        }
+        addrs = interleaved;
+    }
+
+    addrs
+}
+
+/// Concurrently connects to `addrs` starting the next attempt every `delay`
+/// (or as soon as the previous attempt fails). Returns the first established connection.
+async fn connect_concurrently(
+    addrs: &[SocketAddr],
+    delay: Duration,
+    bind_address: Option<SocketAddr>,
+) -> io::Result<TcpStream> {
+    let mut attempts = FuturesUnordered::new();
+    let mut next_addr = addrs.iter();
+    let mut last_err = None;
+
+    loop {
+        if let Some(addr) = next_addr.next() {
+            attempts.push(connect_addr(*addr, bind_address));
+        }
+
+        let mut stagger = Box::pin(tokio::time::sleep(delay));
+        loop {
+            match select(attempts.next(), &mut stagger).await {
+                Either::Left((Some(Ok(stream)), _)) => return Ok(stream),
+                Either::Left((Some(Err(err)), _)) => {
+                    last_err = Some(err);
+                    if !next_addr.as_slice().is_empty() {
+                        // start the next attempt right away
+                        break;
+                    }
+                }
+                Either::Left((None, _)) if next_addr.as_slice().is_empty() => {
+                    return Err(last_err.unwrap_or_else(|| {
+                        io::Error::new(
+                            io::ErrorKind::InvalidInput,
+                            "could not resolve to any address",
+                        )
+                    }));
+                }
+                Either::Left((None, _)) | Either::Right(_) => break,
+            }
+        }
+    }
+}
+
+/// Connects to the addresses one by one, like `TcpStream::connect` does.
+async fn connect_sequentially(
+    addrs: &[SocketAddr],
+    bind_address: Option<SocketAddr>,
+) -> io::Result<TcpStream> {
+    let mut last_err = None;
+    for addr in addrs {
+        match connect_addr(*addr, bind_address).await {
+            Ok(stream) => return Ok(stream),
+            Err(err) => last_err = Some(err),
+        }
+    }
+    Err(last_err.unwrap_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "could not resolve to any address",
+        )
+    }))
+}
+
+/// Connects to `addr` from the given local address, if any
+/// (see [`crate::Opts::bind_address`]).
+async fn connect_addr(addr: SocketAddr, bind_address: Option<SocketAddr>) -> io::Result<TcpStream> {
+    let bind_address = match bind_address {
+        Some(bind_address) => bind_address,
+        None => return TcpStream::connect(addr).await,
+    };
+    let socket = match addr {
+        SocketAddr::V4(_) => TcpSocket::new_v4()?,
+        SocketAddr::V6(_) => TcpSocket::new_v6()?,
+    };
+    socket.bind(bind_address)?;
+    socket.connect(addr).await
+}
+
 impl Stream {
-    #[cfg(unix)]
     fn new<T: Into<Endpoint>>(endpoint: T) -> Self {
         let endpoint = endpoint.into();
 
@@ -340,31 +571,71 @@ impl Stream {
         }
     }
 
-    pub(crate) async fn connect_tcp(
-        addr: &HostPortOrUrl,
-        keepalive: Option<Duration>,
-    ) -> io::Result<Stream> {
-        let tcp_stream = match addr {
+    /// Looks up hosts of an SRV address (see [`crate::Opts::srv_name`]).
+    ///
+    /// Other addresses are returned as is.
+    pub(crate) async fn resolve_srv(addr: &HostPortOrUrl) -> io::Result<Cow<'_, HostPortOrUrl>> {
+        match addr {
+            HostPortOrUrl::Srv(name) => Ok(Cow::Owned(HostPortOrUrl::Multiple(
+                srv::lookup_srv(name).await?,
+            ))),
+            _ => Ok(Cow::Borrowed(addr)),
+        }
+    }
+
+    /// Resolves the given address into a list of socket addresses to connect to.
+    ///
+    /// Addresses of multiple hosts are listed in the order of hosts. Hosts that fail
+    /// to resolve are skipped unless all of them fail.
+    pub(crate) async fn resolve_tcp(addr: &HostPortOrUrl) -> io::Result<Vec<SocketAddr>> {
+        match addr {
             HostPortOrUrl::HostPort {
                 host,
                 port,
                 resolved_ips,
             } => match resolved_ips {
-                Some(ips) => {
-                    let addrs = ips
-                        .iter()
-                        .map(|ip| SocketAddr::new(*ip, *port))
-                        .collect::<Vec<_>>();
-                    TcpStream::connect(&*addrs).await?
-                }
-                None => TcpStream::connect((host.as_str(), *port)).await?,
+                Some(ips) => Ok(ips.iter().map(|ip| SocketAddr::new(*ip, *port)).collect()),
+                None => Ok(lookup_host((host.as_str(), *port)).await?.collect()),
             },
-            HostPortOrUrl::Url(url) => {
-                let addrs = url.socket_addrs(|| Some(DEFAULT_PORT))?;
-                TcpStream::connect(&*addrs).await?
-            }
+            HostPortOrUrl::Url(url) => url.socket_addrs(|| Some(DEFAULT_PORT)),
+            HostPortOrUrl::Multiple(hosts) => lookup_hosts(hosts).await,
+            HostPortOrUrl::Srv(name) => lookup_hosts(&srv::lookup_srv(name).await?).await,
+        }
+    }
+
+    /// Connects to the first reachable address among `addrs`
+    /// (see [`Stream::resolve_tcp`] and [`order_addrs`]).
+    ///
+    /// Addresses are tried concurrently if `happy_eyeballs_delay` is given
+    /// (see [`crate::Opts::happy_eyeballs_delay`]). The socket is bound to `bind_address`
+    /// if given (see [`crate::Opts::bind_address`]).
+    pub(crate) async fn connect_tcp(
+        addrs: &[SocketAddr],
+        keepalive: Option<Duration>,
+        happy_eyeballs_delay: Option<Duration>,
+        bind_address: Option<SocketAddr>,
+    ) -> io::Result<Stream> {
+        let tcp_stream = match (happy_eyeballs_delay, bind_address) {
+            (Some(delay), _) => connect_concurrently(addrs, delay, bind_address).await?,
+            (None, None) => TcpStream::connect(addrs).await?,
+            (None, Some(_)) => connect_sequentially(addrs, bind_address).await?,
         };
 
+        Stream::from_tcp(tcp_stream, keepalive)
+    }
+
+    /// Connects to `host:port` through the given SOCKS5 proxy (see [`crate::Opts::proxy`]).
+    pub(crate) async fn connect_proxy(
+        proxy: &ProxyConfig,
+        host: &str,
+        port: u16,
+        keepalive: Option<Duration>,
+    ) -> io::Result<Stream> {
+        let tcp_stream = socks5::connect_socks5(proxy, host, port).await?;
+        Stream::from_tcp(tcp_stream, keepalive)
+    }
+
+    fn from_tcp(tcp_stream: TcpStream, keepalive: Option<Duration>) -> io::Result<Stream> {
         #[cfg(any(unix, windows))]
         if let Some(duration) = keepalive {
             socket2::SockRef::from(&tcp_stream)
@@ -382,6 +653,14 @@ impl Stream {
         Ok(Stream::new(Socket::new(path).await?))
     }
 
+    /// Wraps a caller-provided transport (see [`crate::Conn::from_stream`]).
+    pub(crate) fn from_custom<T>(stream: T) -> Stream
+    where
+        T: AsyncRead + AsyncWrite + Send + Sync + 'static,
+    {
+        Stream::new(CustomStream(Box::pin(stream)))
+    }
+
     pub(crate) fn set_tcp_nodelay(&self, val: bool) -> io::Result<()> {
         self.codec.as_ref().unwrap().get_ref().set_tcp_nodelay(val)
     }
@@ -404,11 +683,20 @@ impl Stream {
         self.codec.as_ref().unwrap().get_ref().is_secure()
     }
 
+    #[cfg(feature = "rustls-tls")]
+    pub(crate) fn is_tls_resumed(&self) -> bool {
+        self.codec.as_ref().unwrap().get_ref().is_tls_resumed()
+    }
+
     #[cfg(unix)]
     pub(crate) fn is_socket(&self) -> bool {
         self.codec.as_ref().unwrap().get_ref().is_socket()
     }
 
+    pub(crate) fn is_custom(&self) -> bool {
+        self.codec.as_ref().unwrap().get_ref().is_custom()
+    }
+
     pub(crate) fn reset_seq_id(&mut self) {
         if let Some(codec) = self.codec.as_mut() {
             codec.codec_mut().reset_seq_id();
@@ -421,6 +709,19 @@ impl Stream {
         }
     }
 
+    /// Sets the sequence id as if a command was just written.
+    ///
+    /// Used to read a response to a pipelined command, i.e. to a command written before
+    /// the last one. Not supported for compressed streams.
+    pub(crate) fn expect_pipelined_response(&mut self) {
+        if let Some(codec) = self.codec.as_mut() {
+            let codec = codec.codec_mut();
+            codec.reset_seq_id();
+            // encoding an empty packet advances the sequence id the same way writing does
+            let _ = codec.encode(crate::buffer_pool().get(), &mut BytesMut::new());
+        }
+    }
+
     pub(crate) fn set_max_allowed_packet(&mut self, max_allowed_packet: usize) {
         if let Some(codec) = self.codec.as_mut() {
             codec.codec_mut().max_allowed_packet = max_allowed_packet;
@@ -472,6 +773,129 @@ impl stream::Stream for Stream {
 
 #[cfg(test)]
 mod test {
+    use std::{
+        io,
+        net::{IpAddr, SocketAddr},
+        sync::atomic::{AtomicUsize, Ordering},
+        time::{Duration, Instant},
+    };
+
+    use super::{order_addrs, AddrCache};
+    use crate::{
+        opts::HostPortOrUrl,
+        test_misc::{get_opts, unresponsive_listener},
+        Conn, IpPreference, Opts,
+    };
+
+    #[test]
+    fn should_order_addrs() {
+        let v4_1: SocketAddr = "10.0.0.1:3306".parse().unwrap();
+        let v4_2: SocketAddr = "10.0.0.2:3306".parse().unwrap();
+        let v6_1: SocketAddr = "[fd00::1]:3306".parse().unwrap();
+        let v6_2: SocketAddr = "[fd00::2]:3306".parse().unwrap();
+        let addrs = vec![v6_1, v6_2, v4_1, v4_2];
+
+        assert_eq!(order_addrs(addrs.clone(), IpPreference::Any, false), addrs);
+        assert_eq!(
+            order_addrs(addrs.clone(), IpPreference::Ipv4, false),
+            vec![v4_1, v4_2, v6_1, v6_2]
+        );
+        assert_eq!(
+            order_addrs(addrs.clone(), IpPreference::Ipv6, false),
+            vec![v6_1, v6_2, v4_1, v4_2]
+        );
+        assert_eq!(
+            order_addrs(addrs.clone(), IpPreference::Any, true),
+            vec![v6_1, v4_1, v6_2, v4_2]
+        );
+        assert_eq!(
+            order_addrs(addrs.clone(), IpPreference::Ipv4, true),
+            vec![v4_1, v6_1, v4_2, v6_2]
+        );
+        assert_eq!(
+            order_addrs(vec![v4_1, v4_2, v6_1], IpPreference::Ipv6, true),
+            vec![v6_1, v4_1, v4_2]
+        );
+        assert_eq!(order_addrs(vec![], IpPreference::Ipv6, true), vec![]);
+    }
+
+    #[tokio::test]
+    async fn should_resolve_on_reconnect() {
+        let addr = HostPortOrUrl::HostPort {
+            host: "db.example.com".into(),
+            port: 3306,
+            resolved_ips: None,
+        };
+        let primary: SocketAddr = "10.0.0.1:3306".parse().unwrap();
+        let replica: SocketAddr = "10.0.0.2:3306".parse().unwrap();
+
+        // the name points at the replica after the first lookup (i.e. after a failover)
+        let lookups = AtomicUsize::new(0);
+        let resolver = |_: &HostPortOrUrl| {
+            let n = lookups.fetch_add(1, Ordering::Relaxed);
+            async move { Ok::<_, io::Error>(vec![if n == 0 { primary } else { replica }]) }
+        };
+
+        let cache = AddrCache::default();
+        let addrs = cache.resolve(&addr, true, &resolver).await.unwrap();
+        assert_eq!(addrs, [primary]);
+        let addrs = cache.resolve(&addr, true, &resolver).await.unwrap();
+        assert_eq!(addrs, [replica]);
+
+        lookups.store(0, Ordering::Relaxed);
+        let cache = AddrCache::default();
+        let addrs = cache.resolve(&addr, false, &resolver).await.unwrap();
+        assert_eq!(addrs, [primary]);
+        let addrs = cache.resolve(&addr, false, &resolver).await.unwrap();
+        assert_eq!(addrs, [primary]);
+        assert_eq!(lookups.load(Ordering::Relaxed), 1);
+
+        // a failed connection drops sticky addresses
+        cache.forget(&addr);
+        let addrs = cache.resolve(&addr, false, &resolver).await.unwrap();
+        assert_eq!(addrs, [replica]);
+    }
+
+    // binds to 127.0.0.2, that is only routed to loopback on linux
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn should_connect_concurrently_if_first_address_is_dead() -> crate::Result<()> {
+        use tokio::net::{TcpListener, TcpStream};
+
+        // connection attempt to the dead address will hang until the OS-level timeout
+        let (dead, _listener) = unresponsive_listener(([127, 0, 0, 2], 0).into())?;
+
+        // the live address (same port) proxies to the test server
+        let proxy = TcpListener::bind(("127.0.0.1", dead.port())).await?;
+        let opts = Opts::from(get_opts());
+        let server = (opts.ip_or_hostname().to_owned(), opts.tcp_port());
+        tokio::spawn(async move {
+            for _ in 0.. {
+                let (mut client, _) = proxy.accept().await?;
+                let mut upstream = TcpStream::connect(server.clone()).await?;
+                tokio::spawn(async move {
+                    tokio::io::copy_bidirectional(&mut client, &mut upstream).await
+                });
+            }
+            io::Result::Ok(())
+        });
+
+        let ips: Vec<IpAddr> = vec![dead.ip(), "127.0.0.1".parse().unwrap()];
+        let opts = get_opts()
+            .tcp_port(dead.port())
+            .prefer_socket(false)
+            .resolved_ips(Some(ips))
+            .happy_eyeballs_delay(Some(Duration::from_millis(100)));
+
+        let start = Instant::now();
+        let conn = tokio::time::timeout(Duration::from_secs(10), Conn::new(opts))
+            .await
+            .expect("connection attempt is stuck on the dead address")?;
+        assert!(start.elapsed() < Duration::from_secs(10));
+
+        conn.disconnect().await
+    }
+
     #[cfg(unix)] // no sane way to retrieve current keepalive value on windows
     #[tokio::test]
     async fn should_connect_with_keepalive() {
@@ -0,0 +1,50 @@
+// Copyright (c) 2025 mysql_async contributors
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! TCP connections through a SOCKS5 proxy (see [`crate::Opts::proxy`]).
+
+use tokio::net::TcpStream;
+
+use std::io;
+
+use crate::ProxyConfig;
+
+/// Connects to `host:port` through the given proxy. The host name is resolved by the proxy.
+#[cfg(feature = "socks5")]
+pub(crate) async fn connect_socks5(
+    proxy: &ProxyConfig,
+    host: &str,
+    port: u16,
+) -> io::Result<TcpStream> {
+    use tokio_socks::tcp::Socks5Stream;
+
+    let proxy_addr = (proxy.host(), proxy.port());
+    let stream = match proxy.auth() {
+        Some((username, password)) => {
+            Socks5Stream::connect_with_password(proxy_addr, (host, port), username, password).await
+        }
+        None => Socks5Stream::connect(proxy_addr, (host, port)).await,
+    };
+    match stream {
+        Ok(stream) => Ok(stream.into_inner()),
+        Err(tokio_socks::Error::Io(err)) => Err(err),
+        Err(err) => Err(io::Error::other(err)),
+    }
+}
+
+#[cfg(not(feature = "socks5"))]
+pub(crate) async fn connect_socks5(
+    _proxy: &ProxyConfig,
+    _host: &str,
+    _port: u16,
+) -> io::Result<TcpStream> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "SOCKS5 proxy requires feature `socks5'",
+    ))
+}
@@ -0,0 +1,155 @@
+// Copyright (c) 2025 mysql_async contributors
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! DNS SRV lookup of hosts to connect to (see [`crate::Opts::srv_name`]).
+
+use std::io;
+
+#[cfg(any(feature = "srv", test))]
+use rand::Rng;
+
+/// A target of an SRV record.
+#[cfg(any(feature = "srv", test))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct SrvTarget {
+    priority: u16,
+    weight: u16,
+    host: String,
+    port: u16,
+}
+
+/// Looks up the SRV record `name` and returns its targets in the order to try them.
+#[cfg(feature = "srv")]
+pub(crate) async fn lookup_srv(name: &str) -> io::Result<Vec<(String, u16)>> {
+    use hickory_resolver::TokioResolver;
+
+    let resolver = TokioResolver::builder_tokio()
+        .map_err(io::Error::other)?
+        .build();
+    let lookup = resolver.srv_lookup(name).await.map_err(io::Error::other)?;
+    let targets = lookup
+        .iter()
+        .map(|srv| SrvTarget {
+            priority: srv.priority(),
+            weight: srv.weight(),
+            host: srv.target().to_utf8(),
+            port: srv.port(),
+        })
+        .collect();
+
+    let hosts = order_srv_targets(targets, &mut rand::rng());
+    if hosts.is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("SRV record `{}' has no targets", name),
+        ));
+    }
+    Ok(hosts)
+}
+
+#[cfg(not(feature = "srv"))]
+pub(crate) async fn lookup_srv(_name: &str) -> io::Result<Vec<(String, u16)>> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "SRV lookup requires feature `srv'",
+    ))
+}
+
+/// Orders SRV targets as described in RFC 2782.
+///
+/// Targets are sorted by priority (lowest first). Targets of the same priority are ordered
+/// randomly, so that a target is more likely to come first the greater its weight is.
+/// The `.` target, that means that the service is unavailable, is skipped.
+#[cfg(any(feature = "srv", test))]
+fn order_srv_targets<R: Rng>(mut targets: Vec<SrvTarget>, rng: &mut R) -> Vec<(String, u16)> {
+    targets.retain(|target| target.host != "." && !target.host.is_empty());
+    // zero-weight targets go first, so that they have a small chance to be selected
+    targets.sort_by_key(|target| (target.priority, target.weight != 0));
+
+    let mut ordered = Vec::with_capacity(targets.len());
+    let mut rest = &targets[..];
+    while !rest.is_empty() {
+        let priority = rest[0].priority;
+        let group_len = rest
+            .iter()
+            .take_while(|target| target.priority == priority)
+            .count();
+        let (group, tail) = rest.split_at(group_len);
+
+        let mut group = group.to_vec();
+        while !group.is_empty() {
+            let total = group
+                .iter()
+                .map(|target| u32::from(target.weight))
+                .sum::<u32>();
+            let pick = rng.random_range(0..=total);
+            let mut running = 0;
+            let index = group
+                .iter()
+                .position(|target| {
+                    running += u32::from(target.weight);
+                    running >= pick
+                })
+                .unwrap_or(0);
+            let target = group.remove(index);
+            let host = target.host.trim_end_matches('.').to_owned();
+            ordered.push((host, target.port));
+        }
+
+        rest = tail;
+    }
+
+    ordered
+}
+
+#[cfg(test)]
+mod test {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::{order_srv_targets, SrvTarget};
+
+    fn target(priority: u16, weight: u16, host: &str) -> SrvTarget {
+        SrvTarget {
+            priority,
+            weight,
+            host: host.into(),
+            port: 3306,
+        }
+    }
+
+    #[test]
+    fn should_order_srv_targets() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let targets = vec![
+            target(20, 0, "backup.example.com."),
+            target(10, 10, "a.example.com."),
+            target(10, 90, "b.example.com."),
+            target(30, 0, "."),
+        ];
+
+        let mut first_b = 0;
+        for _ in 0..1000 {
+            let ordered = order_srv_targets(targets.clone(), &mut rng);
+            let hosts = ordered
+                .iter()
+                .map(|(host, _)| host.as_str())
+                .collect::<Vec<_>>();
+            assert_eq!(hosts.len(), 3);
+            assert_eq!(hosts[2], "backup.example.com");
+            if hosts[0] == "b.example.com" {
+                first_b += 1;
+            } else {
+                assert_eq!(hosts[..2], ["a.example.com", "b.example.com"]);
+            }
+        }
+        // `b` has 90% of the weight of its priority
+        assert!((800..1000).contains(&first_b), "{}", first_b);
+
+        assert!(order_srv_targets(vec![target(0, 0, ".")], &mut rng).is_empty());
+    }
+}
@@ -1,7 +1,7 @@
-use tokio_native_tls::native_tls::{self, Certificate};
+use tokio_native_tls::native_tls::{self, Certificate, Protocol};
 
 use crate::io::Endpoint;
-use crate::{Result, SslOpts};
+use crate::{DriverError, Result, SslOpts, TlsVersion};
 
 pub use tokio_native_tls::TlsConnector;
 
@@ -23,12 +23,31 @@ impl SslOpts {
             builder.add_root_certificate(root_cert);
         }
 
-        if let Some(client_identity) = self.client_identity() {
+        if let Some((cert_path, key_path)) = self.pem_client_identity()? {
+            let cert = tokio::fs::read(cert_path).await?;
+            let key = tokio::fs::read(key_path).await?;
+            builder.identity(native_tls::Identity::from_pkcs8(&cert, &key)?);
+        } else if let Some(client_identity) = self.client_identity() {
             builder.identity(client_identity.load().await?);
         }
         builder.danger_accept_invalid_hostnames(self.skip_domain_validation());
         builder.danger_accept_invalid_certs(self.accept_invalid_certs());
         builder.disable_built_in_roots(self.disable_built_in_roots());
+
+        let (min, max) = (self.min_tls_version(), self.max_tls_version());
+        let unsupported = || DriverError::UnsupportedTlsVersions { min, max };
+        if min.zip(max).is_some_and(|(min, max)| min > max) {
+            return Err(unsupported().into());
+        }
+        if let Some(min) = min {
+            // native-tls doesn't allow to require TLS 1.3
+            builder.min_protocol_version(Some(to_protocol(min).ok_or_else(unsupported)?));
+        }
+        if let Some(max) = max {
+            // `None` means no limit, i.e. TLS 1.3 is allowed if supported by the platform
+            builder.max_protocol_version(to_protocol(max));
+        }
+
         let tls_connector: TlsConnector = builder.build()?.into();
         Ok(tls_connector)
     }
@@ -55,12 +74,22 @@ impl Endpoint {
             Endpoint::Secure(_) => unreachable!(),
             #[cfg(unix)]
             Endpoint::Socket(_) => unreachable!(),
+            Endpoint::Custom(_) => unreachable!(),
         };
 
         Ok(())
     }
 }
 
+fn to_protocol(version: TlsVersion) -> Option<Protocol> {
+    match version {
+        TlsVersion::Tls1_0 => Some(Protocol::Tlsv10),
+        TlsVersion::Tls1_1 => Some(Protocol::Tlsv11),
+        TlsVersion::Tls1_2 => Some(Protocol::Tlsv12),
+        TlsVersion::Tls1_3 => None,
+    }
+}
+
 fn parse_certs(buf: &[u8]) -> Result<Vec<Certificate>> {
     Ok(Certificate::from_der(buf).map(|x| vec![x]).or_else(|_| {
         pem::parse_many(buf)
@@ -3,16 +3,20 @@ use std::sync::Arc;
 use rustls::{
     client::{
         danger::{ServerCertVerified, ServerCertVerifier},
-        WebPkiServerVerifier,
+        Resumption, WebPkiServerVerifier,
     },
     pki_types::{CertificateDer, ServerName},
-    ClientConfig, RootCertStore,
+    version::{TLS12, TLS13},
+    ClientConfig, HandshakeKind, RootCertStore, SupportedProtocolVersion,
 };
 
 use rustls_pemfile::certs;
 pub(crate) use tokio_rustls::TlsConnector;
 
-use crate::{io::Endpoint, Result, SslOpts, TlsError};
+use crate::{io::Endpoint, ClientIdentity, DriverError, Result, SslOpts, TlsError, TlsVersion};
+
+/// Number of TLS sessions cached by a connector (see [`SslOpts::disable_session_resumption`]).
+const TLS_SESSION_CACHE_SIZE: usize = 256;
 
 impl SslOpts {
     async fn load_root_certs(&self) -> crate::Result<Vec<CertificateDer<'static>>> {
@@ -44,9 +48,26 @@ impl SslOpts {
             root_store.add(cert)?;
         }
 
-        let config_builder = ClientConfig::builder().with_root_certificates(root_store.clone());
+        let versions = [(TlsVersion::Tls1_2, &TLS12), (TlsVersion::Tls1_3, &TLS13)]
+            .into_iter()
+            .filter(|(version, _)| self.accepts_tls_version(*version))
+            .map(|(_, version)| version)
+            .collect::<Vec<&'static SupportedProtocolVersion>>();
+        if versions.is_empty() {
+            return Err(DriverError::UnsupportedTlsVersions {
+                min: self.min_tls_version(),
+                max: self.max_tls_version(),
+            }
+            .into());
+        }
+
+        let config_builder = ClientConfig::builder_with_protocol_versions(&versions)
+            .with_root_certificates(root_store.clone());
 
-        let mut config = if let Some(identity) = self.client_identity() {
+        let pem_identity = self
+            .pem_client_identity()?
+            .map(|(cert, key)| ClientIdentity::new(cert.to_owned().into(), key.to_owned().into()));
+        let mut config = if let Some(identity) = pem_identity.as_ref().or(self.client_identity()) {
             let (cert_chain, priv_key) = identity.load().await?;
             config_builder.with_client_auth_cert(cert_chain, priv_key)?
         } else {
@@ -63,12 +84,30 @@ impl SslOpts {
             web_pki_verifier,
         );
         dangerous.set_certificate_verifier(Arc::new(dangerous_verifier));
+
+        // sessions are keyed by the server name
+        config.resumption = if self.disable_session_resumption() {
+            Resumption::disabled()
+        } else {
+            Resumption::in_memory_sessions(TLS_SESSION_CACHE_SIZE)
+        };
+
         let client_config = Arc::new(config);
         Ok(TlsConnector::from(client_config))
     }
 }
 
 impl Endpoint {
+    /// Returns `true` if this is a TLS connection established using a resumed session.
+    pub(crate) fn is_tls_resumed(&self) -> bool {
+        match self {
+            Endpoint::Secure(tls_stream) => {
+                tls_stream.get_ref().1.handshake_kind() == Some(HandshakeKind::Resumed)
+            }
+            _ => false,
+        }
+    }
+
     pub async fn make_secure(
         &mut self,
         domain: String,
@@ -94,6 +133,7 @@ impl Endpoint {
             Endpoint::Secure(_) => unreachable!(),
             #[cfg(unix)]
             Endpoint::Socket(_) => unreachable!(),
+            Endpoint::Custom(_) => unreachable!(),
         };
 
         Ok(())
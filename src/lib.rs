@@ -93,12 +93,32 @@
 //!
 //!     -   `mysql_common/binlog"
 //!
+//! *   `srv` – enables DNS SRV lookup of hosts for `mysql+srv://` connection URLs
+//!     (see [`Opts::srv_name`]).
+//!
+//! *   `zeroize` – zeroes the memory of passwords stored in connection options
+//!     (see [`Opts::pass`]) on drop.
+//!
+//! *   `socks5` – enables TCP connections through a SOCKS5 proxy (see [`Opts::proxy`]).
+//!
+//! *   `serde` – implements `Serialize` and `Deserialize` for `Opts` (as a connection URL)
+//!     and `OptsBuilder` (as a flat structure). See `serde_without_pass` to skip passwords.
+//!
+//!     **Example:**
+//!
+//!     ```toml
+//!     [dependencies]
+//!     mysql_async = { version = "*", features = ["serde"] }
+//!     ```
+//!
 //! ### Proxied features (see [`mysql_common`` fatures][myslqcommonfeatures])
 //!
 //! *   `derive` – enables `mysql_common/derive` feature
 //! *   `chrono` = enables `mysql_common/chrono` feature
 //! *   `time` = enables `mysql_common/time` feature
-//! *   `bigdecimal` = enables `mysql_common/bigdecimal` feature
+//! *   `bigdecimal` = enables `mysql_common/bigdecimal` feature (`FromValue` and `Into<Value>`
+//!     for `bigdecimal::BigDecimal`, that preserve arbitrary precision and scale of `DECIMAL`
+//!     values, which are sent and received as exact decimal text)
 //! *   `rust_decimal` = enables `mysql_common/rust_decimal` feature
 //! *   `frunk` = enables `mysql_common/frunk` feature
 //!
@@ -254,6 +274,11 @@
 //! **Note:** All values of a text protocol result set will be encoded as strings by the server,
 //! so `from_value` conversion may lead to additional parsing costs.
 //!
+//! **Note:** Multiple statements in a single query (e.g. `SELECT 1; SELECT 2`) are only allowed
+//! if [`OptsBuilder::multi_statements`] is enabled (it's disabled by default, so that a single
+//! injected `;` can't turn one query into many). Otherwise [`DriverError::MultiStatementsDisabled`]
+//! will be emitted.
+//!
 //! ## Binary protocol and prepared statements.
 //!
 //! MySql binary protocol is implemented in the set of `exec*` methods,
@@ -393,6 +418,14 @@
 //! # }
 //! ```
 //!
+//! ## Strict mode
+//!
+//! The server is free to request any file at any time, so a malicious server is able to read
+//! any file accessible to a handler. Enable [`OptsBuilder::strict_local_infile`] to only serve
+//! requests for files named by `LOAD DATA LOCAL INFILE` statements of the last query. Other
+//! requests will be rejected with [`LocalInfileError::UnexpectedRequest`] without calling
+//! a handler.
+//!
 //! [1]: https://dev.mysql.com/doc/refman/8.0/en/load-data-local-security.html
 //!
 //! # Testing
@@ -451,6 +484,9 @@ mod local_infile_handler;
 mod opts;
 mod query;
 mod queryable;
+mod row;
+mod set;
+mod vector;
 
 type BoxFuture<'a, T> = futures_core::future::BoxFuture<'a, Result<T>>;
 
@@ -470,7 +506,37 @@ pub use self::conn::binlog_stream::{request::BinlogStreamRequest, BinlogStream};
 pub use self::conn::Conn;
 
 #[doc(inline)]
-pub use self::conn::pool::Pool;
+pub use self::conn::conn_info::ConnInfo;
+
+#[doc(inline)]
+pub use self::conn::connect_trace::{ConnectOutcome, ConnectTrace};
+
+#[doc(inline)]
+pub use self::conn::innodb_status::{DeadlockTransaction, InnodbDeadlock};
+
+#[doc(inline)]
+pub use self::conn::load_data::LoadDataResult;
+
+#[doc(inline)]
+pub use self::conn::process_list::ProcessInfo;
+
+#[doc(inline)]
+pub use self::conn::query_cache::{QueryCache, QueryCacheKey};
+
+#[doc(inline)]
+pub use self::conn::replica_status::ReplicaStatus;
+
+#[doc(inline)]
+pub use self::conn::server_edition::ServerEdition;
+
+#[doc(inline)]
+pub use self::conn::table_columns::ColumnDef;
+
+#[doc(inline)]
+pub use self::conn::table_maintenance::TableMaintenanceMessage;
+
+#[doc(inline)]
+pub use self::conn::pool::{Pool, PoolStats, WarmupResult};
 
 #[cfg(any(feature = "native-tls-tls", feature = "rustls-tls"))]
 #[doc(inline)]
@@ -491,9 +557,14 @@ pub use self::queryable::transaction::IsolationLevel;
 #[cfg(any(feature = "rustls", feature = "native-tls-tls"))]
 pub use self::opts::ClientIdentity;
 
+#[doc(inline)]
+#[cfg(feature = "serde")]
+pub use self::opts::{serde_without_pass, SerializeOpts};
+
 #[doc(inline)]
 pub use self::opts::{
-    ChangeUserOpts, Opts, OptsBuilder, PoolConstraints, PoolOpts, SslOpts,
+    ChangeUserOpts, IpPreference, Opts, OptsBuilder, PartialResultStrategy, PoolConstraints,
+    PoolMinPolicy, PoolOpts, ProxyConfig, ResetKind, SocketFallback, SslMode, SslOpts, TlsVersion,
     DEFAULT_INACTIVE_CONNECTION_TTL, DEFAULT_POOL_CONSTRAINTS, DEFAULT_STMT_CACHE_SIZE,
     DEFAULT_TTL_CHECK_INTERVAL,
 };
@@ -541,7 +612,12 @@ pub use mysql_common::value::convert::{from_value, from_value_opt, FromValueErro
 pub use mysql_common::value::json::{Deserialized, Serialized};
 
 #[doc(inline)]
-pub use self::queryable::query_result::{result_set_stream::ResultSetStream, QueryResult};
+pub use self::queryable::query_result::{
+    budget::{ResultBudgetMode, ResultMemoryBudget},
+    result_set_stream::ResultSetStream,
+    spill::{SpilledRows, SpilledRowsCursor},
+    QueryResult,
+};
 
 #[doc(inline)]
 pub use self::queryable::transaction::{Transaction, TxOpts};
@@ -552,9 +628,18 @@ pub use self::queryable::{BinaryProtocol, TextProtocol};
 #[doc(inline)]
 pub use self::queryable::stmt::Statement;
 
+#[doc(inline)]
+pub use self::set::{Set, SetMembers};
+
+#[doc(inline)]
+pub use self::vector::Vector;
+
 #[doc(inline)]
 pub use self::conn::pool::Metrics;
 
+#[doc(inline)]
+pub use self::conn::pool::events::{PoolEvent, PoolEvents, POOL_EVENTS_CAPACITY};
+
 #[doc(inline)]
 pub use crate::connection_like::{Connection, ToConnectionResult};
 
@@ -565,6 +650,8 @@ pub mod futures {
 
 /// Traits used in this crate
 pub mod prelude {
+    #[doc(inline)]
+    pub use crate::conn::factory::ConnectionFactory;
     #[doc(inline)]
     pub use crate::connection_like::ToConnection;
     #[doc(inline)]
@@ -576,6 +663,8 @@ pub mod prelude {
     #[doc(inline)]
     pub use crate::queryable::Queryable;
     #[doc(inline)]
+    pub use crate::row::RowExt;
+    #[doc(inline)]
     pub use mysql_common::prelude::ColumnIndex;
     #[doc(inline)]
     pub use mysql_common::prelude::FromRow;
@@ -635,7 +724,7 @@ pub mod test_misc {
             }
         });
 
-        let mut builder = OptsBuilder::from_opts(database_opts.clone());
+        let mut builder = OptsBuilder::from_opts(database_opts.clone()).multi_statements(true);
         if test_ssl() {
             let ssl_opts = SslOpts::default()
                 .with_danger_skip_domain_validation(true)
@@ -655,4 +744,64 @@ pub mod test_misc {
     pub fn test_ssl() -> bool {
         ["true", "1"].contains(&&*env::var("SSL").unwrap_or_default())
     }
+
+    /// Drives `fut` to completion and returns its output along with the maximum of `sample`
+    /// observed meanwhile.
+    ///
+    /// `fut` is polled continuously, so that `sample` is called often enough to observe
+    /// changes made by other tasks.
+    #[cfg(test)]
+    pub(crate) async fn max_while<T>(
+        fut: impl std::future::Future<Output = T>,
+        sample: impl Fn() -> usize,
+    ) -> (T, usize) {
+        let mut fut = std::pin::pin!(fut);
+        let mut max = 0;
+        let output = std::future::poll_fn(|cx| {
+            max = max.max(sample());
+            let poll = fut.as_mut().poll(cx);
+            max = max.max(sample());
+            if poll.is_pending() {
+                cx.waker().wake_by_ref();
+            }
+            poll
+        })
+        .await;
+        (output, max)
+    }
+
+    /// Binds a TCP listener to `addr` that never accepts and fills its backlog, so that
+    /// further connection attempts to the returned address hang.
+    ///
+    /// The listener is closed once the returned sockets are dropped.
+    #[cfg(test)]
+    pub(crate) fn unresponsive_listener(
+        addr: std::net::SocketAddr,
+    ) -> std::io::Result<(std::net::SocketAddr, Vec<socket2::Socket>)> {
+        use socket2::{Domain, Socket, Type};
+        use std::{io, time::Duration};
+
+        let listener = Socket::new(Domain::for_address(addr), Type::STREAM, None)?;
+        listener.bind(&addr.into())?;
+        listener.listen(0)?;
+        let addr = listener
+            .local_addr()?
+            .as_socket()
+            .expect("TCP listener has an address");
+
+        // SYNs are dropped once the accept queue is full
+        let mut sockets = vec![listener];
+        while sockets.len() < 1024 {
+            let client = Socket::new(Domain::for_address(addr), Type::STREAM, None)?;
+            match client.connect_timeout(&addr.into(), Duration::from_millis(100)) {
+                Ok(()) => sockets.push(client),
+                Err(err) if err.kind() == io::ErrorKind::TimedOut => return Ok((addr, sockets)),
+                Err(err) => return Err(err),
+            }
+        }
+        Err(io::Error::new(
+            io::ErrorKind::Other,
+            "listener backlog never filled up",
+        ))
+    }
 }
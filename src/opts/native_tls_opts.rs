@@ -1,15 +1,24 @@
 #![cfg(feature = "native-tls-tls")]
 
-use std::borrow::Cow;
+use std::{borrow::Cow, fmt};
 
 use native_tls::Identity;
 
-use super::PathOrBuf;
+use super::{Password, PathOrBuf};
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Clone, PartialEq, Eq, Hash)]
 pub struct ClientIdentity {
     pkcs12_archive: PathOrBuf<'static>,
-    password: Option<Cow<'static, str>>,
+    password: Option<Password>,
+}
+
+impl fmt::Debug for ClientIdentity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ClientIdentity")
+            .field("pkcs12_archive", &self.pkcs12_archive)
+            .field("password", &self.password.as_ref().map(|_| "***"))
+            .finish()
+    }
 }
 
 impl ClientIdentity {
@@ -32,7 +41,7 @@ impl ClientIdentity {
     where
         T: Into<Cow<'static, str>>,
     {
-        self.password = Some(pass.into());
+        self.password = Some(Password::from(pass.into().into_owned()));
         self
     }
 
@@ -43,7 +52,7 @@ impl ClientIdentity {
 
     /// Returns the archive password.
     pub fn password(&self) -> Option<&str> {
-        self.password.as_ref().map(AsRef::as_ref)
+        self.password.as_ref().map(Password::as_str)
     }
 
     pub(crate) async fn load(&self) -> crate::Result<Identity> {
@@ -0,0 +1,78 @@
+// Copyright (c) 2025 mysql_async contributors
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+use std::fmt;
+
+/// A password stored in connection options (e.g. [`crate::Opts::pass`]).
+///
+/// Its bytes are zeroed on drop if the `zeroize` feature is enabled, so that the password
+/// doesn't linger in freed memory. Copies made outside of this crate (e.g. the connection URL
+/// string given to [`crate::Opts::from_url`]) are out of its reach.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub(crate) struct Password(String);
+
+impl Password {
+    pub(crate) fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Zeroes the whole buffer of the password (including spare capacity) and clears it.
+    #[cfg(feature = "zeroize")]
+    fn zeroize(&mut self) {
+        zeroize::Zeroize::zeroize(&mut self.0);
+    }
+}
+
+impl From<String> for Password {
+    fn from(password: String) -> Self {
+        Self(password)
+    }
+}
+
+impl fmt::Debug for Password {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("\"***\"")
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl Drop for Password {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Password;
+
+    #[test]
+    fn should_redact_password_in_debug() {
+        let password = Password::from(String::from("secret"));
+        assert_eq!(format!("{:?}", password), r#""***""#);
+        assert_eq!(format!("{:?}", Some(password.clone())), r#"Some("***")"#);
+        assert_eq!(password.as_str(), "secret");
+    }
+
+    #[test]
+    #[cfg(feature = "zeroize")]
+    fn should_zero_password_on_drop() {
+        let mut password = Password::from(String::from("secret"));
+        let ptr = password.0.as_ptr();
+        let capacity = password.0.capacity();
+
+        // `Drop` calls this
+        password.zeroize();
+
+        assert_eq!(password.as_str(), "");
+        assert_eq!(password.0.capacity(), capacity);
+        // the buffer is still owned by `password`, so it's safe to read it
+        let buffer = unsafe { std::slice::from_raw_parts(ptr, capacity) };
+        assert!(buffer.iter().all(|&byte| byte == 0), "{:?}", buffer);
+    }
+}
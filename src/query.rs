@@ -11,6 +11,7 @@ use std::borrow::Cow;
 use futures_util::FutureExt;
 
 use crate::{
+    error::{DriverError, MAX_STATEMENT_PARAMS},
     from_row,
     prelude::{FromRow, StatementLike, ToConnection},
     tracing_utils::LevelInfo,
@@ -233,6 +234,95 @@ pub struct QueryWithParams<Q, P> {
     pub params: P,
 }
 
+impl QueryWithParams<String, Params> {
+    /// Builds a prepared multi-row insert from `base` and `rows`.
+    ///
+    /// `base` is an `INSERT` (or `REPLACE`) statement up to the `VALUES` keyword inclusive,
+    /// e.g. `INSERT INTO t (a, b) VALUES`. A group of placeholders is appended for every row
+    /// and values of rows are flattened into a single list of positional params, so that
+    /// the whole insert is executed via the binary protocol in a single round-trip:
+    ///
+    /// ```rust
+    /// # use mysql_async::test_misc::get_opts;
+    /// # #[tokio::main]
+    /// # async fn main() -> mysql_async::Result<()> {
+    /// use mysql_async::*;
+    /// use mysql_async::prelude::*;
+    ///
+    /// let mut conn = Conn::new(get_opts()).await?;
+    /// conn.query_drop("CREATE TEMPORARY TABLE tmp (id INT, name TEXT)").await?;
+    ///
+    /// let rows = vec![(1, "foo"), (2, "bar")];
+    /// let insert = QueryWithParams::insert_values("INSERT INTO tmp (id, name) VALUES", rows)?
+    ///     .expect("rows are not empty");
+    /// assert_eq!(insert.query, "INSERT INTO tmp (id, name) VALUES (?, ?), (?, ?)");
+    /// insert.ignore(&mut conn).await?;
+    /// assert_eq!(conn.affected_rows(), 2);
+    /// # conn.disconnect().await }
+    /// ```
+    ///
+    /// Returns `None` if there are no rows (there is nothing to insert). Note that the number
+    /// of params of a statement is limited, so a large number of rows should be split into
+    /// several inserts.
+    ///
+    /// # Errors
+    ///
+    /// * [`DriverError::InsertValuesArityMismatch`] if a row has a number of values
+    ///   different from the first row;
+    /// * [`DriverError::NamedParamsForPositionalQuery`] if a row is given as named params;
+    /// * [`DriverError::StmtParamsNumberExceedsLimit`] if there are too many values in total.
+    pub fn insert_values<I, R>(base: &str, rows: I) -> crate::Result<Option<Self>>
+    where
+        I: IntoIterator<Item = R>,
+        R: Into<Params>,
+    {
+        let mut query = base.trim_end().to_owned();
+        let mut params = Vec::new();
+        let mut arity = None;
+
+        for (index, row) in rows.into_iter().enumerate() {
+            let row = match row.into() {
+                Params::Empty => Vec::new(),
+                Params::Positional(row) => row,
+                Params::Named(_) => return Err(DriverError::NamedParamsForPositionalQuery.into()),
+            };
+
+            let (required, group) = arity.get_or_insert_with(|| {
+                let placeholders = vec!["?"; row.len()].join(", ");
+                (row.len(), format!("({})", placeholders))
+            });
+            if row.len() != *required {
+                return Err(DriverError::InsertValuesArityMismatch {
+                    index,
+                    required: *required,
+                    supplied: row.len(),
+                }
+                .into());
+            }
+
+            query.push_str(if index == 0 { " " } else { ", " });
+            query.push_str(group);
+            params.extend(row);
+        }
+
+        if arity.is_none() {
+            return Ok(None);
+        }
+
+        if params.len() > MAX_STATEMENT_PARAMS {
+            return Err(DriverError::StmtParamsNumberExceedsLimit {
+                supplied: params.len(),
+            }
+            .into());
+        }
+
+        Ok(Some(QueryWithParams {
+            query,
+            params: Params::Positional(params),
+        }))
+    }
+}
+
 /// Helper, that constructs [`QueryWithParams`].
 pub trait WithParams: Sized {
     fn with<P>(self, params: P) -> QueryWithParams<Self, P>;
@@ -262,10 +352,10 @@ where
         async move {
             let mut conn = conn.to_connection().resolve().await?;
 
-            let statement = conn.as_mut().get_statement(self.query).await?;
+            let statement = conn.as_mut().get_any_statement(self.query).await?;
 
             conn.as_mut()
-                .execute_statement(&statement, self.params.into())
+                .execute_any_statement(&statement, self.params.into())
                 .await?;
 
             Ok(QueryResult::new(conn))
@@ -320,10 +410,12 @@ where
         async move {
             let mut conn = conn.to_connection().resolve().await?;
 
-            let statement = conn.as_mut().get_statement(self.query).await?;
+            let statement = conn.as_mut().get_any_statement(self.query).await?;
 
             for params in self.params {
-                conn.as_mut().execute_statement(&statement, params).await?;
+                conn.as_mut()
+                    .execute_any_statement(&statement, params)
+                    .await?;
             }
 
             Ok(())
@@ -489,4 +581,59 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn should_build_insert_values() -> Result<()> {
+        const BASE: &str = "INSERT INTO tmp_insert_values (id, name) VALUES";
+
+        let rows = (1..=100).map(|id| (id, format!("name {}", id)));
+        let insert = QueryWithParams::insert_values(BASE, rows)?.unwrap();
+        assert_eq!(insert.query.matches("(?, ?)").count(), 100);
+        assert_eq!(insert.query.matches('?').count(), 200);
+        assert!(insert
+            .query
+            .starts_with("INSERT INTO tmp_insert_values (id, name) VALUES (?, ?), (?, ?)"));
+        match insert.params {
+            Params::Positional(ref params) => assert_eq!(params.len(), 200),
+            ref params => panic!("unexpected params: {:?}", params),
+        }
+
+        let mut conn = Conn::new(get_opts()).await?;
+        conn.query_drop("CREATE TEMPORARY TABLE tmp_insert_values (id INT, name TEXT)")
+            .await?;
+        insert.ignore(&mut conn).await?;
+        assert_eq!(conn.affected_rows(), 100);
+        let rows: Vec<(u32, String)> = conn
+            .query("SELECT id, name FROM tmp_insert_values ORDER BY id")
+            .await?;
+        assert_eq!(rows.len(), 100);
+        assert_eq!(rows[99], (100, "name 100".into()));
+        conn.disconnect().await?;
+
+        let empty = QueryWithParams::insert_values(BASE, Vec::<(u32, String)>::new())?;
+        assert_eq!(empty, None);
+
+        let rows = vec![
+            vec![Value::from(1), Value::from("foo")],
+            vec![Value::from(2)],
+        ];
+        let err = QueryWithParams::insert_values(BASE, rows).unwrap_err();
+        assert!(matches!(
+            err,
+            Error::Driver(DriverError::InsertValuesArityMismatch {
+                index: 1,
+                required: 2,
+                supplied: 1,
+            })
+        ));
+
+        let rows = (0..=super::MAX_STATEMENT_PARAMS).map(|x| (x as u32,));
+        let err = QueryWithParams::insert_values(BASE, rows).unwrap_err();
+        assert!(matches!(
+            err,
+            Error::Driver(DriverError::StmtParamsNumberExceedsLimit { .. })
+        ));
+
+        Ok(())
+    }
 }
@@ -15,7 +15,7 @@ use mysql_common::{
     value::ServerSide,
 };
 
-use std::{fmt, sync::Arc};
+use std::{borrow::Cow, fmt, sync::Arc};
 
 use self::{
     query_result::QueryResult,
@@ -31,13 +31,70 @@ use crate::{
     query::AsQuery,
     queryable::query_result::ResultSetMeta,
     tracing_utils::{LevelInfo, LevelTrace, TracingLevel},
-    BoxFuture, Column, Conn, Connection, Params, ResultSetStream, Row,
+    BoxFuture, Column, Conn, Connection, OkPacket, Params, ResultSetStream, Row,
 };
 
 pub mod query_result;
 pub mod stmt;
 pub mod transaction;
 
+/// `ER_PARSE_ERROR` server error code.
+const ER_PARSE_ERROR: u16 = 1064;
+
+/// Returns `true` if there is a `;` followed by another statement in the given query.
+///
+/// Semicolons within string literals, quoted identifiers and comments are ignored,
+/// as well as trailing semicolons.
+fn has_multiple_statements(query: &[u8]) -> bool {
+    let mut i = 0;
+    let mut seen_delimiter = false;
+
+    while i < query.len() {
+        match query[i] {
+            quote @ (b'\'' | b'"' | b'`') => {
+                if seen_delimiter {
+                    return true;
+                }
+                i += 1;
+                while i < query.len() {
+                    if query[i] == b'\\' && quote != b'`' {
+                        i += 1;
+                    } else if query[i] == quote {
+                        break;
+                    }
+                    i += 1;
+                }
+            }
+            b'#' => {
+                while i < query.len() && query[i] != b'\n' {
+                    i += 1;
+                }
+            }
+            b'-' if query[i..].starts_with(b"--")
+                && matches!(query.get(i + 2), None | Some(b' ' | b'\t' | b'\r' | b'\n')) =>
+            {
+                while i < query.len() && query[i] != b'\n' {
+                    i += 1;
+                }
+            }
+            b'/' if query[i..].starts_with(b"/*") => {
+                i += 2;
+                while i < query.len() && !query[i..].starts_with(b"*/") {
+                    i += 1;
+                }
+                i += 1;
+            }
+            b';' => seen_delimiter = true,
+            byte if byte.is_ascii_whitespace() => (),
+            _ if seen_delimiter => return true,
+            _ => (),
+        }
+        i += 1;
+    }
+
+    false
+}
+
 pub trait Protocol: fmt::Debug + Send + Sync + 'static {
     /// Returns `ResultSetMeta`, that corresponds to the current protocol.
     fn result_set_meta(columns: Arc<[Column]>) -> ResultSetMeta;
@@ -101,13 +158,46 @@ impl Conn {
         Ok(())
     }
 
+    /// Applies [`crate::OptsBuilder::sql_rewrite`] (if any) to the given query.
+    pub(crate) fn rewrite_sql<'a>(&self, query: &'a [u8]) -> Cow<'a, [u8]> {
+        let sql_rewrite = match self.opts().sql_rewrite() {
+            Some(sql_rewrite) => sql_rewrite,
+            None => return Cow::Borrowed(query),
+        };
+        match std::str::from_utf8(query) {
+            Ok(sql) => match sql_rewrite.call(sql) {
+                Cow::Borrowed(sql) => Cow::Borrowed(sql.as_bytes()),
+                Cow::Owned(sql) => Cow::Owned(sql.into_bytes()),
+            },
+            Err(_) => Cow::Borrowed(query),
+        }
+    }
+
     /// Low level function that performs a text query.
     pub(crate) async fn raw_query<'a, Q, L: TracingLevel>(&'a mut self, query: Q) -> Result<()>
     where
         Q: AsQuery + 'a,
     {
-        self.routine(QueryRoutine::<'_, L>::new(query.as_query().as_ref()))
+        let query = query.as_query();
+        let query = self.rewrite_sql(query.as_ref());
+        self.reconnect_if_idle_killed().await?;
+        match self
+            .routine(QueryRoutine::<'_, L>::new(query.as_ref()))
             .await
+        {
+            // Without `CLIENT_MULTI_STATEMENTS` the server will treat `a; b` as a single
+            // statement and will fail to parse it. Let's give the caller a clue.
+            Err(Error::Server(server_error))
+                if server_error.code == ER_PARSE_ERROR
+                    && !self
+                        .capabilities()
+                        .contains(CapabilityFlags::CLIENT_MULTI_STATEMENTS)
+                    && has_multiple_statements(query.as_ref()) =>
+            {
+                Err(DriverError::MultiStatementsDisabled { server_error }.into())
+            }
+            result => result,
+        }
     }
 
     /// Used for internal querying of connection settings,
@@ -168,6 +258,32 @@ pub trait Queryable: Send {
     /// Executes the given statement with given params.
     ///
     /// It'll prepare `stmt`, if necessary.
+    ///
+    /// Rows are read lazily from the returned [`QueryResult`] (see [`QueryResult::next`]),
+    /// which mutably borrows `self` until it's consumed or dropped:
+    ///
+    /// * use [`QueryResult::drop_result`] to explicitly finish the result and to observe
+    ///   errors of the remaining result sets;
+    /// * dropping a partially consumed `QueryResult` is fine – the remaining data will be
+    ///   read and discarded before the next operation on this connection (errors contained
+    ///   in the remaining result sets will be emitted by that operation).
+    ///
+    /// ```rust
+    /// # use mysql_async::test_misc::get_opts;
+    /// # #[tokio::main]
+    /// # async fn main() -> mysql_async::Result<()> {
+    /// # use mysql_async::{prelude::*, *};
+    /// let mut conn = Conn::new(get_opts()).await?;
+    ///
+    /// let mut result = conn.exec_iter("SELECT ? UNION ALL SELECT ?", (1, 2)).await?;
+    /// let row = result.next().await?.unwrap();
+    /// assert_eq!(from_row::<u8>(row), 1);
+    /// drop(result); // the second row is not consumed
+    ///
+    /// // the connection is usable right away
+    /// assert_eq!(conn.exec_first("SELECT ?", (3,)).await?, Some(3_u8));
+    /// # conn.disconnect().await }
+    /// ```
     fn exec_iter<'a: 's, 's, Q, P>(
         &'a mut self,
         stmt: Q,
@@ -265,6 +381,9 @@ pub trait Queryable: Send {
     }
 
     /// Performs the given query and drops the query result.
+    ///
+    /// [`Conn::affected_rows`], [`Conn::last_insert_id`] and [`Conn::get_warnings`]
+    /// reflect the last statement of the query afterwards.
     fn query_drop<'a, Q>(&'a mut self, query: Q) -> BoxFuture<'a, ()>
     where
         Q: AsQuery + 'a,
@@ -272,6 +391,15 @@ pub trait Queryable: Send {
         async move { self.query_iter(query).await?.drop_result().await }.boxed()
     }
 
+    /// Performs the given query, drops the query result and returns the OK packet
+    /// of its last statement (see [`QueryResult::drop_result_ok`]).
+    fn query_drop_ok<'a, Q>(&'a mut self, query: Q) -> BoxFuture<'a, Option<OkPacket<'static>>>
+    where
+        Q: AsQuery + 'a,
+    {
+        async move { self.query_iter(query).await?.drop_result_ok().await }.boxed()
+    }
+
     /// Executes the given statement for each item in the given params iterator.
     ///
     /// It'll prepare `stmt` (once), if necessary.
@@ -399,6 +527,9 @@ pub trait Queryable: Send {
     }
 
     /// Executes the given statement and drops the result.
+    ///
+    /// [`Conn::affected_rows`], [`Conn::last_insert_id`] and [`Conn::get_warnings`]
+    /// reflect the statement afterwards.
     fn exec_drop<'a: 'b, 'b, S, P>(&'a mut self, stmt: S, params: P) -> BoxFuture<'b, ()>
     where
         S: StatementLike + 'b,
@@ -407,6 +538,20 @@ pub trait Queryable: Send {
         async move { self.exec_iter(stmt, params).await?.drop_result().await }.boxed()
     }
 
+    /// Executes the given statement, drops the result and returns the OK packet
+    /// of the statement (see [`QueryResult::drop_result_ok`]).
+    fn exec_drop_ok<'a: 'b, 'b, S, P>(
+        &'a mut self,
+        stmt: S,
+        params: P,
+    ) -> BoxFuture<'b, Option<OkPacket<'static>>>
+    where
+        S: StatementLike + 'b,
+        P: Into<Params> + Send + 'b,
+    {
+        async move { self.exec_iter(stmt, params).await?.drop_result_ok().await }.boxed()
+    }
+
     /// Returns a stream over the first result set.
     ///
     /// Please see [`QueryResult::stream_and_drop`][stream_and_drop].
@@ -507,8 +652,8 @@ impl Queryable for Conn {
     {
         let params = params.into();
         async move {
-            let statement = self.get_statement(stmt).await?;
-            self.execute_statement(&statement, params).await?;
+            let statement = self.get_any_statement(stmt).await?;
+            self.execute_any_statement(&statement, params).await?;
             Ok(QueryResult::new(self))
         }
         .boxed()
@@ -522,14 +667,8 @@ impl Queryable for Conn {
         P: Into<Params> + Send,
     {
         async move {
-            let statement = self.get_statement(stmt).await?;
-            for params in params_iter {
-                self.execute_statement(&statement, params).await?;
-                QueryResult::<BinaryProtocol>::new(&mut *self)
-                    .drop_result()
-                    .await?;
-            }
-            Ok(())
+            let statement = self.get_any_statement(stmt).await?;
+            self.execute_statement_batch(&statement, params_iter).await
         }
         .boxed()
     }
@@ -637,7 +776,130 @@ impl<'c, 't: 'c> Queryable for Connection<'c, 't> {
 
 #[cfg(test)]
 mod tests {
-    use crate::{error::Result, prelude::*, test_misc::get_opts, Conn};
+    use super::has_multiple_statements;
+    use crate::{
+        consts::CapabilityFlags, error::Result, prelude::*, test_misc::get_opts, BinaryProtocol,
+        Conn, DriverError, Error, Row, TextProtocol, Value,
+    };
+
+    #[test]
+    fn should_detect_multiple_statements() {
+        assert!(!has_multiple_statements(b"SELECT 1"));
+        assert!(!has_multiple_statements(b"SELECT 1;"));
+        assert!(!has_multiple_statements(b"SELECT 1; -- comment\n  "));
+        assert!(!has_multiple_statements(b"SELECT 1; /* comment */"));
+        assert!(!has_multiple_statements(b"SELECT ';', \";\", `;`"));
+        assert!(!has_multiple_statements(b"SELECT 'it\\'s; fine'"));
+        assert!(!has_multiple_statements(b"SELECT 1 # ; SELECT 2"));
+        assert!(has_multiple_statements(b"SELECT 1; SELECT 2"));
+        assert!(has_multiple_statements(b"SELECT 1;SELECT 2;"));
+        assert!(has_multiple_statements(b"SELECT 1; 'foo'"));
+        assert!(has_multiple_statements(b"SELECT ';'; DO 1"));
+    }
+
+    #[test]
+    fn should_detect_result_set_terminator() {
+        const EOF: &[u8] = b"\xFE\x00\x00\x02\x00";
+        const OK: &[u8] = b"\xFE\x00\x00\x02\x00\x00\x00";
+        // a text row, which column is a string with 8-byte length
+        let mut row = vec![0xFE_u8, 1, 0, 0, 0, 0, 0, 0, 0];
+        row.push(b'x');
+
+        let deprecate_eof = CapabilityFlags::CLIENT_DEPRECATE_EOF;
+        let legacy = CapabilityFlags::empty();
+
+        for caps in [deprecate_eof, legacy] {
+            assert!(TextProtocol::is_last_result_set_packet(caps, EOF));
+            assert!(BinaryProtocol::is_last_result_set_packet(caps, EOF));
+            assert!(!TextProtocol::is_last_result_set_packet(caps, b"\x03foo"));
+            assert!(!BinaryProtocol::is_last_result_set_packet(
+                caps,
+                b"\x00\x00\x2A"
+            ));
+        }
+
+        // OK packet terminates result sets if `CLIENT_DEPRECATE_EOF` was negotiated.
+        assert!(TextProtocol::is_last_result_set_packet(deprecate_eof, OK));
+        // Without `CLIENT_DEPRECATE_EOF` only short packets are EOF packets.
+        assert!(!TextProtocol::is_last_result_set_packet(legacy, &row));
+    }
+
+    #[tokio::test]
+    async fn should_read_result_sets_with_and_without_eof() -> Result<()> {
+        const QUERY: &str =
+            "SELECT 1, 'foo' UNION ALL SELECT 2, 'bar'; SELECT 3 FROM DUAL WHERE FALSE; DO 4";
+
+        for deprecate_eof in [true, false] {
+            let opts = get_opts()
+                .deprecate_eof(deprecate_eof)
+                .multi_statements(true);
+            let mut conn = Conn::new(opts).await?;
+            if !deprecate_eof {
+                assert!(!conn
+                    .capabilities()
+                    .contains(CapabilityFlags::CLIENT_DEPRECATE_EOF));
+            }
+
+            // text protocol, multiple result sets, empty result set
+            let mut result = conn.query_iter(QUERY).await?;
+            assert_eq!(
+                result.collect::<(u8, String)>().await?,
+                vec![(1, "foo".into()), (2, "bar".into())]
+            );
+            assert_eq!(result.collect::<u8>().await?, Vec::<u8>::new());
+            assert_eq!(result.collect::<u8>().await?, Vec::<u8>::new());
+            assert!(result.is_empty());
+            drop(result);
+
+            // binary protocol with parameter and column definitions
+            let stmt = conn.prep("SELECT ?, ? UNION ALL SELECT 3, 'baz'").await?;
+            assert_eq!(stmt.num_params(), 2);
+            assert_eq!(stmt.num_columns(), 2);
+            let rows: Vec<(u8, String)> = conn.exec(&stmt, (1, "foo")).await?;
+            assert_eq!(rows, vec![(1, "foo".into()), (3, "baz".into())]);
+            let rows: Vec<(u8, String)> = conn
+                .exec("SELECT ?, ? FROM DUAL WHERE FALSE", (1, "foo"))
+                .await?;
+            assert!(rows.is_empty());
+
+            // status flags are taken from the terminator
+            conn.query_drop("START TRANSACTION").await?;
+            let _: Vec<u8> = conn.query("SELECT 1").await?;
+            assert!(conn
+                .status()
+                .contains(crate::consts::StatusFlags::SERVER_STATUS_IN_TRANS));
+            conn.query_drop("ROLLBACK").await?;
+
+            assert_eq!(conn.query_first("SELECT 5").await?, Some(5_u8));
+            conn.disconnect().await?;
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn should_reject_multi_statements_unless_enabled() -> Result<()> {
+        let mut conn = Conn::new(get_opts().multi_statements(false)).await?;
+        let err = conn.query_drop("DO 1; DO 2").await.unwrap_err();
+        assert!(matches!(
+            err,
+            Error::Driver(DriverError::MultiStatementsDisabled { .. })
+        ));
+        // connection is still usable
+        assert_eq!(conn.query_first("SELECT 1").await?, Some(1_u8));
+        // single statements with trailing delimiter are fine
+        assert_eq!(conn.query_first("SELECT 2;").await?, Some(2_u8));
+        conn.disconnect().await?;
+
+        let mut conn = Conn::new(get_opts().multi_statements(true)).await?;
+        let mut result = conn.query_iter("SELECT 1; SELECT 2").await?;
+        assert_eq!(result.collect::<u8>().await?, vec![1]);
+        assert_eq!(result.collect::<u8>().await?, vec![2]);
+        drop(result);
+        conn.disconnect().await?;
+
+        Ok(())
+    }
 
     #[tokio::test]
     async fn should_prep() -> Result<()> {
@@ -674,4 +936,295 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn should_prepare_many() -> Result<()> {
+        let mut conn = Conn::new(get_opts()).await?;
+        let cached = conn.prep("SELECT ?").await?;
+
+        let statements = conn
+            .prepare_many(&["SELECT ?", "SELECT :foo, :bar", "DO 1", "SELECT ?, 'x'"])
+            .await?;
+        assert_eq!(statements.len(), 4);
+        assert_eq!(statements[0].id(), cached.id());
+        assert_eq!(statements[1].num_params(), 2);
+        assert_eq!(statements[2].num_columns(), 0);
+
+        // every statement is cached
+        for stmt in &statements {
+            let inner = conn.get_cached_stmt(&stmt.inner.raw_query).unwrap();
+            assert_eq!(inner.id(), stmt.id());
+        }
+
+        // and usable
+        let row: Option<(u8, String)> = conn
+            .exec_first(&statements[1], params! { "foo" => 1, "bar" => "y" })
+            .await?;
+        assert_eq!(row, Some((1, "y".into())));
+        conn.exec_drop(&statements[2], ()).await?;
+        let row: Option<(u8, String)> = conn.exec_first(&statements[3], (2,)).await?;
+        assert_eq!(row, Some((2, "x".into())));
+
+        let result = conn
+            .prepare_many(&["SELECT 2", "SELECT * FROM mysql.no_such_table", "SELECT 3"])
+            .await;
+        match result {
+            Err(Error::Driver(DriverError::PrepareManyFailed { index: 1, error })) => {
+                assert!(matches!(*error, Error::Server(ref err) if err.code == 1146))
+            }
+            other => panic!("expected PrepareManyFailed, got {:?}", other),
+        }
+
+        // the rest of the batch is prepared and the connection is usable
+        assert!(conn.get_cached_stmt(b"SELECT 2").is_some());
+        assert!(conn.get_cached_stmt(b"SELECT 3").is_some());
+        let three: Option<u8> = conn.exec_first("SELECT 3", ()).await?;
+        assert_eq!(three, Some(3));
+        conn.disconnect().await?;
+
+        // a batch that doesn't fit into the statement cache is rejected up front
+        let mut conn = Conn::new(get_opts().stmt_cache_size(2_usize)).await?;
+        let result = conn
+            .prepare_many(&["SELECT 1", "SELECT 2", "SELECT 1", "SELECT 3"])
+            .await;
+        match result {
+            Err(Error::Driver(DriverError::PrepareManyExceedsCache {
+                statements: 3,
+                capacity: 2,
+            })) => (),
+            other => panic!("expected PrepareManyExceedsCache, got {:?}", other),
+        }
+        assert!(conn.get_cached_stmt(b"SELECT 1").is_none());
+        let statements = conn
+            .prepare_many(&["SELECT 1", "SELECT 2", "SELECT 1"])
+            .await?;
+        assert_eq!(statements[0].id(), statements[2].id());
+
+        conn.disconnect().await
+    }
+
+    #[tokio::test]
+    async fn should_prepare_many_in_a_large_batch() -> Result<()> {
+        // requests and responses of the batch don't fit into socket buffers
+        const STATEMENTS: usize = 1000;
+        const COLUMNS: usize = 50;
+
+        let mut conn = Conn::new(get_opts().stmt_cache_size(STATEMENTS)).await?;
+        let columns = (0..COLUMNS)
+            .map(|i| format!("? AS column_{}", i))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let queries = (0..STATEMENTS)
+            .map(|i| format!("SELECT {}, {} AS n", columns, i))
+            .collect::<Vec<_>>();
+        let queries = queries.iter().map(String::as_str).collect::<Vec<_>>();
+
+        let statements = conn.prepare_many(&queries).await?;
+        assert_eq!(statements.len(), STATEMENTS);
+        for stmt in &statements {
+            assert_eq!(stmt.num_params() as usize, COLUMNS);
+            assert_eq!(stmt.num_columns() as usize, COLUMNS + 1);
+        }
+
+        let params = (0..COLUMNS).map(Value::from).collect::<Vec<_>>();
+        let row: Option<Row> = conn.exec_first(&statements[STATEMENTS - 1], params).await?;
+        assert_eq!(row.unwrap().get::<usize, _>("n"), Some(STATEMENTS - 1));
+
+        conn.disconnect().await
+    }
+
+    #[cfg(feature = "bigdecimal")]
+    #[tokio::test]
+    async fn should_round_trip_bigdecimal() -> Result<()> {
+        use mysql_common::bigdecimal::BigDecimal;
+        use std::str::FromStr;
+
+        // beyond the 28 significant digits supported by `rust_decimal`
+        const VALUES: &[&str] = &[
+            "12345678901234567890123456789012345.123456789012345678901234567890",
+            "-0.000000000000000000000000000001",
+            "99999999999999999999999999999999999.999999999999999999999999999999",
+            "0.000000000000000000000000000000",
+        ];
+
+        let mut conn = Conn::new(get_opts()).await?;
+        conn.query_drop("CREATE TEMPORARY TABLE mysql.bigdecimal (d DECIMAL(65, 30))")
+            .await?;
+
+        for value in VALUES {
+            let decimal = BigDecimal::from_str(value).unwrap();
+
+            // binding sends the exact decimal text
+            conn.exec_drop(
+                "INSERT INTO mysql.bigdecimal VALUES (?)",
+                (decimal.clone(),),
+            )
+            .await?;
+            let text: Option<String> = conn
+                .exec_first("SELECT CAST(? AS CHAR)", (decimal.clone(),))
+                .await?;
+            assert_eq!(BigDecimal::from_str(&text.unwrap()).unwrap(), decimal);
+
+            let query = "SELECT d FROM mysql.bigdecimal";
+            let from_text: BigDecimal = conn.query_first(query).await?.unwrap();
+            let from_binary: BigDecimal = conn.exec_first(query, ()).await?.unwrap();
+            assert_eq!(from_text, decimal);
+            assert_eq!(from_binary, decimal);
+
+            conn.query_drop("DELETE FROM mysql.bigdecimal").await?;
+        }
+
+        conn.disconnect().await
+    }
+
+    /// `TIME` values: `-02:30:00`, `100:00:00` and the bounds of the type.
+    const TIME_VALUES: &[(&str, Value)] = &[
+        ("-02:30:00", Value::Time(true, 0, 2, 30, 0, 0)),
+        ("100:00:00", Value::Time(false, 4, 4, 0, 0, 0)),
+        ("838:59:59", Value::Time(false, 34, 22, 59, 59, 0)),
+        ("-838:59:59", Value::Time(true, 34, 22, 59, 59, 0)),
+    ];
+
+    #[tokio::test]
+    async fn should_round_trip_negative_and_multi_day_time() -> Result<()> {
+        let mut conn = Conn::new(get_opts()).await?;
+        conn.query_drop("CREATE TEMPORARY TABLE mysql.tmp_time (t TIME)")
+            .await?;
+
+        for (text, value) in TIME_VALUES {
+            // the binary protocol sends the sign and the number of days
+            conn.exec_drop("INSERT INTO mysql.tmp_time VALUES (?)", (value.clone(),))
+                .await?;
+            let stored: Option<String> = conn
+                .query_first("SELECT CAST(t AS CHAR) FROM mysql.tmp_time")
+                .await?;
+            assert_eq!(stored.as_deref(), Some(*text));
+
+            let from_binary: Value = conn
+                .exec_first("SELECT t FROM mysql.tmp_time", ())
+                .await?
+                .unwrap();
+            assert_eq!(&from_binary, value);
+
+            conn.query_drop("DELETE FROM mysql.tmp_time").await?;
+        }
+
+        conn.disconnect().await
+    }
+
+    #[cfg(feature = "chrono")]
+    #[tokio::test]
+    async fn should_round_trip_time_as_chrono_duration() -> Result<()> {
+        use mysql_common::chrono::Duration;
+
+        let expected = [
+            -Duration::minutes(150),
+            Duration::hours(100),
+            Duration::seconds(838 * 3600 + 59 * 60 + 59),
+            -Duration::seconds(838 * 3600 + 59 * 60 + 59),
+        ];
+
+        let mut conn = Conn::new(get_opts()).await?;
+        for ((text, _), expected) in TIME_VALUES.iter().zip(expected) {
+            let query = format!("SELECT CAST('{}' AS TIME)", text);
+            let from_text: Duration = conn.query_first(&query).await?.unwrap();
+            let from_binary: Duration = conn.exec_first(&*query, ()).await?.unwrap();
+            assert_eq!(from_text, expected);
+            assert_eq!(from_binary, expected);
+
+            let round_trip: Duration = conn
+                .exec_first("SELECT CAST(? AS TIME)", (expected,))
+                .await?
+                .unwrap();
+            assert_eq!(round_trip, expected);
+        }
+
+        conn.disconnect().await
+    }
+
+    #[cfg(feature = "time")]
+    #[tokio::test]
+    async fn should_round_trip_time_as_time_duration() -> Result<()> {
+        use mysql_common::time::Duration;
+
+        let expected = [
+            -Duration::minutes(150),
+            Duration::hours(100),
+            Duration::seconds(838 * 3600 + 59 * 60 + 59),
+            -Duration::seconds(838 * 3600 + 59 * 60 + 59),
+        ];
+
+        let mut conn = Conn::new(get_opts()).await?;
+        for ((text, _), expected) in TIME_VALUES.iter().zip(expected) {
+            let query = format!("SELECT CAST('{}' AS TIME)", text);
+            let from_text: Duration = conn.query_first(&query).await?.unwrap();
+            let from_binary: Duration = conn.exec_first(&*query, ()).await?.unwrap();
+            assert_eq!(from_text, expected);
+            assert_eq!(from_binary, expected);
+
+            let round_trip: Duration = conn
+                .exec_first("SELECT CAST(? AS TIME)", (expected,))
+                .await?
+                .unwrap();
+            assert_eq!(round_trip, expected);
+        }
+
+        conn.disconnect().await
+    }
+
+    #[cfg(feature = "time")]
+    #[tokio::test]
+    async fn should_round_trip_time_crate_types() -> Result<()> {
+        use mysql_common::time::{Date, Month, PrimitiveDateTime, Time};
+
+        let date = Date::from_calendar_date(2024, Month::February, 29).unwrap();
+        let time = Time::from_hms_micro(23, 59, 59, 999_999).unwrap();
+        let date_time = PrimitiveDateTime::new(date, time);
+
+        let mut conn = Conn::new(get_opts()).await?;
+
+        let from_text: Date = conn
+            .query_first("SELECT CAST('2024-02-29' AS DATE)")
+            .await?
+            .unwrap();
+        let round_trip: Date = conn
+            .exec_first("SELECT CAST(? AS DATE)", (date,))
+            .await?
+            .unwrap();
+        assert_eq!(from_text, date);
+        assert_eq!(round_trip, date);
+
+        // fractional seconds are kept up to microseconds
+        let from_text: Time = conn
+            .query_first("SELECT CAST('23:59:59.999999' AS TIME(6))")
+            .await?
+            .unwrap();
+        let round_trip: Time = conn
+            .exec_first("SELECT CAST(? AS TIME(6))", (time,))
+            .await?
+            .unwrap();
+        assert_eq!(from_text, time);
+        assert_eq!(round_trip, time);
+
+        let from_text: PrimitiveDateTime = conn
+            .query_first("SELECT CAST('2024-02-29 23:59:59.999999' AS DATETIME(6))")
+            .await?
+            .unwrap();
+        let round_trip: PrimitiveDateTime = conn
+            .exec_first("SELECT CAST(? AS DATETIME(6))", (date_time,))
+            .await?
+            .unwrap();
+        assert_eq!(from_text, date_time);
+        assert_eq!(round_trip, date_time);
+
+        // `time::Time` is a time of day, so `TIME` values out of its range don't convert
+        // (see `should_round_trip_time_as_time_duration`)
+        for (text, _) in TIME_VALUES {
+            let query = format!("SELECT CAST('{}' AS TIME)", text);
+            let row: crate::Row = conn.query_first(query).await?.unwrap();
+            assert!(matches!(row.get_opt::<Time, _>(0), Some(Err(_))));
+        }
+
+        conn.disconnect().await
+    }
 }
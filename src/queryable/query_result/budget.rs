@@ -0,0 +1,287 @@
+// Copyright (c) 2025 mysql_async contributors
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Shared memory budget of collected results (see [`ResultMemoryBudget`]).
+
+use tokio::sync::Notify;
+
+use std::{
+    fmt,
+    sync::{Arc, Mutex},
+};
+
+use crate::error::{DriverError, Result};
+
+/// Defines what happens if a result doesn't fit into a [`ResultMemoryBudget`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum ResultBudgetMode {
+    /// Reading of a result is paused until other results release enough of the budget
+    /// (backpressure). The server waits for the client in the meantime.
+    ///
+    /// Reading still fails with [`DriverError::ResultMemoryBudgetExceeded`] if the result alone
+    /// exceeds the budget, or if all the results holding the budget wait for each other.
+    #[default]
+    Block,
+    /// Reading of a result fails with [`DriverError::ResultMemoryBudgetExceeded`].
+    Error,
+}
+
+/// Memory budget of results buffered by the driver, shared by all connections
+/// that use it (see [`crate::Opts::result_memory_budget`]).
+///
+/// Rows are accounted (using their approximate in-memory size) while they are being collected
+/// by [`crate::QueryResult::collect`] and its siblings (including `Queryable::query`,
+/// `Queryable::exec` etc.). The accounted memory is released once the collected rows
+/// are given to the caller, so the budget bounds the memory used by results in flight
+/// rather than the memory used by the application. Streamed rows (see
+/// [`crate::QueryResult::next`]) are never accounted because they aren't buffered.
+///
+/// [`crate::QueryResult::collect_spilling`] keeps rows in memory only while they fit
+/// into the budget and spills the rest.
+///
+/// ```
+/// # use mysql_async::*;
+/// // 64 MiB of results in flight across all connections of the pool
+/// let budget = ResultMemoryBudget::new(64 * 1024 * 1024, ResultBudgetMode::Block);
+/// let opts = OptsBuilder::default().result_memory_budget(Some(budget.clone()));
+/// let pool = Pool::new(opts);
+/// assert_eq!(budget.used(), 0);
+/// # drop(pool);
+/// ```
+#[derive(Clone)]
+pub struct ResultMemoryBudget {
+    inner: Arc<BudgetInner>,
+}
+
+struct BudgetInner {
+    limit: usize,
+    mode: ResultBudgetMode,
+    state: Mutex<BudgetState>,
+    released: Notify,
+}
+
+#[derive(Default)]
+struct BudgetState {
+    /// Accounted bytes.
+    used: usize,
+    /// Number of leases that hold some of the budget.
+    holders: usize,
+    /// Number of leases that hold some of the budget and wait for more.
+    waiting_holders: usize,
+}
+
+impl ResultMemoryBudget {
+    /// Creates a new budget of `limit` bytes.
+    pub fn new(limit: usize, mode: ResultBudgetMode) -> Self {
+        Self {
+            inner: Arc::new(BudgetInner {
+                limit,
+                mode,
+                state: Mutex::new(BudgetState::default()),
+                released: Notify::new(),
+            }),
+        }
+    }
+
+    /// Returns the limit of this budget in bytes.
+    pub fn limit(&self) -> usize {
+        self.inner.limit
+    }
+
+    /// Returns the mode of this budget.
+    pub fn mode(&self) -> ResultBudgetMode {
+        self.inner.mode
+    }
+
+    /// Returns the number of currently accounted bytes.
+    pub fn used(&self) -> usize {
+        self.inner.state.lock().unwrap().used
+    }
+
+    /// Starts accounting of a single result.
+    pub(crate) fn lease(&self) -> BudgetLease {
+        BudgetLease {
+            budget: self.clone(),
+            bytes: 0,
+        }
+    }
+
+    fn exceeded(&self) -> crate::Error {
+        DriverError::ResultMemoryBudgetExceeded {
+            limit: self.inner.limit,
+        }
+        .into()
+    }
+}
+
+impl PartialEq for ResultMemoryBudget {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.inner, &other.inner)
+    }
+}
+
+impl Eq for ResultMemoryBudget {}
+
+impl fmt::Debug for ResultMemoryBudget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ResultMemoryBudget")
+            .field("limit", &self.inner.limit)
+            .field("mode", &self.inner.mode)
+            .field("used", &self.used())
+            .finish()
+    }
+}
+
+/// Part of a [`ResultMemoryBudget`] held by a single result. Released on drop.
+pub(crate) struct BudgetLease {
+    budget: ResultMemoryBudget,
+    bytes: usize,
+}
+
+impl BudgetLease {
+    /// Accounts `bytes` more, waiting for them if the budget is in the blocking mode.
+    pub(crate) async fn reserve(&mut self, bytes: usize) -> Result<()> {
+        let inner = self.budget.inner.clone();
+        loop {
+            // Created before the check, so that a release in between isn't missed.
+            let released = inner.released.notified();
+            {
+                let mut state = inner.state.lock().unwrap();
+                if self.bytes + bytes > inner.limit {
+                    return Err(self.budget.exceeded());
+                }
+                if state.used + bytes <= inner.limit {
+                    self.grant(&mut state, bytes);
+                    return Ok(());
+                }
+                if inner.mode == ResultBudgetMode::Error {
+                    return Err(self.budget.exceeded());
+                }
+                if self.bytes > 0 {
+                    // Waiting would be a deadlock if every other holder waits as well.
+                    if state.holders == state.waiting_holders + 1 {
+                        return Err(self.budget.exceeded());
+                    }
+                    state.waiting_holders += 1;
+                }
+            }
+
+            let _waiting = WaitingHolder {
+                budget: &self.budget,
+                holder: self.bytes > 0,
+            };
+            released.await;
+        }
+    }
+
+    /// Accounts `bytes` more if they fit into the budget, never waits.
+    pub(crate) fn try_reserve(&mut self, bytes: usize) -> bool {
+        let inner = self.budget.inner.clone();
+        let mut state = inner.state.lock().unwrap();
+        if state.used + bytes <= inner.limit {
+            self.grant(&mut state, bytes);
+            true
+        } else {
+            false
+        }
+    }
+
+    fn grant(&mut self, state: &mut BudgetState, bytes: usize) {
+        if self.bytes == 0 && bytes > 0 {
+            state.holders += 1;
+        }
+        state.used += bytes;
+        self.bytes += bytes;
+    }
+}
+
+impl Drop for BudgetLease {
+    fn drop(&mut self) {
+        if self.bytes > 0 {
+            let mut state = self.budget.inner.state.lock().unwrap();
+            state.used -= self.bytes;
+            state.holders -= 1;
+            drop(state);
+            self.budget.inner.released.notify_waiters();
+        }
+    }
+}
+
+/// Keeps [`BudgetState::waiting_holders`] up to date even if the waiting future is dropped.
+struct WaitingHolder<'a> {
+    budget: &'a ResultMemoryBudget,
+    holder: bool,
+}
+
+impl Drop for WaitingHolder<'_> {
+    fn drop(&mut self) {
+        if self.holder {
+            self.budget.inner.state.lock().unwrap().waiting_holders -= 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use super::{ResultBudgetMode, ResultMemoryBudget};
+    use crate::{DriverError, Error};
+
+    #[tokio::test]
+    async fn should_account_result_memory() {
+        let budget = ResultMemoryBudget::new(100, ResultBudgetMode::Error);
+        let mut a = budget.lease();
+        let mut b = budget.lease();
+
+        a.reserve(60).await.unwrap();
+        assert!(matches!(
+            b.reserve(50).await,
+            Err(Error::Driver(DriverError::ResultMemoryBudgetExceeded {
+                limit: 100
+            }))
+        ));
+        assert!(b.try_reserve(40));
+        assert!(!b.try_reserve(1));
+        assert_eq!(budget.used(), 100);
+
+        drop(a);
+        assert_eq!(budget.used(), 40);
+        drop(b);
+        assert_eq!(budget.used(), 0);
+    }
+
+    #[tokio::test]
+    async fn should_block_until_released() {
+        let budget = ResultMemoryBudget::new(100, ResultBudgetMode::Block);
+        let mut a = budget.lease();
+        a.reserve(60).await.unwrap();
+
+        let waiter = tokio::spawn({
+            let budget = budget.clone();
+            async move {
+                let mut b = budget.lease();
+                b.reserve(30).await?;
+                b.reserve(20).await?;
+                Ok::<_, Error>(b.bytes)
+            }
+        });
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(!waiter.is_finished());
+        assert_eq!(budget.used(), 90);
+
+        // `a` can't wait for `b`, because `b` waits for `a`.
+        assert!(a.reserve(20).await.is_err());
+        // A result that doesn't fit into the budget alone fails right away.
+        assert!(budget.lease().reserve(101).await.is_err());
+
+        drop(a);
+        assert_eq!(waiter.await.unwrap().unwrap(), 50);
+        assert_eq!(budget.used(), 0);
+    }
+}
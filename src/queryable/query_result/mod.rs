@@ -10,15 +10,20 @@ use mysql_common::row::convert::FromRowError;
 
 use std::{borrow::Cow, fmt, marker::PhantomData, result::Result as StdResult, sync::Arc};
 
+use self::spill::{row_size, SpilledRows};
 use crate::{
     conn::{routines::NextSetRoutine, PendingResult},
     connection_like::Connection,
     error::*,
     prelude::{FromRow, Protocol},
-    Column, Row,
+    queryable::Protocol as _,
+    row::tinyint1_to_bool,
+    BinaryProtocol, Column, OkPacket, PartialResultStrategy, Row, TextProtocol,
 };
 
+pub mod budget;
 pub mod result_set_stream;
+pub mod spill;
 mod tests;
 
 /// Result set metadata.
@@ -36,6 +41,19 @@ impl ResultSetMeta {
             ResultSetMeta::Text(cols) | ResultSetMeta::Binary(cols) => cols,
         }
     }
+
+    /// Decodes a row of this result set.
+    ///
+    /// Note that the protocol of a result set is defined by its metadata rather than
+    /// by the protocol of the query result (see [`crate::Opts::sql_prepared_statements`]).
+    fn read_row(&self, packet: &[u8]) -> crate::Result<Row> {
+        match self {
+            ResultSetMeta::Text(cols) => TextProtocol::read_result_set_row(packet, cols.clone()),
+            ResultSetMeta::Binary(cols) => {
+                BinaryProtocol::read_result_set_row(packet, cols.clone())
+            }
+        }
+    }
 }
 
 /// Result of a query or statement execution.
@@ -70,6 +88,10 @@ impl ResultSetMeta {
 /// ```
 pub struct QueryResult<'a, 't: 'a, P> {
     conn: Connection<'a, 't>,
+    /// Number of rows read from the current (or the last finished) result set.
+    row_count: u64,
+    /// `true` if the result set boundary was reached.
+    set_finished: bool,
     __phantom: PhantomData<P>,
 }
 
@@ -77,6 +99,8 @@ impl<'a, 't: 'a, P> fmt::Debug for QueryResult<'a, 't, P> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("QueryResult")
             .field("conn", &self.conn)
+            .field("row_count", &self.row_count)
+            .field("set_finished", &self.set_finished)
             .field("__phantom", &"PhantomData<P>")
             .finish()
     }
@@ -89,6 +113,8 @@ where
     pub fn new<T: Into<Connection<'a, 't>>>(conn: T) -> Self {
         QueryResult {
             conn: conn.into(),
+            row_count: 0,
+            set_finished: false,
             __phantom: PhantomData,
         }
     }
@@ -119,8 +145,9 @@ where
     /// Low-level function that reads a result set row.
     ///
     /// Returns `None` if there are no more rows in the current set.
-    async fn next_row(&mut self, columns: Arc<[Column]>) -> crate::Result<Option<Row>> {
+    async fn next_row(&mut self, meta: &ResultSetMeta) -> crate::Result<Option<Row>> {
         let mut row = None;
+        let columns = meta.columns();
 
         if columns.is_empty() {
             // Empty, but not yet consumed result set.
@@ -141,66 +168,167 @@ where
                 self.conn.as_mut().set_pending_result(None)?;
             } else {
                 // `packet` is a result set row.
-                row = Some(P::read_result_set_row(&packet, columns)?);
+                match meta.read_row(&packet) {
+                    Ok(decoded) => row = Some(decoded),
+                    Err(err) => {
+                        // The rest of the result set is still on the wire.
+                        self.discard_rows(meta).await;
+                        return Err(err);
+                    }
+                }
+            }
+
+            if self.conn.opts().tinyint1_is_bool() {
+                row = row.map(|row| tinyint1_to_bool(row, columns));
             }
         }
 
         Ok(row)
     }
 
+    /// Reads and discards the rest of the current result set after a row failed to decode,
+    /// so that the connection stays usable.
+    ///
+    /// Errors are ignored here, because the error that caused the discard
+    /// is more relevant to the caller.
+    async fn discard_rows(&mut self, meta: &ResultSetMeta) {
+        loop {
+            match self.conn.as_mut().read_packet().await {
+                Ok(packet) if !P::is_last_result_set_packet(self.conn.capabilities(), &packet) => {}
+                _ => break,
+            }
+        }
+        self.set_finished = true;
+        if self.conn.as_mut().set_pending_result(None).is_ok() {
+            let _ = self.next_set(meta).await;
+        }
+    }
+
     /// Low-level function that jumps to the next result set.
     ///
+    /// `meta` is the metadata of the previous result set – the next one uses the same protocol.
+    ///
     /// Returns `false` if there are no more result sets.
-    async fn next_set(&mut self) -> crate::Result<bool> {
+    async fn next_set(&mut self, meta: &ResultSetMeta) -> crate::Result<bool> {
         if self.conn.more_results_exists() {
             // More data will follow.
-            self.conn
-                .as_mut()
-                .routine(NextSetRoutine::<P>::new())
-                .await?;
+            match meta {
+                ResultSetMeta::Text(_) => {
+                    self.conn
+                        .as_mut()
+                        .routine(NextSetRoutine::<TextProtocol>::new())
+                        .await?
+                }
+                ResultSetMeta::Binary(_) => {
+                    self.conn
+                        .as_mut()
+                        .routine(NextSetRoutine::<BinaryProtocol>::new())
+                        .await?
+                }
+            }
         }
         Ok(self.conn.has_pending_result())
     }
 
     /// Low-level function that reads a next row and tries to jump
     /// to the next result set if the current one is exhausted.
-    async fn next_row_or_next_set(&mut self, meta: ResultSetMeta) -> crate::Result<Option<Row>> {
-        let columns = meta.columns().clone();
-
-        self.next_row_or_next_set2(columns).await
-    }
-
-    /// Low-level function that reads a next row and tries to jump
-    /// to the next result set if the current one is exhausted.
-    async fn next_row_or_next_set2(
-        &mut self,
-        columns: Arc<[Column]>,
-    ) -> crate::Result<Option<Row>> {
-        if let Some(row) = self.next_row(columns).await? {
+    async fn next_row_or_next_set(&mut self, meta: &ResultSetMeta) -> crate::Result<Option<Row>> {
+        if self.set_finished {
+            self.set_finished = false;
+            self.row_count = 0;
+        }
+        let max_rows = self.conn.opts().max_rows();
+        let mut row = self.next_row(meta).await?;
+        if row.is_some() && max_rows.is_some_and(|max_rows| self.row_count >= max_rows as u64) {
+            // The row limit is reached, so the rest of the result set is discarded.
+            while self.next_row(meta).await?.is_some() {}
+            self.conn.as_mut().set_truncated();
+            row = None;
+        }
+        if let Some(row) = row {
+            self.row_count += 1;
             Ok(Some(row))
         } else {
-            self.next_set().await?;
+            self.set_finished = true;
+            self.next_set(meta).await?;
             Ok(None)
         }
     }
 
     /// Skips the taken result set.
     async fn skip_taken(&mut self, meta: Arc<ResultSetMeta>) -> crate::Result<()> {
-        while (self.next_row_or_next_set((*meta).clone()).await?).is_some() {}
+        while (self.next_row_or_next_set(&meta).await?).is_some() {}
         Ok(())
     }
 
-    #[doc(hidden)]
+    /// Reads the next row of this query result.
+    ///
+    /// Returns `None` on a result set boundary. If there is another result set,
+    /// then subsequent calls will return its rows (see [`QueryResult::is_empty`]).
     pub async fn next(&mut self) -> Result<Option<Row>> {
         loop {
             match self.conn.as_mut().use_pending_result()?.cloned() {
-                Some(PendingResult::Pending(meta)) => return self.next_row_or_next_set(meta).await,
+                Some(PendingResult::Pending(meta)) => {
+                    return self.next_row_or_next_set(&meta).await
+                }
                 Some(PendingResult::Taken(meta)) => self.skip_taken(meta).await?,
                 None => return Ok(None),
             }
         }
     }
 
+    /// Returns the number of rows read from the current result set so far.
+    ///
+    /// Once the result set is exhausted (e.g. by [`QueryResult::collect`]) it returns
+    /// the total number of rows of this result set until a row of the next result set is read.
+    ///
+    /// Note that it only reflects rows actually read by the client, not the number of rows
+    /// the server would return for the query (e.g. it's not a `COUNT(*)` if the result wasn't
+    /// fully consumed). Rows read via [`QueryResult::stream`] are also counted.
+    ///
+    /// ```rust
+    /// # use mysql_async::{test_misc::get_opts, Conn};
+    /// # use mysql_async::prelude::*;
+    /// # #[tokio::main]
+    /// # async fn main() -> mysql_async::Result<()> {
+    /// let mut conn = Conn::new(get_opts()).await?;
+    /// let mut result = conn.query_iter("SELECT 1 UNION ALL SELECT 2").await?;
+    /// assert_eq!(result.row_count(), 0);
+    /// result.next().await?;
+    /// assert_eq!(result.row_count(), 1);
+    /// let rest = result.collect::<u8>().await?;
+    /// assert_eq!(rest, vec![2]);
+    /// assert_eq!(result.row_count(), 2);
+    /// # drop(result);
+    /// # conn.disconnect().await }
+    /// ```
+    pub fn row_count(&self) -> u64 {
+        self.row_count
+    }
+
+    /// Returns `true` if rows were discarded because of the client-side row limit
+    /// (see [`crate::Opts::max_rows`]).
+    ///
+    /// It's only known once the limit is reached, i.e. rows of a truncated result set are
+    /// read as usual up to the limit and then the result set boundary is reported.
+    ///
+    /// ```rust
+    /// # use mysql_async::{test_misc::get_opts, Conn};
+    /// # use mysql_async::prelude::*;
+    /// # #[tokio::main]
+    /// # async fn main() -> mysql_async::Result<()> {
+    /// let mut conn = Conn::new(get_opts().max_rows(1)).await?;
+    /// let mut result = conn.query_iter("SELECT 1 UNION ALL SELECT 2").await?;
+    /// let rows = result.collect::<u8>().await?;
+    /// assert_eq!(rows, vec![1]);
+    /// assert!(result.was_truncated());
+    /// # drop(result);
+    /// # conn.disconnect().await }
+    /// ```
+    pub fn was_truncated(&self) -> bool {
+        self.conn.was_truncated()
+    }
+
     /// Last insert id, if any.
     pub fn last_insert_id(&self) -> Option<u64> {
         self.conn.last_insert_id()
@@ -216,6 +344,16 @@ where
         self.conn.info()
     }
 
+    /// Number of rows matched by an `UPDATE` query (see [`crate::Conn::matched_rows`]).
+    pub fn matched_rows(&self) -> Option<u64> {
+        self.conn.matched_rows()
+    }
+
+    /// Number of rows changed by an `UPDATE` query (see [`crate::Conn::changed_rows`]).
+    pub fn changed_rows(&self) -> Option<u64> {
+        self.conn.changed_rows()
+    }
+
     /// Number of warnings as reported by the server, or `0`.
     pub fn warnings(&self) -> u16 {
         self.conn.get_warnings()
@@ -239,11 +377,37 @@ where
     where
         R: FromRow + Send + 'static,
     {
-        self.reduce(Vec::new(), |mut acc, row| {
-            acc.push(FromRow::from_row(row));
-            acc
-        })
-        .await
+        self.collect_with_capacity(0).await
+    }
+
+    /// Collects the current result set of this query result into a vector
+    /// with at least the specified capacity.
+    ///
+    /// It works the same way as [`QueryResult::collect`], but reserves space for `capacity`
+    /// rows up front, which avoids reallocations if the number of rows is known in advance
+    /// (e.g. from a prior `COUNT`). This is a hint rather than a limit: the vector grows
+    /// as usual if there are more rows, and all the rows are collected anyway.
+    ///
+    /// # Panic
+    ///
+    /// Same as [`QueryResult::collect`].
+    pub async fn collect_with_capacity<R>(&mut self, capacity: usize) -> Result<Vec<R>>
+    where
+        R: FromRow + Send + 'static,
+    {
+        let buffered = self.conn.opts().partial_result_strategy()
+            == PartialResultStrategy::ReturnPartial
+            || self.conn.opts().result_memory_budget().is_some();
+        if buffered {
+            let rows = self.collect_rows(capacity).await?;
+            Ok(rows.into_iter().map(FromRow::from_row).collect())
+        } else {
+            self.reduce(Vec::with_capacity(capacity), |mut acc, row| {
+                acc.push(FromRow::from_row(row));
+                acc
+            })
+            .await
+        }
     }
 
     /// Collects the current result set of this query result.
@@ -254,11 +418,88 @@ where
     where
         R: FromRow + Send + 'static,
     {
-        self.reduce(Vec::new(), |mut acc, row| {
-            acc.push(FromRow::from_row_opt(row));
-            acc
-        })
-        .await
+        let buffered = self.conn.opts().partial_result_strategy()
+            == PartialResultStrategy::ReturnPartial
+            || self.conn.opts().result_memory_budget().is_some();
+        if buffered {
+            let rows = self.collect_rows(0).await?;
+            Ok(rows.into_iter().map(FromRow::from_row_opt).collect())
+        } else {
+            self.reduce(Vec::new(), |mut acc, row| {
+                acc.push(FromRow::from_row_opt(row));
+                acc
+            })
+            .await
+        }
+    }
+
+    /// Reads the rest of the current result set without converting rows.
+    ///
+    /// Rows are accounted in the result memory budget, if any
+    /// (see [`crate::Opts::result_memory_budget`]).
+    ///
+    /// If an error interrupts the result set after some rows were read, then those rows
+    /// are returned within [`DriverError::PartialResult`]
+    /// (see [`crate::Opts::partial_result_strategy`]).
+    async fn collect_rows(&mut self, capacity: usize) -> Result<Vec<Row>> {
+        let partial =
+            self.conn.opts().partial_result_strategy() == PartialResultStrategy::ReturnPartial;
+        let mut lease = self.conn.opts().result_memory_budget().map(|x| x.lease());
+        let mut rows = Vec::with_capacity(capacity);
+        loop {
+            let next = match (self.next().await, lease.as_mut()) {
+                (Ok(Some(row)), Some(lease)) => {
+                    lease.reserve(row_size(&row)).await.map(|_| Some(row))
+                }
+                (next, _) => next,
+            };
+            match next {
+                Ok(Some(row)) => rows.push(row),
+                Ok(None) => break Ok(rows),
+                Err(error) if rows.is_empty() || !partial => break Err(error),
+                Err(error) => {
+                    break Err(DriverError::PartialResult {
+                        rows,
+                        error: Box::new(error),
+                    }
+                    .into())
+                }
+            }
+        }
+    }
+
+    /// Collects the current result set of this query result spilling rows to disk
+    /// once they occupy more than `memory_limit` bytes of memory.
+    ///
+    /// This allows to fully materialize a result set that doesn't fit into memory
+    /// (see [`SpilledRows`]). Rows are stored in a temporary file within
+    /// [`std::env::temp_dir`] as is, so they'll be read back losslessly and in order.
+    ///
+    /// It will stop on the nearest result set boundary (see `QueryResult::collect` docs).
+    pub async fn collect_spilling(&mut self, memory_limit: usize) -> Result<SpilledRows> {
+        let columns = self.columns().unwrap_or_else(|| Vec::new().into());
+        let mut rows = SpilledRows::new(columns);
+        let mut memory = 0;
+        let mut lease = self.conn.opts().result_memory_budget().map(|x| x.lease());
+
+        while let Some(row) = self.next().await? {
+            if !rows.is_spilled() {
+                let size = row_size(&row);
+                memory += size;
+                let fits = match lease {
+                    Some(ref mut lease) => memory <= memory_limit && lease.try_reserve(size),
+                    None => memory <= memory_limit,
+                };
+                if fits {
+                    rows.push_memory(row);
+                    continue;
+                }
+            }
+            rows.push_spilled(row).await?;
+        }
+
+        rows.finish().await?;
+        Ok(rows)
     }
 
     /// Collects the current result set of this query result and drops everything else.
@@ -367,7 +608,46 @@ where
     }
 
     /// Drops this query result.
+    ///
+    /// Reads and discards everything that is left, so that the connection is clean afterwards.
+    #[doc(alias = "finish")]
     pub async fn drop_result(mut self) -> Result<()> {
+        self.drain().await
+    }
+
+    /// Drops this query result and returns the last OK packet, if any.
+    ///
+    /// The packet belongs to the last result set, i.e. to the last statement of a multi-statement
+    /// query. Same values are available via [`crate::Conn::last_ok_packet`] (as well as
+    /// [`crate::Conn::affected_rows`], [`crate::Conn::last_insert_id`] etc.) right after
+    /// [`QueryResult::drop_result`].
+    pub async fn drop_result_ok(mut self) -> Result<Option<OkPacket<'static>>> {
+        self.drain().await?;
+        Ok(self.conn.last_ok_packet().cloned())
+    }
+
+    /// Abandons this query result and gives back the underlying connection.
+    ///
+    /// Use this if you've found what you need and don't want to consume the rest of the rows.
+    /// The connection returned is clean and could be queried right away.
+    ///
+    /// # Cost
+    ///
+    /// MySQL protocol has no way to cancel a result set that is already being sent,
+    /// so the remaining rows (and result sets) are still read off the wire and discarded.
+    /// Rows are not decoded into Rust values though. For really large results it might be
+    /// cheaper to `KILL QUERY` from another connection or to simply drop the connection.
+    ///
+    /// # Errors
+    ///
+    /// Any error contained in the remaining result sets will be emitted here.
+    pub async fn abort(mut self) -> Result<Connection<'a, 't>> {
+        self.drain().await?;
+        Ok(self.conn)
+    }
+
+    /// Reads and discards everything that is left in this query result.
+    async fn drain(&mut self) -> Result<()> {
         loop {
             while self.next().await?.is_some() {}
             if !self.conn.has_pending_result() {
@@ -23,6 +23,8 @@ use crate::{
     QueryResult, Row,
 };
 
+use super::ResultSetMeta;
+
 enum CowMut<'r, 'a: 'r, 't: 'a, P> {
     Borrowed(&'r mut QueryResult<'a, 't, P>),
     Owned(QueryResult<'a, 't, P>),
@@ -70,7 +72,7 @@ impl<'r, 'a: 'r, 't: 'a, P> fmt::Debug for ResultSetStreamState<'r, 'a, 't, P> {
 pub struct ResultSetStream<'r, 'a: 'r, 't: 'a, T, P> {
     query_result: Option<ResultSetStreamState<'r, 'a, 't, P>>,
     ok_packet: Option<OkPacket<'static>>,
-    columns: Arc<[Column]>,
+    meta: ResultSetMeta,
     __from_row_type: PhantomData<T>,
 }
 
@@ -104,12 +106,12 @@ impl<'r, 'a: 'r, 't: 'a, T, P> ResultSetStream<'r, 'a, 't, T, P> {
 
     /// See [`QueryResult::columns_ref`].
     pub fn columns_ref(&self) -> &[Column] {
-        &self.columns[..]
+        &self.meta.columns()[..]
     }
 
     /// See [`QueryResult::columns`].
     pub fn columns(&self) -> Arc<[Column]> {
-        self.columns.clone()
+        self.meta.columns().clone()
     }
 
     /// See [`Conn::info`][1].
@@ -153,11 +155,11 @@ where
     ) -> Poll<Option<Self::Item>> {
         let this = self.get_mut();
         loop {
-            let columns = this.columns.clone();
+            let meta = this.meta.clone();
             match this.query_result.take() {
                 Some(ResultSetStreamState::Idle(mut query_result)) => {
                     let fut = Box::pin(async move {
-                        let row = query_result.as_mut().next_row_or_next_set2(columns).await;
+                        let row = query_result.as_mut().next_row_or_next_set(&meta).await;
                         (row, query_result)
                     });
                     this.query_result = Some(ResultSetStreamState::NextFut(fut));
@@ -188,7 +190,7 @@ where
 {
     async fn setup_stream(
         &mut self,
-    ) -> crate::Result<Option<(Option<OkPacket<'static>>, Arc<[Column]>)>> {
+    ) -> crate::Result<Option<(Option<OkPacket<'static>>, ResultSetMeta)>> {
         match self.conn.as_mut().use_pending_result()? {
             Some(PendingResult::Taken(meta)) => {
                 let meta = (*meta).clone();
@@ -199,12 +201,12 @@ where
         }
 
         let ok_packet = self.conn.last_ok_packet().cloned();
-        let columns = match self.conn.as_mut().take_pending_result()? {
-            Some(meta) => meta.columns().clone(),
+        let meta = match self.conn.as_mut().take_pending_result()? {
+            Some(meta) => (*meta).clone(),
             None => return Ok(None),
         };
 
-        Ok(Some((ok_packet, columns)))
+        Ok(Some((ok_packet, meta)))
     }
 
     /// Returns a [`Stream`] for the current result set.
@@ -248,6 +250,49 @@ where
     /// # drop(stream); drop(result); conn.disconnect().await }
     /// ```
     ///
+    /// ## Multiple result sets
+    ///
+    /// Every call returns a stream of rows of a single result set (with its own columns),
+    /// so it's possible to process an arbitrary number of result sets (e.g. ones returned
+    /// by a stored procedure) without knowing the count in advance and without buffering them:
+    ///
+    /// ```rust
+    /// # use mysql_async::test_misc::get_opts;
+    /// # #[tokio::main]
+    /// # async fn main() -> mysql_async::Result<()> {
+    /// # use mysql_async::*;
+    /// # use mysql_async::prelude::*;
+    /// # use futures_util::TryStreamExt;
+    /// let mut conn = Conn::new(get_opts()).await?;
+    ///
+    /// let mut result = conn.query_iter("SELECT 1; SELECT 2 UNION ALL SELECT 3").await?;
+    ///
+    /// let mut rows = Vec::new();
+    /// while let Some(set) = result.stream::<u8>().await? {
+    ///     rows.push(set.try_collect::<Vec<_>>().await?);
+    /// }
+    /// assert_eq!(rows, vec![vec![1], vec![2, 3]]);
+    ///
+    /// # drop(result); conn.disconnect().await }
+    /// ```
+    ///
+    /// The stream borrows the query result mutably, so it must be dropped before the next
+    /// one is requested. Keeping a result set while requesting the next one doesn't compile:
+    ///
+    /// ```compile_fail
+    /// # use mysql_async::test_misc::get_opts;
+    /// # #[tokio::main]
+    /// # async fn main() -> mysql_async::Result<()> {
+    /// # use mysql_async::*;
+    /// # use mysql_async::prelude::*;
+    /// let mut conn = Conn::new(get_opts()).await?;
+    /// let mut result = conn.query_iter("SELECT 1; SELECT 2").await?;
+    /// let first = result.stream::<u8>().await?;
+    /// let second = result.stream::<u8>().await?;
+    /// drop(first);
+    /// # drop(second); drop(result); conn.disconnect().await }
+    /// ```
+    ///
     /// ## Errors
     ///
     /// Note, that [`QueryResult::stream`] may error if:
@@ -283,9 +328,9 @@ where
                 .setup_stream()
                 .await?
                 .map(
-                    move |(ok_packet, columns)| ResultSetStream::<'r, 'a, 't, T, P> {
+                    move |(ok_packet, meta)| ResultSetStream::<'r, 'a, 't, T, P> {
                         ok_packet,
-                        columns,
+                        meta,
                         query_result: Some(ResultSetStreamState::Idle(CowMut::Borrowed(self))),
                         __from_row_type: PhantomData,
                     },
@@ -347,9 +392,9 @@ where
             Ok(self
                 .setup_stream()
                 .await?
-                .map(|(ok_packet, columns)| ResultSetStream::<'a, 'a, 't, T, P> {
+                .map(|(ok_packet, meta)| ResultSetStream::<'a, 'a, 't, T, P> {
                     ok_packet,
-                    columns,
+                    meta,
                     query_result: Some(ResultSetStreamState::Idle(CowMut::Owned(self))),
                     __from_row_type: PhantomData,
                 }))
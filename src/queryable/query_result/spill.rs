@@ -0,0 +1,496 @@
+// Copyright (c) 2025 mysql_async contributors
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Result collector that spills rows to disk (see [`crate::QueryResult::collect_spilling`]).
+
+use bytes::{BufMut, BytesMut};
+use mysql_common::{
+    packets::Column,
+    row::{new_row, Row},
+    value::Value,
+};
+use tokio::{
+    fs::{File, OpenOptions},
+    io::{AsyncWriteExt, BufWriter},
+};
+
+use std::{fmt, io, mem, path::PathBuf, sync::Arc};
+
+/// Number of bytes read from a spill file at once.
+const READ_CHUNK_SIZE: usize = 64 * 1024;
+
+const TAG_NULL: u8 = 0;
+const TAG_BYTES: u8 = 1;
+const TAG_INT: u8 = 2;
+const TAG_UINT: u8 = 3;
+const TAG_FLOAT: u8 = 4;
+const TAG_DOUBLE: u8 = 5;
+const TAG_DATE: u8 = 6;
+const TAG_TIME: u8 = 7;
+
+/// Fully materialized result set, that may be partially stored in a temporary file.
+///
+/// Returned by [`crate::QueryResult::collect_spilling`]. Rows are kept in memory until
+/// the memory limit is exceeded, all subsequent rows are written to a temporary file
+/// that is removed when this value is dropped.
+///
+/// The file is only accessible to the current user, and on unix it's unlinked right after
+/// it's created, so it never outlives the process and isn't visible in the file system.
+///
+/// Rows could be read any number of times using [`SpilledRows::cursor`]:
+///
+/// ```rust
+/// # use mysql_async::{test_misc::get_opts, Conn};
+/// # use mysql_async::prelude::*;
+/// # #[tokio::main]
+/// # async fn main() -> mysql_async::Result<()> {
+/// let mut conn = Conn::new(get_opts()).await?;
+/// let mut result = conn.query_iter("SELECT 1 UNION ALL SELECT 2").await?;
+/// // keep at most 1 KiB of rows in memory
+/// let rows = result.collect_spilling(1024).await?;
+/// drop(result);
+///
+/// for _ in 0..2 {
+///     let mut cursor = rows.cursor().await?;
+///     let mut sum = 0;
+///     while let Some(row) = cursor.next().await? {
+///         sum += mysql_async::from_row::<u8>(row);
+///     }
+///     assert_eq!(sum, 3);
+/// }
+/// # conn.disconnect().await }
+/// ```
+pub struct SpilledRows {
+    columns: Arc<[Column]>,
+    memory: Vec<Row>,
+    spill: Option<SpillFile>,
+    len: usize,
+}
+
+impl SpilledRows {
+    pub(crate) fn new(columns: Arc<[Column]>) -> Self {
+        Self {
+            columns,
+            memory: Vec::new(),
+            spill: None,
+            len: 0,
+        }
+    }
+
+    /// Returns the columns of this result set.
+    pub fn columns(&self) -> Arc<[Column]> {
+        self.columns.clone()
+    }
+
+    /// Returns the total number of rows.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if there are no rows.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns `true` if some rows were spilled to disk.
+    pub fn is_spilled(&self) -> bool {
+        self.spill.is_some()
+    }
+
+    /// Returns a new cursor positioned at the first row.
+    pub async fn cursor(&self) -> crate::Result<SpilledRowsCursor<'_>> {
+        let reader = self.spill.as_ref().map(|spill| SpillReader {
+            file: spill.file.clone(),
+            offset: 0,
+            buf: Vec::new(),
+            pos: 0,
+        });
+
+        Ok(SpilledRowsCursor {
+            rows: self,
+            memory_pos: 0,
+            reader,
+        })
+    }
+
+    /// Adds a row keeping it in memory.
+    pub(crate) fn push_memory(&mut self, row: Row) {
+        self.memory.push(row);
+        self.len += 1;
+    }
+
+    /// Adds a row writing it to the temporary file (the file is created if necessary).
+    pub(crate) async fn push_spilled(&mut self, row: Row) -> io::Result<()> {
+        if self.spill.is_none() {
+            self.spill = Some(SpillFile::create().await?);
+        }
+        let spill = self.spill.as_mut().expect("created above");
+
+        spill.buf.clear();
+        encode_row(&mut spill.buf, row.unwrap());
+        spill.writer.write_all(&spill.buf).await?;
+        self.len += 1;
+        Ok(())
+    }
+
+    /// Flushes spilled rows, so that they could be read back.
+    pub(crate) async fn finish(&mut self) -> io::Result<()> {
+        match self.spill {
+            Some(ref mut spill) => spill.writer.flush().await,
+            None => Ok(()),
+        }
+    }
+}
+
+impl fmt::Debug for SpilledRows {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SpilledRows")
+            .field("columns", &self.columns)
+            .field("in_memory", &self.memory.len())
+            .field(
+                "spill_path",
+                &self.spill.as_ref().and_then(|x| x.path.as_ref()),
+            )
+            .field("len", &self.len)
+            .finish()
+    }
+}
+
+/// Cursor over rows of a [`SpilledRows`].
+#[derive(Debug)]
+pub struct SpilledRowsCursor<'r> {
+    rows: &'r SpilledRows,
+    memory_pos: usize,
+    reader: Option<SpillReader>,
+}
+
+impl SpilledRowsCursor<'_> {
+    /// Returns the next row, or `None` if there are no more rows.
+    pub async fn next(&mut self) -> crate::Result<Option<Row>> {
+        if let Some(row) = self.rows.memory.get(self.memory_pos) {
+            self.memory_pos += 1;
+            return Ok(Some(row.clone()));
+        }
+
+        match self.reader {
+            Some(ref mut reader) => match decode_row(reader).await? {
+                Some(values) => Ok(Some(new_row(values, self.rows.columns.clone()))),
+                None => {
+                    self.reader = None;
+                    Ok(None)
+                }
+            },
+            None => Ok(None),
+        }
+    }
+}
+
+/// Temporary file that is removed on drop.
+struct SpillFile {
+    /// Handle used to read the file back (see [`SpillReader`]).
+    file: Arc<std::fs::File>,
+    writer: BufWriter<File>,
+    buf: BytesMut,
+    /// Path of the file, if it couldn't be unlinked while open.
+    path: Option<PathBuf>,
+}
+
+impl SpillFile {
+    async fn create() -> io::Result<Self> {
+        let dir = std::env::temp_dir();
+        loop {
+            let path = dir.join(format!(
+                "mysql_async_spill_{}_{:016x}",
+                std::process::id(),
+                rand::random::<u64>()
+            ));
+            let mut options = OpenOptions::new();
+            options.read(true).write(true).create_new(true);
+            #[cfg(unix)]
+            options.mode(0o600);
+            let file = match options.open(&path).await {
+                Ok(file) => file,
+                Err(err) if err.kind() == io::ErrorKind::AlreadyExists => continue,
+                Err(err) => return Err(err),
+            };
+
+            // rows are read back via the open handle, so the file doesn't need a name
+            let path = if cfg!(unix) && tokio::fs::remove_file(&path).await.is_ok() {
+                None
+            } else {
+                Some(path)
+            };
+
+            let reader = file.try_clone().await?.into_std().await;
+            return Ok(Self {
+                file: Arc::new(reader),
+                writer: BufWriter::new(file),
+                buf: BytesMut::new(),
+                path,
+            });
+        }
+    }
+}
+
+impl Drop for SpillFile {
+    fn drop(&mut self) {
+        if let Some(ref path) = self.path {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
+
+/// Reads a spill file from the beginning.
+///
+/// Positional reads are used, so that any number of readers could share the file handle.
+#[derive(Debug)]
+struct SpillReader {
+    file: Arc<std::fs::File>,
+    /// Offset of the next chunk in the file.
+    offset: u64,
+    buf: Vec<u8>,
+    /// Position of the next byte in `buf`.
+    pos: usize,
+}
+
+impl SpillReader {
+    /// Reads the next chunk of the file. Returns `false` at the end of the file.
+    async fn fill_buf(&mut self) -> io::Result<bool> {
+        let file = self.file.clone();
+        let offset = self.offset;
+        let buf = tokio::task::spawn_blocking(move || {
+            let mut buf = vec![0; READ_CHUNK_SIZE];
+            let len = read_at(&file, &mut buf, offset)?;
+            buf.truncate(len);
+            Ok::<_, io::Error>(buf)
+        })
+        .await
+        .map_err(io::Error::other)??;
+
+        self.offset += buf.len() as u64;
+        self.buf = buf;
+        self.pos = 0;
+        Ok(!self.buf.is_empty())
+    }
+
+    async fn read_exact(&mut self, out: &mut [u8]) -> io::Result<()> {
+        let mut filled = 0;
+        while filled < out.len() {
+            if self.pos == self.buf.len() && !self.fill_buf().await? {
+                return Err(io::ErrorKind::UnexpectedEof.into());
+            }
+            let len = (out.len() - filled).min(self.buf.len() - self.pos);
+            out[filled..filled + len].copy_from_slice(&self.buf[self.pos..self.pos + len]);
+            self.pos += len;
+            filled += len;
+        }
+        Ok(())
+    }
+
+    async fn read_array<const N: usize>(&mut self) -> io::Result<[u8; N]> {
+        let mut out = [0_u8; N];
+        self.read_exact(&mut out).await?;
+        Ok(out)
+    }
+}
+
+#[cfg(unix)]
+fn read_at(file: &std::fs::File, buf: &mut [u8], offset: u64) -> io::Result<usize> {
+    std::os::unix::fs::FileExt::read_at(file, buf, offset)
+}
+
+#[cfg(windows)]
+fn read_at(file: &std::fs::File, buf: &mut [u8], offset: u64) -> io::Result<usize> {
+    std::os::windows::fs::FileExt::seek_read(file, buf, offset)
+}
+
+/// Returns an approximate amount of memory occupied by the given row.
+pub(crate) fn row_size(row: &Row) -> usize {
+    let values = (0..row.len()).filter_map(|i| row.as_ref(i));
+    mem::size_of::<Row>()
+        + values
+            .map(|value| match value {
+                Value::Bytes(bytes) => mem::size_of::<Value>() + bytes.len(),
+                _ => mem::size_of::<Value>(),
+            })
+            .sum::<usize>()
+}
+
+fn encode_row(buf: &mut BytesMut, values: Vec<Value>) {
+    buf.put_u32_le(values.len() as u32);
+    for value in values {
+        match value {
+            Value::NULL => buf.put_u8(TAG_NULL),
+            Value::Bytes(bytes) => {
+                buf.put_u8(TAG_BYTES);
+                buf.put_u64_le(bytes.len() as u64);
+                buf.put_slice(&bytes);
+            }
+            Value::Int(x) => {
+                buf.put_u8(TAG_INT);
+                buf.put_i64_le(x);
+            }
+            Value::UInt(x) => {
+                buf.put_u8(TAG_UINT);
+                buf.put_u64_le(x);
+            }
+            Value::Float(x) => {
+                buf.put_u8(TAG_FLOAT);
+                buf.put_u32_le(x.to_bits());
+            }
+            Value::Double(x) => {
+                buf.put_u8(TAG_DOUBLE);
+                buf.put_u64_le(x.to_bits());
+            }
+            Value::Date(y, m, d, h, i, s, us) => {
+                buf.put_u8(TAG_DATE);
+                buf.put_u16_le(y);
+                buf.put_slice(&[m, d, h, i, s]);
+                buf.put_u32_le(us);
+            }
+            Value::Time(neg, d, h, i, s, us) => {
+                buf.put_u8(TAG_TIME);
+                buf.put_u8(neg as u8);
+                buf.put_u32_le(d);
+                buf.put_slice(&[h, i, s]);
+                buf.put_u32_le(us);
+            }
+        }
+    }
+}
+
+/// Reads a row written by `encode_row`. Returns `None` at the end of the file.
+async fn decode_row(reader: &mut SpillReader) -> io::Result<Option<Vec<Value>>> {
+    let len = match reader.read_array().await {
+        Ok(len) => u32::from_le_bytes(len) as usize,
+        Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(err) => return Err(err),
+    };
+
+    let mut values = Vec::with_capacity(len);
+    for _ in 0..len {
+        let [tag] = reader.read_array().await?;
+        let value = match tag {
+            TAG_NULL => Value::NULL,
+            TAG_BYTES => {
+                let len = u64::from_le_bytes(reader.read_array().await?) as usize;
+                let mut bytes = vec![0; len];
+                reader.read_exact(&mut bytes).await?;
+                Value::Bytes(bytes)
+            }
+            TAG_INT => Value::Int(i64::from_le_bytes(reader.read_array().await?)),
+            TAG_UINT => Value::UInt(u64::from_le_bytes(reader.read_array().await?)),
+            TAG_FLOAT => Value::Float(f32::from_le_bytes(reader.read_array().await?)),
+            TAG_DOUBLE => Value::Double(f64::from_le_bytes(reader.read_array().await?)),
+            TAG_DATE => {
+                let y = u16::from_le_bytes(reader.read_array().await?);
+                let [m, d, h, i, s] = reader.read_array().await?;
+                let us = u32::from_le_bytes(reader.read_array().await?);
+                Value::Date(y, m, d, h, i, s, us)
+            }
+            TAG_TIME => {
+                let [neg] = reader.read_array().await?;
+                let d = u32::from_le_bytes(reader.read_array().await?);
+                let [h, i, s] = reader.read_array().await?;
+                let us = u32::from_le_bytes(reader.read_array().await?);
+                Value::Time(neg != 0, d, h, i, s, us)
+            }
+            tag => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("unknown value tag in a spill file: {}", tag),
+                ))
+            }
+        };
+        values.push(value);
+    }
+
+    Ok(Some(values))
+}
+
+#[cfg(test)]
+mod test {
+    use mysql_common::{constants::ColumnType, packets::Column, row::new_row, value::Value};
+
+    use super::SpilledRows;
+
+    #[tokio::test]
+    async fn should_round_trip_spilled_values() -> crate::Result<()> {
+        let values = vec![
+            Value::NULL,
+            Value::Bytes(b"\0foo\xFF".to_vec()),
+            Value::Bytes(Vec::new()),
+            Value::Int(i64::MIN),
+            Value::UInt(u64::MAX),
+            Value::Float(f32::NAN),
+            Value::Float(-0.0),
+            Value::Double(std::f64::consts::PI),
+            Value::Date(2024, 2, 29, 23, 59, 58, 999_999),
+            Value::Time(true, 34, 22, 59, 59, 1),
+        ];
+        let columns: std::sync::Arc<[Column]> = (0..values.len())
+            .map(|_| Column::new(ColumnType::MYSQL_TYPE_VAR_STRING))
+            .collect();
+
+        let mut rows = SpilledRows::new(columns.clone());
+        rows.push_memory(new_row(values.clone(), columns.clone()));
+        rows.push_spilled(new_row(values.clone(), columns.clone()))
+            .await?;
+        rows.push_spilled(new_row(vec![Value::NULL; values.len()], columns.clone()))
+            .await?;
+        rows.finish().await?;
+        assert_eq!(rows.len(), 3);
+        assert!(rows.is_spilled());
+
+        let mut cursor = rows.cursor().await?;
+        for _ in 0..2 {
+            let read = cursor.next().await?.unwrap().unwrap();
+            assert_eq!(read.len(), values.len());
+            // `NaN != NaN`, so values are compared via `Debug`
+            assert_eq!(format!("{:?}", read), format!("{:?}", values));
+        }
+        assert_eq!(
+            cursor.next().await?.unwrap().unwrap(),
+            vec![Value::NULL; values.len()]
+        );
+        assert!(cursor.next().await?.is_none());
+
+        // cursors are independent
+        let mut first = rows.cursor().await?;
+        let mut second = rows.cursor().await?;
+        for _ in 0..2 {
+            first.next().await?.unwrap();
+        }
+        second.next().await?.unwrap();
+        assert_eq!(
+            first.next().await?.unwrap().unwrap(),
+            vec![Value::NULL; values.len()]
+        );
+        assert_eq!(second.next().await?.unwrap().len(), values.len());
+        drop((first, second));
+
+        let spill = rows.spill.as_ref().unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+
+            // the file is private and is unlinked right away
+            let mode = spill.file.metadata()?.permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+            assert!(spill.path.is_none());
+        }
+        let path = spill.path.clone();
+        drop(cursor);
+        drop(rows);
+        if let Some(path) = path {
+            assert!(!path.exists());
+        }
+
+        Ok(())
+    }
+}
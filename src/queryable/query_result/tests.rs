@@ -272,3 +272,438 @@ async fn should_iterate_over_resultset() -> super::Result<()> {
     assert_eq!(acc.load(Ordering::SeqCst), 10);
     Ok(())
 }
+
+#[tokio::test]
+async fn should_abort_query_result() -> super::Result<()> {
+    const QUERY: &str = r"
+        SELECT REPEAT('x', 1024) FROM
+            information_schema.COLUMNS a,
+            information_schema.COLUMNS b
+        LIMIT 20000;
+        SELECT 42;
+    ";
+
+    let conn = Conn::new(get_opts()).await?;
+    let mut result = QUERY.run(conn).await?;
+
+    for _ in 0..3 {
+        let row = result.next().await?.unwrap();
+        assert_eq!(from_row::<String>(row).len(), 1024);
+    }
+
+    let mut conn = result.abort().await?;
+    assert!(!conn.has_pending_result());
+
+    let answer: Option<u8> = conn.query_first("SELECT 42").await?;
+    assert_eq!(answer, Some(42));
+    conn.query_drop("DO 1").await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_keep_connection_in_sync_after_partial_reads() -> super::Result<()> {
+    const QUERY: &str = "SELECT ? UNION ALL SELECT ? UNION ALL SELECT ?";
+
+    let mut conn = Conn::new(get_opts()).await?;
+
+    // interleave row reads of a result with an explicit finish
+    let mut result = conn.exec_iter(QUERY, (1, 2, 3)).await?;
+    assert_eq!(from_row::<u8>(result.next().await?.unwrap()), 1);
+    assert_eq!(from_row::<u8>(result.next().await?.unwrap()), 2);
+    result.drop_result().await?;
+    assert!(!conn.has_pending_result());
+
+    // drop a partially consumed result
+    for _ in 0..3 {
+        let mut result = conn.exec_iter(QUERY, (4, 5, 6)).await?;
+        assert_eq!(from_row::<u8>(result.next().await?.unwrap()), 4);
+        drop(result);
+
+        let rows: Vec<(u8, u8)> = conn.exec("SELECT ?, ?", (7, 8)).await?;
+        assert_eq!(rows, vec![(7, 8)]);
+    }
+
+    // drop an untouched result
+    drop(conn.exec_iter(QUERY, (1, 2, 3)).await?);
+
+    // read the whole thing
+    let mut result = conn.exec_iter(QUERY, (9, 10, 11)).await?;
+    let mut values = Vec::new();
+    while let Some(row) = result.next().await? {
+        values.push(from_row::<u8>(row));
+    }
+    assert!(result.is_empty());
+    drop(result);
+    assert_eq!(values, vec![9, 10, 11]);
+
+    // text protocol shares the same lifecycle
+    let mut result = conn.query_iter("SELECT 1 UNION ALL SELECT 2").await?;
+    assert_eq!(from_row::<u8>(result.next().await?.unwrap()), 1);
+    drop(result);
+    assert_eq!(conn.query_first("SELECT 3").await?, Some(3_u8));
+
+    conn.disconnect().await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_collect_spilling_to_disk() -> super::Result<()> {
+    const ROWS: usize = 1000;
+
+    let mut conn = Conn::new(get_opts()).await?;
+    let query = (1..=ROWS)
+        .map(|i| {
+            format!(
+                "SELECT {} AS id, REPEAT('x', 100), NULL, -1.5, TIMESTAMP('2024-01-02 03:04:05.6')",
+                i
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(" UNION ALL ");
+
+    for protocol_is_binary in [false, true] {
+        let expected: Vec<Row> = if protocol_is_binary {
+            conn.exec(query.as_str(), ()).await?
+        } else {
+            conn.query(query.as_str()).await?
+        };
+
+        let mut result = if protocol_is_binary {
+            conn.exec_iter(query.as_str(), ()).await?
+        } else {
+            conn.query_iter(query.as_str()).await?
+        };
+        // small threshold forces most of the rows to go to disk
+        let rows = result.collect_spilling(4096).await?;
+        assert!(result.is_empty());
+        drop(result);
+
+        assert!(rows.is_spilled());
+        assert_eq!(rows.len(), ROWS);
+        assert_eq!(rows.columns()[0].name_str(), "id");
+
+        // the cursor is re-readable
+        for _ in 0..2 {
+            let mut cursor = rows.cursor().await?;
+            let mut actual = Vec::new();
+            while let Some(row) = cursor.next().await? {
+                actual.push(row);
+            }
+            assert_eq!(actual.len(), ROWS);
+            for (i, (actual, expected)) in actual.into_iter().zip(&expected).enumerate() {
+                assert_eq!(actual.get::<usize, _>(0), Some(i + 1));
+                assert_eq!(actual.unwrap(), expected.clone().unwrap());
+            }
+        }
+    }
+
+    // memory limit that isn't exceeded
+    let mut result = conn.query_iter("SELECT 1 UNION ALL SELECT 2").await?;
+    let rows = result.collect_spilling(1024 * 1024).await?;
+    drop(result);
+    assert!(!rows.is_spilled());
+    assert_eq!(rows.len(), 2);
+
+    conn.disconnect().await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_collect_with_capacity() -> super::Result<()> {
+    const ROWS: usize = 10_000;
+
+    let mut conn = Conn::new(get_opts()).await?;
+    let digits = (0..10)
+        .map(|i| format!("SELECT {} AS d", i))
+        .collect::<Vec<_>>()
+        .join(" UNION ALL ");
+    let query = format!(
+        "SELECT a.d + b.d * 10 + c.d * 100 + e.d * 1000 + 1 AS n \
+            FROM ({0}) a, ({0}) b, ({0}) c, ({0}) e ORDER BY n",
+        digits
+    );
+
+    let expected: Vec<u64> = conn.query(query.as_str()).await?;
+    assert_eq!(expected, (1..=ROWS as u64).collect::<Vec<_>>());
+
+    for capacity in [ROWS, ROWS / 2, 0] {
+        let mut result = conn.query_iter(query.as_str()).await?;
+        let rows = result.collect_with_capacity::<u64>(capacity).await?;
+        assert!(result.is_empty());
+        drop(result);
+
+        // capacity is a hint, not a limit
+        assert_eq!(rows, expected);
+        assert!(rows.capacity() >= capacity);
+    }
+
+    let mut result = conn.exec_iter(query.as_str(), ()).await?;
+    let rows = result.collect_with_capacity::<u64>(ROWS).await?;
+    drop(result);
+    assert_eq!(rows, expected);
+
+    conn.disconnect().await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_count_rows_read() -> super::Result<()> {
+    const ROWS: u64 = 100;
+
+    let mut conn = Conn::new(get_opts().multi_statements(true)).await?;
+    let query = (1..=ROWS)
+        .map(|i| format!("SELECT {}", i))
+        .collect::<Vec<_>>()
+        .join(" UNION ALL ");
+
+    let mut result = conn.exec_iter(query.as_str(), ()).await?;
+    assert_eq!(result.row_count(), 0);
+    let rows = result.collect::<u64>().await?;
+    assert_eq!(rows.len() as u64, ROWS);
+    assert_eq!(result.row_count(), ROWS);
+    drop(result);
+
+    // counter resets on a result set boundary
+    let mut result = conn
+        .query_iter(format!(
+            "{}; SELECT 1 FROM DUAL WHERE FALSE; SELECT 1, 2",
+            query
+        ))
+        .await?;
+    result.for_each(drop).await?;
+    assert_eq!(result.row_count(), ROWS);
+    assert!(result.collect::<u8>().await?.is_empty());
+    assert_eq!(result.row_count(), 0);
+
+    let mut stream = result.stream::<(u8, u8)>().await?.unwrap();
+    stream.try_next().await?.unwrap();
+    drop(stream);
+    assert_eq!(result.row_count(), 1);
+
+    // rows that weren't read are not counted
+    drop(result);
+    let mut result = conn.query_iter(query.as_str()).await?;
+    result.next().await?;
+    result.next().await?;
+    assert_eq!(result.row_count(), 2);
+    drop(result);
+
+    conn.disconnect().await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_report_truncated_result() -> super::Result<()> {
+    const QUERY: &str = "SELECT 1 UNION ALL SELECT 2 UNION ALL SELECT 3";
+
+    let mut conn = Conn::new(get_opts().max_rows(2).multi_statements(true)).await?;
+
+    let mut result = conn.query_iter(QUERY).await?;
+    assert!(!result.was_truncated());
+    assert_eq!(result.collect::<u8>().await?, vec![1, 2]);
+    assert!(result.was_truncated());
+    assert!(result.is_empty());
+    drop(result);
+    assert!(conn.was_truncated());
+
+    // binary protocol and the next result set of a multi-result query
+    assert_eq!(conn.exec::<u8, _, _>(QUERY, ()).await?, vec![1, 2]);
+    assert!(conn.was_truncated());
+    let mut result = conn.query_iter(format!("{}; SELECT 4", QUERY)).await?;
+    assert_eq!(result.collect::<u8>().await?, vec![1, 2]);
+    assert_eq!(result.collect::<u8>().await?, vec![4]);
+    assert!(result.was_truncated());
+    drop(result);
+
+    // the connection is still usable and the flag is reset by the next query
+    assert_eq!(conn.query_first::<u8, _>("SELECT 5").await?, Some(5));
+    assert!(!conn.was_truncated());
+
+    conn.disconnect().await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_not_report_truncated_result_under_limit() -> super::Result<()> {
+    let mut conn = Conn::new(get_opts().max_rows(2)).await?;
+
+    let mut result = conn.query_iter("SELECT 1 UNION ALL SELECT 2").await?;
+    assert_eq!(result.collect::<u8>().await?, vec![1, 2]);
+    assert!(!result.was_truncated());
+    drop(result);
+    assert!(!conn.was_truncated());
+    conn.disconnect().await?;
+
+    let mut conn = Conn::new(get_opts()).await?;
+    let rows = conn
+        .query::<u8, _>("SELECT 1 UNION ALL SELECT 2 UNION ALL SELECT 3")
+        .await?;
+    assert_eq!(rows.len(), 3);
+    assert!(!conn.was_truncated());
+
+    conn.disconnect().await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_stream_result_sets_as_substreams() -> super::Result<()> {
+    const PROC_DEF: &str = r"CREATE PROCEDURE three_sets() BEGIN
+        SELECT 1 AS a UNION ALL SELECT 2;
+        SELECT 'foo' AS b, 'bar' AS c;
+        SELECT 3 AS d UNION ALL SELECT 4 UNION ALL SELECT 5;
+    END";
+
+    let mut conn = Conn::new(get_opts()).await?;
+    conn.query_drop("DROP PROCEDURE IF EXISTS three_sets")
+        .await?;
+    conn.query_drop(PROC_DEF).await?;
+
+    let mut result = conn.query_iter("CALL three_sets()").await?;
+    let mut columns = Vec::new();
+    let mut rows = Vec::new();
+    while let Some(set) = result.stream::<Row>().await? {
+        columns.push(
+            set.columns_ref()
+                .iter()
+                .map(|c| c.name_str().into_owned())
+                .collect::<Vec<_>>(),
+        );
+        rows.push(set.try_collect::<Vec<_>>().await?.len());
+    }
+    drop(result);
+    assert_eq!(columns, vec![vec!["a"], vec!["b", "c"], vec!["d"]]);
+    assert_eq!(rows, vec![2, 1, 3]);
+
+    // partially consumed result sets are skipped
+    let mut result = conn.query_iter("CALL three_sets()").await?;
+    let mut first = result.stream::<Row>().await?.unwrap();
+    assert_eq!(from_row::<u8>(first.try_next().await?.unwrap()), 1);
+    drop(first);
+    let _ = result.stream::<Row>().await?.unwrap();
+    let third = result.stream::<Row>().await?.unwrap();
+    let third = third.map_ok(from_row::<u8>).try_collect::<Vec<_>>().await?;
+    assert_eq!(third, vec![3, 4, 5]);
+    assert!(result.stream::<Row>().await?.is_none());
+    drop(result);
+
+    conn.query_drop("DROP PROCEDURE three_sets").await?;
+    conn.disconnect().await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_handle_partially_read_result() -> super::Result<()> {
+    use crate::{DriverError, Error, OptsBuilder, PartialResultStrategy};
+
+    const FUNC_DEF: &str = r"CREATE FUNCTION fail_after_two(x INT) RETURNS INT DETERMINISTIC
+    BEGIN
+        IF x > 2 THEN
+            SIGNAL SQLSTATE '45000' SET MESSAGE_TEXT = 'fail_after_two';
+        END IF;
+        RETURN x;
+    END";
+    const QUERY: &str = "SELECT fail_after_two(n) FROM \
+        (SELECT 1 AS n UNION ALL SELECT 2 UNION ALL SELECT 3 UNION ALL SELECT 4) t ORDER BY n";
+
+    let mut conn = Conn::new(get_opts()).await?;
+    conn.query_drop("DROP FUNCTION IF EXISTS fail_after_two")
+        .await?;
+    conn.query_drop(FUNC_DEF).await?;
+
+    // rows read before the error are discarded by default
+    match conn.query::<u8, _>(QUERY).await {
+        Err(Error::Server(err)) => assert!(err.message.contains("fail_after_two")),
+        other => panic!("unexpected result: {:?}", other),
+    }
+    // the connection is still usable
+    assert_eq!(conn.query_first::<u8, _>("SELECT 42").await?, Some(42));
+    conn.disconnect().await?;
+
+    let opts = OptsBuilder::from_opts(get_opts())
+        .partial_result_strategy(PartialResultStrategy::ReturnPartial);
+    let mut conn = Conn::new(opts).await?;
+
+    match conn.query::<u8, _>(QUERY).await {
+        Err(Error::Driver(DriverError::PartialResult { rows, error })) => {
+            let rows = rows.into_iter().map(from_row::<u8>).collect::<Vec<_>>();
+            assert_eq!(rows, vec![1, 2]);
+            assert!(matches!(*error, Error::Server(_)));
+        }
+        other => panic!("unexpected result: {:?}", other),
+    }
+    assert_eq!(conn.query_first::<u8, _>("SELECT 42").await?, Some(42));
+
+    conn.query_drop("DROP FUNCTION fail_after_two").await?;
+    conn.disconnect().await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_respect_result_memory_budget() -> super::Result<()> {
+    use crate::{test_misc::max_while, DriverError, Error, ResultBudgetMode, ResultMemoryBudget};
+
+    // ~100 KiB of rows
+    const QUERY: &str = r"
+        WITH RECURSIVE seq (n) AS (SELECT 1 UNION ALL SELECT n + 1 FROM seq WHERE n < 100)
+        SELECT n, REPEAT('x', 1000) FROM seq";
+    const LIMIT: usize = 256 * 1024;
+
+    let budget = ResultMemoryBudget::new(LIMIT, ResultBudgetMode::Block);
+    let opts = get_opts().result_memory_budget(Some(budget.clone()));
+
+    let mut tasks = Vec::new();
+    for _ in 0..6 {
+        let opts = opts.clone();
+        tasks.push(tokio::spawn(async move {
+            let mut conn = Conn::new(opts).await?;
+            let result = conn.query::<(u32, String), _>(QUERY).await;
+            // the connection is usable in either case
+            conn.query_drop("DO 1").await?;
+            conn.disconnect().await?;
+            result
+        }));
+    }
+
+    let results = async {
+        let mut results = Vec::new();
+        for task in tasks {
+            results.push(task.await.unwrap());
+        }
+        results
+    };
+    let (results, max_used) = max_while(results, || budget.used()).await;
+
+    let mut succeeded = 0;
+    for result in results {
+        match result {
+            Ok(rows) => {
+                assert_eq!(rows.len(), 100);
+                succeeded += 1;
+            }
+            Err(Error::Driver(DriverError::ResultMemoryBudgetExceeded { limit })) => {
+                assert_eq!(limit, LIMIT)
+            }
+            Err(err) => return Err(err),
+        }
+    }
+
+    assert!(succeeded > 0);
+    assert!(max_used <= LIMIT);
+    assert_eq!(budget.used(), 0);
+
+    // a result that doesn't fit into the budget fails right away in the erroring mode
+    let budget = ResultMemoryBudget::new(16 * 1024, ResultBudgetMode::Error);
+    let opts = get_opts().result_memory_budget(Some(budget.clone()));
+    let mut conn = Conn::new(opts).await?;
+    let err = conn.query::<(u32, String), _>(QUERY).await.unwrap_err();
+    assert!(matches!(
+        err,
+        Error::Driver(DriverError::ResultMemoryBudgetExceeded { .. })
+    ));
+    assert_eq!(budget.used(), 0);
+    assert_eq!(conn.query_first::<u8, _>("SELECT 1").await?, Some(1));
+    conn.disconnect().await?;
+
+    Ok(())
+}
@@ -16,10 +16,16 @@ use mysql_common::{
 use std::{borrow::Cow, sync::Arc};
 
 use crate::{
-    conn::routines::{ExecRoutine, PrepareRoutine},
-    consts::CapabilityFlags,
+    conn::{
+        local_infile::placeholder_count,
+        routines::{BulkExecRoutine, ExecRoutine, PrepareManyRoutine, PrepareRoutine},
+        stmt_cache::SqlEntry,
+    },
+    consts::{CapabilityFlags, StatusFlags},
     error::*,
-    Column, Params,
+    prelude::Queryable,
+    tracing_utils::LevelInfo,
+    Column, Params, Value,
 };
 
 use super::AsQuery;
@@ -37,6 +43,11 @@ pub trait StatementLike: Send + Sync {
     fn to_statement<'a>(self, conn: &'a mut crate::Conn) -> ToStatementResult<'a>
     where
         Self: 'a;
+
+    /// Returns a query with `?` placeholders and names of named parameters (if any).
+    ///
+    /// Used by SQL-level prepared statements (see [`crate::Opts::sql_prepared_statements`]).
+    fn to_raw_query(self) -> Result<(Vec<u8>, Vec<Vec<u8>>)>;
 }
 
 fn to_statement_move<'a, T: AsQuery + 'a>(
@@ -46,12 +57,10 @@ fn to_statement_move<'a, T: AsQuery + 'a>(
     let fut = async move {
         let query = stmt.as_query();
         let parsed = ParsedNamedParams::parse(query.as_ref())?;
-        let inner_stmt = match conn.get_cached_stmt(parsed.query()) {
+        let raw_query = conn.rewrite_sql(parsed.query());
+        let inner_stmt = match conn.get_cached_stmt(&raw_query) {
             Some(inner_stmt) => inner_stmt,
-            None => {
-                conn.prepare_statement(Cow::Borrowed(parsed.query()))
-                    .await?
-            }
+            None => conn.prepare_statement(raw_query).await?,
         };
         Ok(Statement::new(
             inner_stmt,
@@ -66,6 +75,19 @@ fn to_statement_move<'a, T: AsQuery + 'a>(
     ToStatementResult::Mediate(fut)
 }
 
+fn to_raw_query_move<T: AsQuery>(stmt: T) -> Result<(Vec<u8>, Vec<Vec<u8>>)> {
+    let query = stmt.as_query();
+    let parsed = ParsedNamedParams::parse(query.as_ref())?;
+    Ok((
+        parsed.query().to_vec(),
+        parsed
+            .params()
+            .iter()
+            .map(|x| x.as_ref().to_vec())
+            .collect::<Vec<_>>(),
+    ))
+}
+
 impl StatementLike for Cow<'_, str> {
     fn to_statement<'a>(self, conn: &'a mut crate::Conn) -> ToStatementResult<'a>
     where
@@ -73,6 +95,10 @@ impl StatementLike for Cow<'_, str> {
     {
         to_statement_move(self, conn)
     }
+
+    fn to_raw_query(self) -> Result<(Vec<u8>, Vec<Vec<u8>>)> {
+        to_raw_query_move(self)
+    }
 }
 
 impl StatementLike for &'_ str {
@@ -82,6 +108,10 @@ impl StatementLike for &'_ str {
     {
         to_statement_move(self, conn)
     }
+
+    fn to_raw_query(self) -> Result<(Vec<u8>, Vec<Vec<u8>>)> {
+        to_raw_query_move(self)
+    }
 }
 
 impl StatementLike for String {
@@ -91,6 +121,10 @@ impl StatementLike for String {
     {
         to_statement_move(self, conn)
     }
+
+    fn to_raw_query(self) -> Result<(Vec<u8>, Vec<Vec<u8>>)> {
+        to_raw_query_move(self)
+    }
 }
 
 impl StatementLike for Box<str> {
@@ -100,6 +134,10 @@ impl StatementLike for Box<str> {
     {
         to_statement_move(self, conn)
     }
+
+    fn to_raw_query(self) -> Result<(Vec<u8>, Vec<Vec<u8>>)> {
+        to_raw_query_move(self)
+    }
 }
 
 impl StatementLike for Arc<str> {
@@ -109,6 +147,10 @@ impl StatementLike for Arc<str> {
     {
         to_statement_move(self, conn)
     }
+
+    fn to_raw_query(self) -> Result<(Vec<u8>, Vec<Vec<u8>>)> {
+        to_raw_query_move(self)
+    }
 }
 
 impl StatementLike for Cow<'_, [u8]> {
@@ -118,6 +160,10 @@ impl StatementLike for Cow<'_, [u8]> {
     {
         to_statement_move(self, conn)
     }
+
+    fn to_raw_query(self) -> Result<(Vec<u8>, Vec<Vec<u8>>)> {
+        to_raw_query_move(self)
+    }
 }
 
 impl StatementLike for &'_ [u8] {
@@ -127,6 +173,10 @@ impl StatementLike for &'_ [u8] {
     {
         to_statement_move(self, conn)
     }
+
+    fn to_raw_query(self) -> Result<(Vec<u8>, Vec<Vec<u8>>)> {
+        to_raw_query_move(self)
+    }
 }
 
 impl StatementLike for Vec<u8> {
@@ -136,6 +186,10 @@ impl StatementLike for Vec<u8> {
     {
         to_statement_move(self, conn)
     }
+
+    fn to_raw_query(self) -> Result<(Vec<u8>, Vec<Vec<u8>>)> {
+        to_raw_query_move(self)
+    }
 }
 
 impl StatementLike for Box<[u8]> {
@@ -145,6 +199,10 @@ impl StatementLike for Box<[u8]> {
     {
         to_statement_move(self, conn)
     }
+
+    fn to_raw_query(self) -> Result<(Vec<u8>, Vec<Vec<u8>>)> {
+        to_raw_query_move(self)
+    }
 }
 
 impl StatementLike for Arc<[u8]> {
@@ -154,6 +212,10 @@ impl StatementLike for Arc<[u8]> {
     {
         to_statement_move(self, conn)
     }
+
+    fn to_raw_query(self) -> Result<(Vec<u8>, Vec<Vec<u8>>)> {
+        to_raw_query_move(self)
+    }
 }
 
 impl StatementLike for Statement {
@@ -163,6 +225,10 @@ impl StatementLike for Statement {
     {
         ToStatementResult::Immediate(self.clone())
     }
+
+    fn to_raw_query(self) -> Result<(Vec<u8>, Vec<Vec<u8>>)> {
+        Ok((self.inner.raw_query.to_vec(), self.named_params))
+    }
 }
 
 impl<T: StatementLike + Clone> StatementLike for &'_ T {
@@ -172,6 +238,10 @@ impl<T: StatementLike + Clone> StatementLike for &'_ T {
     {
         self.clone().to_statement(conn)
     }
+
+    fn to_raw_query(self) -> Result<(Vec<u8>, Vec<Vec<u8>>)> {
+        self.clone().to_raw_query()
+    }
 }
 
 /// Statement data.
@@ -293,6 +363,30 @@ impl Statement {
     }
 }
 
+/// Name of an SQL-level prepared statement (see [`crate::Opts::sql_prepared_statements`]).
+///
+/// Cached statements are named `<SQL_STMT_NAME>_<n>`, whereas this name is reused
+/// (and thus implicitly deallocated) by uncached ones.
+const SQL_STMT_NAME: &str = "mysql_async_stmt";
+
+/// Prefix of user variables used to pass parameters to SQL-level prepared statements.
+const SQL_STMT_PARAM_PREFIX: &str = "@mysql_async_param_";
+
+/// Statement prepared using SQL-level `PREPARE` (see [`crate::Opts::sql_prepared_statements`]).
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub(crate) struct SqlStatement {
+    inner: SqlEntry,
+    /// An empty vector in case of no named params.
+    named_params: Vec<Vec<u8>>,
+}
+
+/// Statement prepared either using the binary protocol or using SQL-level `PREPARE`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub(crate) enum AnyStatement {
+    Binary(Statement),
+    Sql(SqlStatement),
+}
+
 impl crate::Conn {
     /// Low-level helpers, that reads the given number of column packets terminated by EOF packet.
     ///
@@ -325,25 +419,287 @@ impl crate::Conn {
     where
         U: StatementLike,
     {
+        self.reconnect_if_idle_killed().await?;
         match stmt_like.to_statement(self) {
             ToStatementResult::Immediate(statement) => Ok(statement),
             ToStatementResult::Mediate(statement) => statement.await,
         }
     }
 
+    /// Helper, that retrieves `AnyStatement` from `StatementLike`.
+    ///
+    /// It'll use SQL-level `PREPARE` if [`crate::Opts::sql_prepared_statements`] is enabled.
+    pub(crate) async fn get_any_statement<U>(&mut self, stmt_like: U) -> Result<AnyStatement>
+    where
+        U: StatementLike,
+    {
+        if self.opts().sql_prepared_statements() {
+            let (raw_query, named_params) = stmt_like.to_raw_query()?;
+            let inner = match self.stmt_cache_mut().sql_by_query(&raw_query) {
+                Some(inner) => inner,
+                None => self.prepare_sql_statement(raw_query).await?,
+            };
+            Ok(AnyStatement::Sql(SqlStatement {
+                inner,
+                named_params,
+            }))
+        } else {
+            self.get_statement(stmt_like)
+                .await
+                .map(AnyStatement::Binary)
+        }
+    }
+
+    /// Helper, that executes the given statement with the given params.
+    pub(crate) async fn execute_any_statement<P>(
+        &mut self,
+        statement: &AnyStatement,
+        params: P,
+    ) -> Result<()>
+    where
+        P: Into<Params>,
+    {
+        match statement {
+            AnyStatement::Binary(statement) => self.execute_statement(statement, params).await,
+            AnyStatement::Sql(statement) => {
+                self.execute_sql_statement(statement, params.into()).await
+            }
+        }
+    }
+
+    /// Low-level helper, that prepares the given statement using SQL-level `PREPARE`.
+    ///
+    /// The statement is cached the same way as statements prepared using the binary protocol
+    /// (see [`crate::Opts::stmt_cache_size`]), and evicted statements are deallocated.
+    async fn prepare_sql_statement(&mut self, raw_query: Vec<u8>) -> Result<SqlEntry> {
+        let no_backslash_escape = self
+            .status()
+            .contains(StatusFlags::SERVER_STATUS_NO_BACKSLASH_ESCAPES);
+        let num_params = placeholder_count(&raw_query, no_backslash_escape);
+        let cached = self.opts().stmt_cache_size() > 0;
+        let entry = SqlEntry {
+            name: if cached {
+                self.stmt_cache_mut().next_sql_name(SQL_STMT_NAME)
+            } else {
+                SQL_STMT_NAME.into()
+            },
+            num_params: u16::try_from(num_params).unwrap_or(u16::MAX),
+        };
+
+        let raw_query: Arc<[u8]> = raw_query.into();
+        let query = format!(
+            "PREPARE {} FROM {}",
+            entry.name,
+            Value::Bytes(raw_query.to_vec()).as_sql(no_backslash_escape)
+        );
+        self.query_drop(query).await?;
+
+        if cached {
+            if let Some(evicted) = self.stmt_cache_mut().put_sql(raw_query, entry.clone()) {
+                self.query_drop(format!("DEALLOCATE PREPARE {}", evicted.name))
+                    .await?;
+            }
+        }
+
+        Ok(entry)
+    }
+
+    /// Low-level helper, that executes SQL-level prepared statement with the given params.
+    ///
+    /// Parameters are validated the same way as for the binary protocol
+    /// and passed via user variables.
+    async fn execute_sql_statement(
+        &mut self,
+        statement: &SqlStatement,
+        params: Params,
+    ) -> Result<()> {
+        let params = match params {
+            Params::Empty => Vec::new(),
+            Params::Positional(params) => params,
+            named @ Params::Named(_) => {
+                if statement.named_params.is_empty() {
+                    return Err(DriverError::NamedParamsForPositionalQuery.into());
+                }
+                named.into_values(Some(&statement.named_params))?
+            }
+        };
+
+        if params.len() > MAX_STATEMENT_PARAMS {
+            return Err(DriverError::StmtParamsNumberExceedsLimit {
+                supplied: params.len(),
+            }
+            .into());
+        }
+
+        if statement.inner.num_params as usize != params.len() {
+            return Err(DriverError::StmtParamsMismatch {
+                required: statement.inner.num_params,
+                supplied: params.len(),
+            }
+            .into());
+        }
+
+        let no_backslash_escape = self
+            .status()
+            .contains(StatusFlags::SERVER_STATUS_NO_BACKSLASH_ESCAPES);
+        let mut execute = format!("EXECUTE {}", statement.inner.name);
+
+        if !params.is_empty() {
+            let mut set = String::from("SET ");
+            for (i, param) in params.iter().enumerate() {
+                if i == 0 {
+                    execute.push_str(" USING ");
+                } else {
+                    set.push_str(", ");
+                    execute.push_str(", ");
+                }
+                let variable = format!("{}{}", SQL_STMT_PARAM_PREFIX, i);
+                set.push_str(&variable);
+                set.push_str(" = ");
+                set.push_str(&param.as_sql(no_backslash_escape));
+                execute.push_str(&variable);
+            }
+            self.query_drop(set).await?;
+        }
+
+        self.raw_query::<'_, _, LevelInfo>(execute).await
+    }
+
     /// Low-level helper, that prepares the given statement.
     ///
     /// `raw_query` is a query with `?` placeholders (if any).
     async fn prepare_statement(&mut self, raw_query: Cow<'_, [u8]>) -> Result<Arc<StmtInner>> {
         let inner_stmt = self.routine(PrepareRoutine::new(raw_query)).await?;
+        self.stmt_cache_mut().opened(inner_stmt.id());
 
         if let Some(old_stmt) = self.cache_stmt(&inner_stmt) {
             self.close_statement(old_stmt.id()).await?;
         }
 
+        self.enforce_max_prepared_statements().await?;
+
         Ok(inner_stmt)
     }
 
+    /// Prepares a batch of statements, pipelining `COM_STMT_PREPARE` requests.
+    ///
+    /// Requests are sent in chunks (of up to 64 requests or 16 KiB) and then responses
+    /// to a chunk are read in order, so a chunk takes about one round-trip instead
+    /// of a round-trip per statement. Chunks are bounded, so that requests in flight fit
+    /// into socket buffers and neither side blocks on write. Statements that are
+    /// already cached are not prepared again. Requests are sent one by one if compression
+    /// is enabled (pipelining isn't supported for compressed connections).
+    ///
+    /// Returned statements correspond to `queries` (in order) and are cached the same way
+    /// as statements prepared by [`Queryable::prep`]. Statements of the batch must be open
+    /// at once, so [`DriverError::PrepareManyExceedsCache`] is returned up front (nothing is
+    /// prepared) if the number of distinct queries exceeds [`crate::Opts::stmt_cache_size`]
+    /// (unless the cache is disabled) or [`crate::Opts::max_prepared_statements`].
+    /// Split larger batches.
+    ///
+    /// If a query fails to prepare, then [`DriverError::PrepareManyFailed`] is returned with
+    /// the index of the first failed query. Note that the rest of the batch is prepared anyway,
+    /// and successfully prepared statements remain cached, so the connection stays usable.
+    ///
+    /// ```
+    /// # use mysql_async::{prelude::*, test_misc::get_opts, Conn};
+    /// # #[tokio::main]
+    /// # async fn main() -> mysql_async::Result<()> {
+    /// let mut conn = Conn::new(get_opts()).await?;
+    /// let statements = conn
+    ///     .prepare_many(&["SELECT ?", "SELECT :foo + :bar", "DO 1"])
+    ///     .await?;
+    /// let sum: Option<u32> = conn
+    ///     .exec_first(&statements[1], params! { "foo" => 1, "bar" => 2 })
+    ///     .await?;
+    /// assert_eq!(sum, Some(3));
+    /// # conn.disconnect().await }
+    /// ```
+    pub async fn prepare_many(&mut self, queries: &[&str]) -> Result<Vec<Statement>> {
+        let failed = |index, error| {
+            Error::from(DriverError::PrepareManyFailed {
+                index,
+                error: Box::new(error),
+            })
+        };
+
+        let mut statements = Vec::with_capacity(queries.len());
+        let mut to_prepare: Vec<Arc<[u8]>> = Vec::new();
+        for (index, query) in queries.iter().enumerate() {
+            let (raw_query, named_params) =
+                to_raw_query_move(*query).map_err(|error| failed(index, error))?;
+            let raw_query: Arc<[u8]> = self.rewrite_sql(&raw_query).into();
+            let cached = self.get_cached_stmt(&raw_query);
+            if cached.is_none() && !to_prepare.contains(&raw_query) {
+                to_prepare.push(raw_query.clone());
+            }
+            statements.push((raw_query, named_params, cached));
+        }
+
+        let cache_size = Some(self.opts().stmt_cache_size()).filter(|size| *size > 0);
+        let max_prepared = self.opts().max_prepared_statements().map(|max| max.max(1));
+        let capacity = match (cache_size, max_prepared) {
+            (Some(x), Some(y)) => Some(x.min(y)),
+            (x, y) => x.or(y),
+        };
+        if let Some(capacity) = capacity {
+            let mut distinct = statements
+                .iter()
+                .map(|(raw_query, _, _)| raw_query)
+                .collect::<Vec<_>>();
+            distinct.sort_unstable();
+            distinct.dedup();
+            if distinct.len() > capacity {
+                return Err(DriverError::PrepareManyExceedsCache {
+                    statements: distinct.len(),
+                    capacity,
+                }
+                .into());
+            }
+        }
+
+        let pipeline = self.opts().compression().is_none();
+        let results = self
+            .routine(PrepareManyRoutine::new(to_prepare.clone(), pipeline))
+            .await?;
+
+        let mut prepared = Vec::with_capacity(results.len());
+        for (raw_query, result) in to_prepare.into_iter().zip(results) {
+            if let Ok(ref inner_stmt) = result {
+                self.stmt_cache_mut().opened(inner_stmt.id());
+                if let Some(old_stmt) = self.cache_stmt(inner_stmt) {
+                    self.close_statement(old_stmt.id()).await?;
+                }
+            }
+            prepared.push((raw_query, result));
+        }
+
+        self.enforce_max_prepared_statements().await?;
+
+        let mut output = Vec::with_capacity(statements.len());
+        for (index, (raw_query, named_params, cached)) in statements.into_iter().enumerate() {
+            let inner_stmt = match cached {
+                Some(inner_stmt) => inner_stmt,
+                None => {
+                    let position = prepared
+                        .iter()
+                        .position(|(query, _)| *query == raw_query)
+                        .expect("every uncached query is prepared");
+                    match prepared[position].1 {
+                        Ok(ref inner_stmt) => inner_stmt.clone(),
+                        Err(_) => {
+                            let (_, result) = prepared.swap_remove(position);
+                            return Err(failed(index, result.unwrap_err()));
+                        }
+                    }
+                }
+            };
+            output.push(Statement::new(inner_stmt, named_params));
+        }
+
+        Ok(output)
+    }
+
     /// Helper, that executes the given statement with the given params.
     pub(crate) async fn execute_statement<P>(
         &mut self,
@@ -353,11 +709,73 @@ impl crate::Conn {
     where
         P: Into<Params>,
     {
+        self.stmt_cache_mut().touch(statement.id());
         self.routine(ExecRoutine::new(statement, params.into()))
             .await?;
         Ok(())
     }
 
+    /// Helper, that executes the given statement once per each item of `params_iter`.
+    ///
+    /// Uses MariaDB's `COM_STMT_BULK_EXECUTE` if the server supports it, so that the whole
+    /// batch takes about one round-trip. Falls back to sequential execution otherwise,
+    /// as well as for statements that return rows and for params that can't be sent
+    /// in bulk (see [`BulkExecRoutine::new`]).
+    pub(crate) async fn execute_statement_batch<I, P>(
+        &mut self,
+        statement: &AnyStatement,
+        params_iter: I,
+    ) -> Result<()>
+    where
+        I: IntoIterator<Item = P>,
+        P: Into<Params>,
+    {
+        let statement = match statement {
+            AnyStatement::Binary(statement)
+                if self.supports_bulk_execute()
+                    && statement.num_params() > 0
+                    && statement.columns().is_empty() =>
+            {
+                statement
+            }
+            _ => {
+                for params in params_iter {
+                    self.execute_any_statement(statement, params).await?;
+                    self.drop_result().await?;
+                }
+                return Ok(());
+            }
+        };
+
+        let mut rows = Vec::new();
+        for params in params_iter {
+            rows.push(match params.into() {
+                Params::Empty => Vec::new(),
+                Params::Positional(params) => params,
+                named @ Params::Named(_) => {
+                    if statement.named_params.is_empty() {
+                        return Err(DriverError::NamedParamsForPositionalQuery.into());
+                    }
+                    named.into_values(Some(&statement.named_params))?
+                }
+            });
+        }
+
+        match BulkExecRoutine::new(statement, &rows) {
+            Some(routine) => {
+                self.stmt_cache_mut().touch(statement.id());
+                self.routine(routine).await
+            }
+            None => {
+                for params in rows {
+                    self.execute_statement(statement, params).await?;
+                    self.drop_result().await?;
+                }
+                Ok(())
+            }
+        }
+    }
+
     /// Helper, that closes statement with the given id.
     pub(crate) async fn close_statement(&mut self, id: u32) -> Result<()> {
         self.stmt_cache_mut().remove(id);
@@ -0,0 +1,450 @@
+// Copyright (c) 2025 mysql_async contributors
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+use mysql_common::{
+    constants::ColumnType,
+    packets::Column,
+    prelude::{ColumnIndex, FromValue},
+    row::{new_row, Row},
+    value::{
+        convert::{from_value_opt, FromValueError},
+        Value,
+    },
+};
+
+use std::{
+    borrow::Cow,
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
+
+use crate::{error::DriverError, Result};
+
+/// Borrowing accessors for [`Row`].
+///
+/// [`Row::get`] converts a value into an owned type, so reading a string column allocates
+/// a new `String` every time. Accessors of this trait borrow the value from the row instead,
+/// so the result is valid as long as the row itself:
+///
+/// ```rust
+/// # use mysql_async::{test_misc::get_opts, Row};
+/// # use mysql_async::prelude::*;
+/// # #[tokio::main]
+/// # async fn main() -> mysql_async::Result<()> {
+/// # let mut conn = mysql_async::Conn::new(get_opts()).await?;
+/// let rows: Vec<Row> = conn.query("SELECT 'foo' AS name, 42 AS num, NULL").await?;
+/// for row in &rows {
+///     let name = row.get_str("name").unwrap();
+///     assert!(matches!(name, std::borrow::Cow::Borrowed("foo")));
+///     assert_eq!(row.get_bytes(0), Some(&b"foo"[..]));
+///     assert_eq!(row.get_str("num").as_deref(), Some("42"));
+///     assert_eq!(row.get_str(2), None);
+/// }
+/// # conn.disconnect().await }
+/// ```
+pub trait RowExt {
+    /// Returns a reference to the raw bytes of the given column.
+    ///
+    /// Returns `None` if there is no such column, if the column was already taken
+    /// or if the value is not a byte string (e.g. it's `NULL` or it was decoded
+    /// into a number by the binary protocol).
+    fn get_bytes<I: ColumnIndex>(&self, index: I) -> Option<&[u8]>;
+
+    /// Returns the value of the given column as a string.
+    ///
+    /// The result borrows the row's buffer if the value is a valid UTF-8 byte string.
+    /// Numeric values (as returned by the binary protocol) are formatted into an owned string.
+    ///
+    /// Returns `None` if there is no such column, if the column was already taken,
+    /// if the value is `NULL`, if it's not a valid UTF-8, or if it's a temporal value
+    /// decoded by the binary protocol.
+    fn get_str<I: ColumnIndex>(&self, index: I) -> Option<Cow<'_, str>>;
+
+    /// Converts the value of the given column into `T` without panicking.
+    ///
+    /// Unlike [`Row::get`] (that panics) and [`Row::get_opt`] (that returns the value
+    /// itself as an error) a `NULL` converted into a non-optional type results in
+    /// [`DriverError::UnexpectedNull`] that names the column. Other conversion errors
+    /// are reported as [`DriverError::FromValue`]. Optional types (e.g. `Option<i64>`)
+    /// are converted as usual, so `NULL` becomes `None`.
+    ///
+    /// Returns `None` if there is no such column or if the column was already taken.
+    ///
+    /// ```rust
+    /// # use mysql_async::{test_misc::get_opts, DriverError, Error, Row};
+    /// # use mysql_async::prelude::*;
+    /// # #[tokio::main]
+    /// # async fn main() -> mysql_async::Result<()> {
+    /// # let mut conn = mysql_async::Conn::new(get_opts()).await?;
+    /// let row: Row = conn.query_first("SELECT NULL AS email").await?.unwrap();
+    /// match row.get_non_null::<String, _>("email") {
+    ///     Some(Err(Error::Driver(DriverError::UnexpectedNull { column }))) => {
+    ///         assert_eq!(column, "email");
+    ///     }
+    ///     _ => unreachable!(),
+    /// }
+    /// assert_eq!(row.get_non_null::<Option<String>, _>("email").unwrap()?, None);
+    /// # conn.disconnect().await }
+    /// ```
+    fn get_non_null<T: FromValue, I: ColumnIndex>(&self, index: I) -> Option<Result<T>>;
+
+    /// Same as [`RowExt::get_non_null`] but interprets an integer value as unsigned
+    /// regardless of the column metadata.
+    ///
+    /// The value is reinterpreted as a 64-bit two's complement integer, i.e. `-1` becomes
+    /// `u64::MAX`. This is useful for computed columns (views, expressions) whose signedness
+    /// is reported incorrectly by the server. Non-integer values are converted as is.
+    ///
+    /// ```rust
+    /// # use mysql_async::{test_misc::get_opts, Row};
+    /// # use mysql_async::prelude::*;
+    /// # #[tokio::main]
+    /// # async fn main() -> mysql_async::Result<()> {
+    /// # let mut conn = mysql_async::Conn::new(get_opts()).await?;
+    /// let row: Row = conn.query_first("SELECT CAST(18446744073709551615 AS SIGNED)").await?.unwrap();
+    /// assert_eq!(row.get_unsigned::<u64, _>(0).unwrap()?, u64::MAX);
+    /// # conn.disconnect().await }
+    /// ```
+    fn get_unsigned<T: FromValue, I: ColumnIndex>(&self, index: I) -> Option<Result<T>>;
+
+    /// Same as [`RowExt::get_non_null`] but interprets an integer value as signed
+    /// regardless of the column metadata (see [`RowExt::get_unsigned`]).
+    fn get_signed<T: FromValue, I: ColumnIndex>(&self, index: I) -> Option<Result<T>>;
+
+    /// Returns values of the row keyed by column names.
+    ///
+    /// Useful if the shape of a result isn't known at compile time (otherwise see [`FromRow`]).
+    /// Columns that were already taken are skipped.
+    ///
+    /// Duplicate column names (e.g. `id` of both sides of a join) are suffixed
+    /// in the column order: the first `id` keeps its name, the next ones become `id_2`,
+    /// `id_3` and so on (skipping names of any other column, so e.g. an actual `id_2` column
+    /// keeps its name and the second `id` becomes `id_3`).
+    ///
+    /// ```rust
+    /// # use mysql_async::{test_misc::get_opts, Row, Value};
+    /// # use mysql_async::prelude::*;
+    /// # #[tokio::main]
+    /// # async fn main() -> mysql_async::Result<()> {
+    /// # let mut conn = mysql_async::Conn::new(get_opts()).await?;
+    /// let row: Row = conn.query_first("SELECT 1 AS id, 'foo' AS name, 2 AS id").await?.unwrap();
+    /// let map = row.as_map();
+    /// assert_eq!(map["name"], Value::Bytes(b"foo".to_vec()));
+    /// assert_eq!(map["id"], Value::Bytes(b"1".to_vec()));
+    /// assert_eq!(map["id_2"], Value::Bytes(b"2".to_vec()));
+    /// # conn.disconnect().await }
+    /// ```
+    ///
+    /// [`FromRow`]: crate::prelude::FromRow
+    fn as_map(&self) -> HashMap<String, Value>;
+}
+
+impl RowExt for Row {
+    fn get_bytes<I: ColumnIndex>(&self, index: I) -> Option<&[u8]> {
+        let index = index.idx(self.columns_ref())?;
+        match self.as_ref(index)? {
+            Value::Bytes(bytes) => Some(bytes),
+            _ => None,
+        }
+    }
+
+    fn get_str<I: ColumnIndex>(&self, index: I) -> Option<Cow<'_, str>> {
+        let index = index.idx(self.columns_ref())?;
+        match self.as_ref(index)? {
+            Value::Bytes(bytes) => std::str::from_utf8(bytes).ok().map(Cow::Borrowed),
+            Value::Int(x) => Some(Cow::Owned(x.to_string())),
+            Value::UInt(x) => Some(Cow::Owned(x.to_string())),
+            Value::Float(x) => Some(Cow::Owned(x.to_string())),
+            Value::Double(x) => Some(Cow::Owned(x.to_string())),
+            Value::NULL | Value::Date(..) | Value::Time(..) => None,
+        }
+    }
+
+    fn get_non_null<T: FromValue, I: ColumnIndex>(&self, index: I) -> Option<Result<T>> {
+        let index = index.idx(self.columns_ref())?;
+        let value = self.as_ref(index)?.clone();
+        Some(convert_column(self, index, value))
+    }
+
+    fn get_unsigned<T: FromValue, I: ColumnIndex>(&self, index: I) -> Option<Result<T>> {
+        let index = index.idx(self.columns_ref())?;
+        let value = with_signedness(self.as_ref(index)?.clone(), true);
+        Some(convert_column(self, index, value))
+    }
+
+    fn get_signed<T: FromValue, I: ColumnIndex>(&self, index: I) -> Option<Result<T>> {
+        let index = index.idx(self.columns_ref())?;
+        let value = with_signedness(self.as_ref(index)?.clone(), false);
+        Some(convert_column(self, index, value))
+    }
+
+    fn as_map(&self) -> HashMap<String, Value> {
+        let columns = self.columns_ref();
+        // actual column names take precedence over generated ones
+        let mut used: HashSet<String> = columns
+            .iter()
+            .map(|column| column.name_str().into_owned())
+            .collect();
+        let mut seen = HashSet::with_capacity(columns.len());
+
+        let mut map = HashMap::with_capacity(self.len());
+        for (index, column) in columns.iter().enumerate() {
+            let name = column.name_str();
+            let key = if seen.insert(name.clone()) {
+                name.into_owned()
+            } else {
+                let mut suffix = 2;
+                let mut key = format!("{}_{}", name, suffix);
+                while used.contains(&key) {
+                    suffix += 1;
+                    key = format!("{}_{}", name, suffix);
+                }
+                used.insert(key.clone());
+                key
+            };
+
+            if let Some(value) = self.as_ref(index) {
+                map.insert(key, value.clone());
+            }
+        }
+        map
+    }
+}
+
+/// Converts a value of the `index`-th column (see [`RowExt::get_non_null`]).
+fn convert_column<T: FromValue>(row: &Row, index: usize, value: Value) -> Result<T> {
+    match from_value_opt(value) {
+        Ok(value) => Ok(value),
+        Err(FromValueError(Value::NULL)) => Err(DriverError::UnexpectedNull {
+            column: row.columns_ref()[index].name_str().into_owned(),
+        }
+        .into()),
+        Err(FromValueError(value)) => Err(DriverError::FromValue { value }.into()),
+    }
+}
+
+/// Reinterprets an integer value as unsigned (or signed) 64-bit integer.
+///
+/// Integers of the text protocol are reinterpreted only if they are out of range
+/// for the requested signedness.
+fn with_signedness(value: Value, unsigned: bool) -> Value {
+    match value {
+        Value::Int(x) if unsigned => Value::UInt(x as u64),
+        Value::UInt(x) if !unsigned => Value::Int(x as i64),
+        Value::Bytes(bytes) => {
+            let text = std::str::from_utf8(&bytes).unwrap_or_default();
+            if unsigned {
+                match text.parse::<i64>() {
+                    Ok(x) if x < 0 => Value::UInt(x as u64),
+                    _ => Value::Bytes(bytes),
+                }
+            } else {
+                match text.parse::<u64>() {
+                    Ok(x) if x > i64::MAX as u64 => Value::Int(x as i64),
+                    _ => Value::Bytes(bytes),
+                }
+            }
+        }
+        value => value,
+    }
+}
+
+/// Returns `true` if the given column is a `TINYINT(1)` column.
+fn is_tinyint1(column: &Column) -> bool {
+    column.column_type() == ColumnType::MYSQL_TYPE_TINY && column.column_length() == 1
+}
+
+/// Replaces non-zero values of `TINYINT(1)` columns with `1`
+/// (see [`crate::Opts::tinyint1_is_bool`]).
+pub(crate) fn tinyint1_to_bool(row: Row, columns: &Arc<[Column]>) -> Row {
+    if !columns.iter().any(is_tinyint1) {
+        return row;
+    }
+
+    let values = row
+        .unwrap()
+        .into_iter()
+        .zip(columns.iter())
+        .map(|(value, column)| match value {
+            Value::Int(x) if is_tinyint1(column) => Value::Int((x != 0) as i64),
+            Value::UInt(x) if is_tinyint1(column) => Value::UInt((x != 0) as u64),
+            Value::Bytes(x) if is_tinyint1(column) && x != b"0" => Value::Bytes(b"1".to_vec()),
+            value => value,
+        })
+        .collect();
+
+    new_row(values, columns.clone())
+}
+
+#[cfg(test)]
+mod test {
+    use super::RowExt;
+    use crate::{
+        from_value_opt, prelude::*, test_misc::get_opts, Conn, DriverError, Error, Row, Value,
+    };
+
+    #[tokio::test]
+    async fn should_read_tinyint1_as_bool_and_integer() -> crate::Result<()> {
+        const CREATE: &str = "CREATE TEMPORARY TABLE mysql.tmp_tinyint1 (id INT, flag TINYINT(1))";
+        const INSERT: &str =
+            "INSERT INTO mysql.tmp_tinyint1 VALUES (1, 0), (2, 1), (3, 5), (4, -3)";
+        const SELECT: &str = "SELECT flag FROM mysql.tmp_tinyint1 ORDER BY id";
+
+        let mut conn = Conn::new(get_opts()).await?;
+        conn.query_drop(CREATE).await?;
+        conn.query_drop(INSERT).await?;
+
+        // By default values are returned as-is.
+        let text: Vec<i8> = conn.query(SELECT).await?;
+        let binary: Vec<i8> = conn.exec(SELECT, ()).await?;
+        assert_eq!(text, vec![0, 1, 5, -3]);
+        assert_eq!(binary, vec![0, 1, 5, -3]);
+
+        let values: Vec<Value> = conn.exec(SELECT, ()).await?;
+        assert!(!from_value_opt::<bool>(values[0].clone()).unwrap());
+        assert!(from_value_opt::<bool>(values[1].clone()).unwrap());
+        assert!(from_value_opt::<bool>(values[2].clone()).is_err());
+        conn.disconnect().await?;
+
+        // Legacy heuristic.
+        let mut conn = Conn::new(get_opts().tinyint1_is_bool(true)).await?;
+        conn.query_drop(CREATE).await?;
+        conn.query_drop(INSERT).await?;
+
+        let text: Vec<bool> = conn.query(SELECT).await?;
+        let binary: Vec<bool> = conn.exec(SELECT, ()).await?;
+        assert_eq!(text, vec![false, true, true, true]);
+        assert_eq!(binary, vec![false, true, true, true]);
+
+        let ints: Vec<i8> = conn.exec(SELECT, ()).await?;
+        assert_eq!(ints, vec![0, 1, 1, 1]);
+
+        // Other integer columns are not affected.
+        let (a, b): (i8, i64) = conn
+            .query_first("SELECT CAST(7 AS SIGNED), 7")
+            .await?
+            .unwrap();
+        assert_eq!((a, b), (7, 7));
+
+        conn.disconnect().await?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn should_read_row_as_map() -> crate::Result<()> {
+        let mut conn = Conn::new(get_opts()).await?;
+        conn.query_drop("CREATE TEMPORARY TABLE mysql.tmp_map_a (id INT, name TEXT)")
+            .await?;
+        conn.query_drop("CREATE TEMPORARY TABLE mysql.tmp_map_b (id INT, a_id INT, name TEXT)")
+            .await?;
+        conn.query_drop("INSERT INTO mysql.tmp_map_a VALUES (1, 'foo')")
+            .await?;
+        conn.query_drop("INSERT INTO mysql.tmp_map_b VALUES (10, 1, NULL)")
+            .await?;
+
+        const SELECT: &str = "SELECT a.*, b.*, 42 AS id_2 FROM mysql.tmp_map_a a \
+            JOIN mysql.tmp_map_b b ON b.a_id = a.id";
+        let text: Row = conn.query_first(SELECT).await?.unwrap();
+        let binary: Row = conn.exec_first(SELECT, ()).await?.unwrap();
+
+        for row in [text, binary] {
+            let map = row.as_map();
+            assert_eq!(map.len(), 6);
+            assert_eq!(from_value_opt::<i64>(map["id"].clone()).unwrap(), 1);
+            assert_eq!(
+                from_value_opt::<String>(map["name"].clone()).unwrap(),
+                "foo"
+            );
+            assert_eq!(from_value_opt::<i64>(map["id_3"].clone()).unwrap(), 10);
+            assert_eq!(from_value_opt::<i64>(map["a_id"].clone()).unwrap(), 1);
+            assert_eq!(map["name_2"], Value::NULL);
+            assert_eq!(from_value_opt::<i64>(map["id_2"].clone()).unwrap(), 42);
+        }
+
+        // a generated name never shadows an actual one, wherever it is
+        for query in [
+            "SELECT 1 AS id, 2 AS id, 3 AS id_2",
+            "SELECT 1 AS id, 3 AS id_2, 2 AS id",
+        ] {
+            let row: Row = conn.query_first(query).await?.unwrap();
+            let map = row.as_map();
+            assert_eq!(map.len(), 3);
+            assert_eq!(from_value_opt::<i64>(map["id"].clone()).unwrap(), 1);
+            assert_eq!(from_value_opt::<i64>(map["id_2"].clone()).unwrap(), 3);
+            assert_eq!(from_value_opt::<i64>(map["id_3"].clone()).unwrap(), 2);
+        }
+
+        let mut row: Row = conn.query_first("SELECT 1 AS a, 2 AS b").await?.unwrap();
+        let _: Option<i64> = row.take("a");
+        assert_eq!(row.as_map().into_keys().collect::<Vec<_>>(), ["b"]);
+
+        conn.disconnect().await?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn should_read_integers_with_signedness_override() -> crate::Result<()> {
+        const SELECT: &str = "SELECT CAST(18446744073709551615 AS SIGNED) AS s, \
+            CAST(-1 AS UNSIGNED) AS u, 42 AS n, 'foo' AS t";
+
+        let mut conn = Conn::new(get_opts()).await?;
+        let text: Row = conn.query_first(SELECT).await?.unwrap();
+        let binary: Row = conn.exec_first(SELECT, ()).await?.unwrap();
+
+        for row in [text, binary] {
+            assert_eq!(row.get_non_null::<i64, _>("s").unwrap()?, -1);
+            assert_eq!(row.get_unsigned::<u64, _>("s").unwrap()?, u64::MAX);
+            assert_eq!(row.get_non_null::<u64, _>("u").unwrap()?, u64::MAX);
+            assert_eq!(row.get_signed::<i64, _>("u").unwrap()?, -1);
+
+            // values within range are read as is
+            assert_eq!(row.get_unsigned::<u64, _>("n").unwrap()?, 42);
+            assert_eq!(row.get_signed::<i8, _>("n").unwrap()?, 42);
+            assert_eq!(row.get_unsigned::<String, _>("t").unwrap()?, "foo");
+            assert!(row.get_signed::<i64, _>("missing").is_none());
+        }
+
+        conn.disconnect().await?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn should_report_unexpected_null() -> crate::Result<()> {
+        fn null_column<T>(result: Option<crate::Result<T>>) -> String {
+            match result {
+                Some(Err(Error::Driver(DriverError::UnexpectedNull { column }))) => column,
+                Some(Err(err)) => panic!("unexpected error: {}", err),
+                Some(Ok(_)) => panic!("NULL converted into a non-optional type"),
+                None => panic!("no such column"),
+            }
+        }
+
+        let mut conn = Conn::new(get_opts()).await?;
+        for row in [
+            conn.query_first::<Row, _>("SELECT NULL AS id, NULL AS name, 'foo' AS title")
+                .await?
+                .unwrap(),
+            conn.exec_first::<Row, _, _>("SELECT NULL AS id, NULL AS name, 'foo' AS title", ())
+                .await?
+                .unwrap(),
+        ] {
+            assert_eq!(null_column(row.get_non_null::<i64, _>("id")), "id");
+            assert_eq!(null_column(row.get_non_null::<String, _>(1)), "name");
+            assert_eq!(row.get_non_null::<Option<i64>, _>("id").unwrap()?, None);
+            assert_eq!(row.get_non_null::<String, _>("title").unwrap()?, "foo");
+            assert!(matches!(
+                row.get_non_null::<i64, _>("title"),
+                Some(Err(Error::Driver(DriverError::FromValue { .. })))
+            ));
+            assert!(row.get_non_null::<i64, _>("missing").is_none());
+        }
+
+        conn.disconnect().await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,234 @@
+// Copyright (c) 2025 mysql_async contributors
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+use mysql_common::value::{
+    convert::{FromValue, FromValueError},
+    Value,
+};
+
+use std::convert::TryFrom;
+
+/// Mapping between members of a `SET` column and flags of a Rust type (see [`Set`]).
+///
+/// It's meant to be implemented for flag types, such as ones generated by `bitflags`
+/// or `enumset`:
+///
+/// ```rust
+/// # use mysql_async::SetMembers;
+/// #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// struct Perms(u8);
+///
+/// impl SetMembers for Perms {
+///     const MEMBERS: &'static [(&'static str, Self)] =
+///         &[("read", Perms(1)), ("write", Perms(2)), ("exec", Perms(4))];
+///
+///     fn empty() -> Self {
+///         Perms(0)
+///     }
+///
+///     fn union(self, other: Self) -> Self {
+///         Perms(self.0 | other.0)
+///     }
+///
+///     fn contains(&self, other: Self) -> bool {
+///         self.0 & other.0 == other.0
+///     }
+/// }
+/// ```
+pub trait SetMembers: Sized + Copy {
+    /// Names of the `SET` members with corresponding flags.
+    ///
+    /// Names are matched case-insensitively (as MySql does).
+    const MEMBERS: &'static [(&'static str, Self)];
+
+    /// Returns a value without any flags set.
+    fn empty() -> Self;
+
+    /// Returns a value with flags of both `self` and `other` set.
+    fn union(self, other: Self) -> Self;
+
+    /// Returns `true` if all flags of `other` are set in `self`.
+    fn contains(&self, other: Self) -> bool;
+}
+
+/// Value of a `SET` column mapped to flags of `T` (see [`SetMembers`]).
+///
+/// Converts from the comma-separated representation of a `SET` value returned
+/// by the server (a member that isn't listed in [`SetMembers::MEMBERS`] is a conversion error),
+/// and converts into the same representation, so it could be used as a query parameter:
+///
+/// ```rust
+/// # use mysql_async::{test_misc::get_opts, Set, SetMembers};
+/// # use mysql_async::prelude::*;
+/// # #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// # struct Perms(u8);
+/// # impl SetMembers for Perms {
+/// #     const MEMBERS: &'static [(&'static str, Self)] =
+/// #         &[("read", Perms(1)), ("write", Perms(2)), ("exec", Perms(4))];
+/// #     fn empty() -> Self { Perms(0) }
+/// #     fn union(self, other: Self) -> Self { Perms(self.0 | other.0) }
+/// #     fn contains(&self, other: Self) -> bool { self.0 & other.0 == other.0 }
+/// # }
+/// # #[tokio::main]
+/// # async fn main() -> mysql_async::Result<()> {
+/// # let mut conn = mysql_async::Conn::new(get_opts()).await?;
+/// let perms: Set<Perms> = conn.query_first("SELECT 'write,read'").await?.unwrap();
+/// assert_eq!(perms.0, Perms(3));
+///
+/// let param: String = conn.exec_first("SELECT ?", (Set(Perms(5)),)).await?.unwrap();
+/// assert_eq!(param, "read,exec");
+/// # conn.disconnect().await }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Set<T>(pub T);
+
+impl<T: SetMembers> Set<T> {
+    /// Parses the comma-separated representation of a `SET` value.
+    ///
+    /// Returns `None` if there is an unknown member.
+    pub fn parse(value: &[u8]) -> Option<Self> {
+        if value.is_empty() {
+            return Some(Set(T::empty()));
+        }
+
+        value
+            .split(|x| *x == b',')
+            .try_fold(T::empty(), |acc, name| {
+                let (_, flag) = T::MEMBERS
+                    .iter()
+                    .find(|(member, _)| member.as_bytes().eq_ignore_ascii_case(name))?;
+                Some(acc.union(*flag))
+            })
+            .map(Set)
+    }
+
+    /// Returns the comma-separated representation of this value
+    /// (members are listed in the order of [`SetMembers::MEMBERS`]).
+    pub fn to_set_string(&self) -> String {
+        T::MEMBERS
+            .iter()
+            .filter(|(_, flag)| self.0.contains(*flag))
+            .map(|(name, _)| *name)
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+
+    /// Unwraps the inner flags.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T: SetMembers> From<Set<T>> for Value {
+    fn from(set: Set<T>) -> Self {
+        Value::Bytes(set.to_set_string().into_bytes())
+    }
+}
+
+impl<T: SetMembers> TryFrom<Value> for Set<T> {
+    type Error = FromValueError;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::Bytes(ref bytes) => Set::parse(bytes).ok_or(FromValueError(value)),
+            value => Err(FromValueError(value)),
+        }
+    }
+}
+
+impl<T: SetMembers> FromValue for Set<T> {
+    type Intermediate = Set<T>;
+}
+
+#[cfg(test)]
+mod test {
+    use bitflags::bitflags;
+
+    use super::{Set, SetMembers};
+    use crate::{prelude::*, test_misc::get_opts, Conn, Value};
+
+    bitflags! {
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        struct Perms: u8 {
+            const READ = 1;
+            const WRITE = 1 << 1;
+            const EXEC = 1 << 2;
+        }
+    }
+
+    impl SetMembers for Perms {
+        const MEMBERS: &'static [(&'static str, Self)] = &[
+            ("read", Perms::READ),
+            ("write", Perms::WRITE),
+            ("exec", Perms::EXEC),
+        ];
+
+        fn empty() -> Self {
+            Perms::empty()
+        }
+
+        fn union(self, other: Self) -> Self {
+            Perms::union(self, other)
+        }
+
+        fn contains(&self, other: Self) -> bool {
+            Perms::contains(self, other)
+        }
+    }
+
+    #[test]
+    fn should_convert_set_to_and_from_value() {
+        let perms = Set(Perms::READ | Perms::EXEC);
+        assert_eq!(Value::from(perms), Value::Bytes(b"read,exec".to_vec()));
+        assert_eq!(Value::from(Set(Perms::empty())), Value::Bytes(Vec::new()));
+
+        assert_eq!(Set::parse(b"exec,READ"), Some(perms));
+        assert_eq!(Set::parse(b""), Some(Set(Perms::empty())));
+        assert_eq!(Set::<Perms>::parse(b"read,delete"), None);
+        assert_eq!(crate::from_value::<Set<Perms>>(perms.into()), perms);
+        assert!(crate::from_value_opt::<Set<Perms>>(Value::Int(1)).is_err());
+    }
+
+    #[tokio::test]
+    async fn should_roundtrip_set_column() -> crate::Result<()> {
+        let mut conn = Conn::new(get_opts()).await?;
+        conn.query_drop(
+            "CREATE TEMPORARY TABLE mysql.tmp_set (id INT, perms SET('read', 'write', 'exec'))",
+        )
+        .await?;
+
+        let values = [
+            Perms::empty(),
+            Perms::WRITE,
+            Perms::READ | Perms::EXEC,
+            Perms::all(),
+        ];
+        conn.exec_batch(
+            "INSERT INTO mysql.tmp_set (id, perms) VALUES (?, ?)",
+            values
+                .iter()
+                .enumerate()
+                .map(|(id, perms)| (id, Set(*perms))),
+        )
+        .await?;
+
+        const SELECT: &str = "SELECT perms FROM mysql.tmp_set ORDER BY id";
+        let text: Vec<Set<Perms>> = conn.query(SELECT).await?;
+        let binary: Vec<Set<Perms>> = conn.exec(SELECT, ()).await?;
+        for fetched in [text, binary] {
+            let fetched = fetched.into_iter().map(Set::into_inner).collect::<Vec<_>>();
+            assert_eq!(fetched, values);
+        }
+
+        let stored: Vec<String> = conn.query(SELECT).await?;
+        assert_eq!(stored, ["", "write", "read,exec", "read,write,exec"]);
+
+        conn.disconnect().await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,191 @@
+// Copyright (c) 2025 mysql_async contributors
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+use mysql_common::{
+    constants::ColumnType,
+    packets::Column,
+    value::{
+        convert::{FromValue, FromValueError},
+        Value,
+    },
+};
+
+use std::{convert::TryFrom, mem::size_of};
+
+/// Value of a `VECTOR` column (MySQL 9.0+, MariaDB 11.7+).
+///
+/// Server represents a vector as a sequence of IEEE 754 single-precision floats
+/// in little-endian byte order. This type converts to and from that representation,
+/// so it could be used both as a query parameter and as a result of a `FromValue` conversion:
+///
+/// ```rust
+/// # use mysql_async::{test_misc::get_opts, Vector};
+/// # use mysql_async::prelude::*;
+/// # #[tokio::main]
+/// # async fn main() -> mysql_async::Result<()> {
+/// # let mut conn = mysql_async::Conn::new(get_opts()).await?;
+/// let embedding = Vector::from(vec![0.5_f32, -1.0, 2.25]);
+/// let fetched: Option<Vector> = conn.exec_first("SELECT ?", (embedding.clone(),)).await?;
+/// assert_eq!(fetched, Some(embedding));
+/// # conn.disconnect().await }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Vector(pub Vec<f32>);
+
+impl Vector {
+    /// Returns `true` if the given column is a `VECTOR` column.
+    pub fn is_vector_column(column: &Column) -> bool {
+        column.column_type() == ColumnType::MYSQL_TYPE_VECTOR
+    }
+
+    /// Decodes a vector from its binary representation.
+    ///
+    /// Returns `None` if the length of `bytes` is not a multiple of the size of `f32`.
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() % size_of::<f32>() != 0 {
+            return None;
+        }
+
+        let elements = bytes
+            .chunks_exact(size_of::<f32>())
+            .map(|chunk| f32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+            .collect();
+
+        Some(Self(elements))
+    }
+
+    /// Encodes this vector into its binary representation.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(self.0.len() * size_of::<f32>());
+        for element in &self.0 {
+            bytes.extend_from_slice(&element.to_le_bytes());
+        }
+        bytes
+    }
+
+    /// Returns the number of dimensions of this vector.
+    pub fn dimensions(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Unwraps the inner `Vec<f32>`.
+    pub fn into_inner(self) -> Vec<f32> {
+        self.0
+    }
+}
+
+impl From<Vec<f32>> for Vector {
+    fn from(elements: Vec<f32>) -> Self {
+        Self(elements)
+    }
+}
+
+impl From<&[f32]> for Vector {
+    fn from(elements: &[f32]) -> Self {
+        Self(elements.to_vec())
+    }
+}
+
+impl From<Vector> for Vec<f32> {
+    fn from(vector: Vector) -> Self {
+        vector.0
+    }
+}
+
+impl From<Vector> for Value {
+    fn from(vector: Vector) -> Self {
+        Value::Bytes(vector.to_bytes())
+    }
+}
+
+impl TryFrom<Value> for Vector {
+    type Error = FromValueError;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::Bytes(ref bytes) => Vector::from_bytes(bytes).ok_or(FromValueError(value)),
+            value => Err(FromValueError(value)),
+        }
+    }
+}
+
+impl FromValue for Vector {
+    type Intermediate = Vector;
+}
+
+#[cfg(test)]
+mod test {
+    use super::Vector;
+    use crate::{prelude::*, test_misc::get_opts, Conn, Row, Value};
+
+    #[test]
+    fn should_convert_vector_to_and_from_bytes() {
+        let vector = Vector(vec![0.0, 1.0, -1.5, f32::MAX, f32::MIN_POSITIVE]);
+        let bytes = vector.to_bytes();
+        assert_eq!(bytes.len(), 20);
+        assert_eq!(&bytes[4..8], &[0x00, 0x00, 0x80, 0x3f]);
+        assert_eq!(Vector::from_bytes(&bytes), Some(vector.clone()));
+        assert_eq!(Vector::from_bytes(&bytes[..19]), None);
+
+        assert_eq!(Value::from(vector.clone()), Value::Bytes(bytes));
+        assert_eq!(crate::from_value::<Vector>(vector.clone().into()), vector);
+        assert!(crate::from_value_opt::<Vector>(Value::Int(1)).is_err());
+    }
+
+    #[tokio::test]
+    async fn should_roundtrip_vector_column() -> crate::Result<()> {
+        const DIMENSIONS: usize = 384;
+
+        let mut conn = Conn::new(get_opts()).await?;
+        let version = conn.server_version();
+        let supported = if conn.inner.is_mariadb {
+            version >= (11, 7, 0)
+        } else {
+            version >= (9, 0, 0)
+        };
+        if !supported {
+            return conn.disconnect().await;
+        }
+
+        conn.query_drop(format!(
+            "CREATE TEMPORARY TABLE mysql.tmp_vector (id INT PRIMARY KEY, v VECTOR({}) NOT NULL)",
+            DIMENSIONS
+        ))
+        .await?;
+
+        let embedding = Vector(
+            (0..DIMENSIONS)
+                .map(|i| (i as f32 * 0.37).sin() * 100.0)
+                .collect(),
+        );
+        conn.exec_drop(
+            "INSERT INTO mysql.tmp_vector (id, v) VALUES (?, ?)",
+            (1, embedding.clone()),
+        )
+        .await?;
+
+        let mut result = conn.query_iter("SELECT v FROM mysql.tmp_vector").await?;
+        assert!(Vector::is_vector_column(&result.columns_ref()[0]));
+        let rows = result.collect_and_drop::<Row>().await?;
+        let fetched: Vector = rows[0].get(0).unwrap();
+
+        let fetched_bin: Option<Vector> = conn
+            .exec_first("SELECT v FROM mysql.tmp_vector WHERE id = ?", (1,))
+            .await?;
+
+        for fetched in [fetched, fetched_bin.unwrap()] {
+            assert_eq!(fetched.dimensions(), DIMENSIONS);
+            for (expected, actual) in embedding.0.iter().zip(fetched.0.iter()) {
+                assert!((expected - actual).abs() <= f32::EPSILON * expected.abs().max(1.0));
+            }
+        }
+
+        conn.disconnect().await?;
+        Ok(())
+    }
+}
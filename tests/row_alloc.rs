@@ -0,0 +1,79 @@
+// Copyright (c) 2025 mysql_async contributors
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Checks that borrowing accessors of `RowExt` don't allocate.
+//!
+//! It's a separate test binary, because it replaces the global allocator.
+
+use mysql_async::{prelude::*, test_misc::get_opts, Conn, Result, Row};
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::Cell,
+};
+
+/// Counts allocations made by the current thread.
+struct CountingAlloc;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let _ = ALLOCATIONS.try_with(|x| x.set(x.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+fn count_allocations<F: FnOnce() -> usize>(f: F) -> (usize, usize) {
+    let before = ALLOCATIONS.with(Cell::get);
+    let result = f();
+    (ALLOCATIONS.with(Cell::get) - before, result)
+}
+
+#[tokio::test]
+async fn should_read_columns_without_allocation() -> Result<()> {
+    const ROWS: usize = 1000;
+
+    let mut conn = Conn::new(get_opts()).await?;
+    let rows: Vec<Row> = conn
+        .query(format!(
+            "SELECT CONCAT('value_', seq) AS val FROM (SELECT 1 AS seq{}) t",
+            (2..=ROWS)
+                .map(|i| format!(" UNION ALL SELECT {}", i))
+                .collect::<String>()
+        ))
+        .await?;
+    assert_eq!(rows.len(), ROWS);
+
+    let (owning, owning_len) = count_allocations(|| {
+        rows.iter()
+            .map(|row| row.get::<String, _>("val").unwrap().len())
+            .sum()
+    });
+    let (borrowing, borrowing_len) = count_allocations(|| {
+        rows.iter()
+            .map(|row| row.get_str("val").unwrap().len())
+            .sum()
+    });
+
+    assert_eq!(owning_len, borrowing_len);
+    assert!(owning >= ROWS, "{} allocations", owning);
+    assert_eq!(borrowing, 0);
+
+    conn.disconnect().await?;
+    Ok(())
+}